@@ -13,6 +13,7 @@ use baselard::{
         json_combiner::JsonCombiner, json_to_data_processor::JsonToDataProcessor,
         ml_model::MLModel, string_length_counter::StringLengthCounter, replay::Replay,
     },
+    history::{JsonlHistory, RetentionPolicy, RotationPolicy},
 };
 
 use baselard::{
@@ -104,6 +105,12 @@ struct AppState {
 #[derive(Deserialize)]
 struct AliasExecuteRequest {
     overrides: Option<DAGConfig>,
+    /// Inputs for this request, in place of those configured for the nodes
+    #[serde(default)]
+    inputs: HashMap<String, Data>,
+    /// Return every node's output rather than the DAG's declared outputs
+    #[serde(default)]
+    debug: bool,
 }
 
 /// Convenience endpoint to view the DAG in a tree format, incidentally validates
@@ -259,6 +266,7 @@ async fn execute_by_alias(
             if !use_cache {
                 dag_config.enable_memory_cache = false;
             }
+            dag_config.debug_all_nodes = request.debug;
 
             match DAG::from_ir(
                 &ir,
@@ -266,7 +274,7 @@ async fn execute_by_alias(
                 dag_config,
                 Some(Arc::clone(&state.cache)),
             ) {
-                Ok(dag) => dag.execute(None).await,
+                Ok(dag) => dag.execute_with_inputs(None, request.inputs).await,
                 Err(e) => Err(DAGError::InvalidConfiguration(e.to_string())),
             }
         }
@@ -323,10 +331,19 @@ async fn main() {
     registry.register::<DataToJsonProcessor>("DataToJsonProcessor");
     registry.register::<JsonToDataProcessor>("JsonToDataProcessor");
     registry.register::<JsonCombiner>("JsonCombiner");
-    registry.register::<MLModel>("MLModel");
+    registry.register_async::<MLModel>("MLModel");
     registry.register::<Replay>("Replay");
 
-    let cache = Cache::new(Some("/tmp/axum_dag_history.jsonl"), 10_000);
+    let history = JsonlHistory::new("/tmp/axum_dag_history.jsonl")
+        .with_rotation(RotationPolicy {
+            max_segment_bytes: Some(64 * 1024 * 1024),
+            max_segment_age_ms: Some(24 * 60 * 60 * 1000),
+        })
+        .with_retention(RetentionPolicy {
+            max_age_ms: Some(7 * 24 * 60 * 60 * 1000),
+            max_entries: None,
+        });
+    let cache = Cache::with_history_store(history, 10_000);
 
     let state = Arc::new(AppState {
         registry: Arc::new(registry),
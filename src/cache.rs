@@ -1,16 +1,21 @@
 use indexmap::IndexMap;
-use moka::sync::Cache as MokaCache;
+use moka::sync::{Cache as MokaCache, CacheBuilder};
+use moka::Expiry;
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::fs::OpenOptions;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+use std::time::{Duration, Instant};
 
 use crate::component::Data;
 use crate::dag::{NodeID, RequestId};
+use crate::history::{HistoryError, HistoryStore, JsonlHistory};
+
+/// Called with the error whenever a result couldn't be written to the history
+pub type HistoryErrorHandler = Arc<dyn Fn(&HistoryError) + Send + Sync>;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DAGResult {
@@ -25,27 +30,257 @@ struct DAGInputHash {
     inputs_hash: u64,
 }
 
-/// We cache results in two ways:
+/// Which DAG a result is cached for
+#[derive(Debug, Clone)]
+pub(crate) struct ResultKey {
+    pub(crate) alias: String,
+    pub(crate) ir_hash: u64,
+    /// `ir_hash`, or a hash of it and the outputs returned if they aren't
+    /// the DAG's own
+    pub(crate) cache_hash: u64,
+}
+
+/// A result cached by DAG inputs and configuration, and what produced it,
+/// to invalidate it by
+#[derive(Debug, Clone)]
+struct CachedResult {
+    result: DAGResult,
+    alias: Option<Arc<str>>,
+    ir_hash: u64,
+}
+
+/// How the in-memory caches of a `Cache` evict their entries
+#[derive(Debug, Clone, Copy)]
+pub struct CacheSettings {
+    /// Most entries each cache holds, or most bytes if `weigh_by_size`
+    pub max_capacity: u64,
+    /// Evict entries this long after they were stored
+    pub time_to_live_ms: Option<u64>,
+    /// Evict entries this long after they were last read or stored
+    pub time_to_idle_ms: Option<u64>,
+    /// Weigh entries by their size serialized to JSON, so that
+    /// `max_capacity` bounds bytes rather than entries
+    pub weigh_by_size: bool,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self::with_capacity(10_000)
+    }
+}
+
+impl CacheSettings {
+    /// At most `max_capacity` entries per cache, kept until evicted
+    #[must_use]
+    pub fn with_capacity(max_capacity: u64) -> Self {
+        Self {
+            max_capacity,
+            time_to_live_ms: None,
+            time_to_idle_ms: None,
+            weigh_by_size: false,
+        }
+    }
+
+    fn builder<K, V>(&self, weigh: fn(&K, &V) -> u32) -> CacheBuilder<K, V, MokaCache<K, V>>
+    where
+        K: Hash + Eq + Send + Sync + 'static,
+        V: Clone + Send + Sync + 'static,
+    {
+        let mut builder = MokaCache::builder().max_capacity(self.max_capacity);
+        if let Some(ttl) = self.time_to_live_ms {
+            builder = builder.time_to_live(Duration::from_millis(ttl));
+        }
+        if let Some(tti) = self.time_to_idle_ms {
+            builder = builder.time_to_idle(Duration::from_millis(tti));
+        }
+        if self.weigh_by_size {
+            builder = builder.weigher(weigh);
+        }
+        builder
+    }
+}
+
+/// Counts the bytes written to it
+struct ByteCounter(usize);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The size of `value` serialized to JSON, without buffering it
+fn serialized_size(value: &impl Serialize) -> u32 {
+    let mut counter = ByteCounter(0);
+    match serde_json::to_writer(&mut counter, value) {
+        Ok(()) => u32::try_from(counter.0).unwrap_or(u32::MAX),
+        Err(_) => u32::MAX,
+    }
+}
+
+/// Identifies the output of a node by what determines it: the component,
+/// its configuration and the input it executed with. Nodes of different
+/// DAGs share outputs this way.
+///
+/// The input is kept along with its hash and compared on lookup, so that
+/// inputs whose hashes collide never share an output.
+#[derive(Debug, Clone)]
+pub struct NodeCacheKey {
+    component_type: String,
+    config_hash: u64,
+    input_hash: u64,
+    input: Data,
+}
+
+impl NodeCacheKey {
+    pub(crate) fn new(component_type: &str, config_hash: u64, input: &Data) -> Self {
+        let mut hasher = DefaultHasher::new();
+        input.hash(&mut hasher);
+        Self {
+            component_type: component_type.to_string(),
+            config_hash,
+            input_hash: hasher.finish(),
+            input: input.clone(),
+        }
+    }
+}
+
+impl PartialEq for NodeCacheKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.input_hash == other.input_hash
+            && self.config_hash == other.config_hash
+            && self.component_type == other.component_type
+            && self.input == other.input
+    }
+}
+
+// An input that isn't equal to itself, as with a NaN float, is never found
+impl Eq for NodeCacheKey {}
+
+impl Hash for NodeCacheKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.component_type.hash(state);
+        self.config_hash.hash(state);
+        self.input_hash.hash(state);
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NodeResult {
+    output: Data,
+    ttl: Option<Duration>,
+}
+
+/// Expires each memoized node output after its node's TTL, if any
+struct NodeResultExpiry;
+
+impl Expiry<NodeCacheKey, NodeResult> for NodeResultExpiry {
+    fn expire_after_create(
+        &self,
+        _key: &NodeCacheKey,
+        value: &NodeResult,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        value.ttl
+    }
+
+    fn expire_after_update(
+        &self,
+        _key: &NodeCacheKey,
+        value: &NodeResult,
+        _updated_at: Instant,
+        _duration_until_expiry: Option<Duration>,
+    ) -> Option<Duration> {
+        value.ttl
+    }
+}
+
+/// We cache results in three ways:
 /// - By DAG inputs and configuration, for memoized behavior
-/// - By request ID (in memory and backed on a file) for specific replay
+/// - By node component, configuration and input, for memoized nodes
+/// - By request ID (in memory and backed by a `HistoryStore`) for specific replay
 pub struct Cache {
     /// Cache keyed by DAG inputs configuration
-    request_cache: Arc<MokaCache<DAGInputHash, DAGResult>>,
+    request_cache: Arc<MokaCache<DAGInputHash, CachedResult>>,
+    /// Outputs of nodes marked `cacheable`
+    node_results: Arc<MokaCache<NodeCacheKey, NodeResult>>,
     /// Cache keyed by request ID for testing/lookup
     history_cache: Arc<MokaCache<RequestId, DAGResult>>,
-    /// History file path
-    history_file: Option<PathBuf>,
+    /// Where results are kept for replay beyond the in-memory cache
+    history: Option<Arc<dyn HistoryStore>>,
+    /// How many results couldn't be written to the history
+    history_write_failures: Arc<AtomicU64>,
+    history_error_handler: Option<HistoryErrorHandler>,
 }
 
 impl Cache {
+    /// A cache keeping its history, if any, in a JSONL file
     pub fn new(history_file: Option<impl Into<PathBuf>>, max_capacity: u64) -> Self {
+        let history = history_file
+            .map(|path| Arc::new(JsonlHistory::new(path)) as Arc<dyn HistoryStore>);
+        Self::with_history(history, CacheSettings::with_capacity(max_capacity))
+    }
+
+    /// A cache keeping its history in `store`
+    pub fn with_history_store(store: impl HistoryStore + 'static, max_capacity: u64) -> Self {
+        Self::with_history(Some(Arc::new(store)), CacheSettings::with_capacity(max_capacity))
+    }
+
+    fn with_history(history: Option<Arc<dyn HistoryStore>>, settings: CacheSettings) -> Self {
+        Self {
+            request_cache: Arc::new(
+                settings
+                    .builder(|_, cached: &CachedResult| serialized_size(&cached.result))
+                    .support_invalidation_closures()
+                    .build(),
+            ),
+            node_results: Arc::new(
+                settings
+                    .builder(|key: &NodeCacheKey, result: &NodeResult| {
+                        serialized_size(&key.input).saturating_add(serialized_size(&result.output))
+                    })
+                    .expire_after(NodeResultExpiry)
+                    .build(),
+            ),
+            history_cache: Arc::new(settings.builder(|_, result| serialized_size(result)).build()),
+            history,
+            history_write_failures: Arc::new(AtomicU64::new(0)),
+            history_error_handler: None,
+        }
+    }
+
+    /// Evicts entries following `settings` rather than by count alone. The
+    /// in-memory caches start over, so set it up front.
+    #[must_use]
+    pub fn with_settings(self, settings: CacheSettings) -> Self {
         Self {
-            request_cache: Arc::new(MokaCache::new(max_capacity)),
-            history_cache: Arc::new(MokaCache::new(max_capacity)),
-            history_file: history_file.map(Into::into),
+            history_error_handler: self.history_error_handler,
+            history_write_failures: self.history_write_failures,
+            ..Self::with_history(self.history, settings)
         }
     }
 
+    /// Calls `handler` whenever a result couldn't be written to the history
+    #[must_use]
+    pub fn with_history_error_handler(
+        mut self,
+        handler: impl Fn(&HistoryError) + Send + Sync + 'static,
+    ) -> Self {
+        self.history_error_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// How many results couldn't be written to the history so far
+    #[must_use]
+    pub fn history_write_failures(&self) -> u64 {
+        self.history_write_failures.load(Ordering::Relaxed)
+    }
+
     fn create_cache_key(ir_hash: u64, inputs: &HashMap<String, Data>) -> DAGInputHash {
         DAGInputHash {
             ir_hash,
@@ -53,9 +288,14 @@ impl Cache {
         }
     }
 
+    /// Equal inputs hash the same regardless of the maps' iteration order,
+    /// as runtime inputs come in a new map with every request
     fn calculate_inputs_hash(map: &HashMap<String, Data>) -> u64 {
+        let mut entries: Vec<_> = map.iter().collect();
+        entries.sort_by_key(|(key, _)| *key);
+
         let mut hasher = DefaultHasher::new();
-        for (key, value) in map {
+        for (key, value) in entries {
             key.hash(&mut hasher);
             value.hash(&mut hasher);
         }
@@ -68,6 +308,37 @@ impl Cache {
         inputs: &HashMap<NodeID, Data>,
         results: &IndexMap<NodeID, Data>,
         request_id: &RequestId,
+    ) {
+        self.store(None, ir_hash, ir_hash, inputs, results, request_id);
+    }
+
+    /// Stores the result of an execution of the DAG `key` is for, so that
+    /// it can be invalidated by the DAG's alias or IR hash
+    pub(crate) fn store_dag_result(
+        &self,
+        key: &ResultKey,
+        inputs: &HashMap<NodeID, Data>,
+        results: &IndexMap<NodeID, Data>,
+        request_id: &RequestId,
+    ) {
+        self.store(
+            Some(&key.alias),
+            key.ir_hash,
+            key.cache_hash,
+            inputs,
+            results,
+            request_id,
+        );
+    }
+
+    fn store(
+        &self,
+        alias: Option<&str>,
+        ir_hash: u64,
+        cache_hash: u64,
+        inputs: &HashMap<NodeID, Data>,
+        results: &IndexMap<NodeID, Data>,
+        request_id: &RequestId,
     ) {
         let timestamp = chrono::Utc::now();
 
@@ -77,22 +348,29 @@ impl Cache {
             node_results: results.clone(),
         };
 
-        let cache_key = Self::create_cache_key(ir_hash, inputs);
-        self.request_cache.insert(cache_key, dag_result.clone());
+        let cache_key = Self::create_cache_key(cache_hash, inputs);
+        let cached = CachedResult {
+            result: dag_result.clone(),
+            alias: alias.map(Arc::from),
+            ir_hash,
+        };
+        self.request_cache.insert(cache_key, cached);
 
-        if let Some(file_path) = &self.history_file {
+        if let Some(history) = &self.history {
             self.history_cache.insert(request_id.to_string(), dag_result.clone());
 
-            let file_path = file_path.clone();
-            tokio::spawn(async move {
-                if let Ok(mut file) = OpenOptions::new()
-                    .create(true)
-                    .append(true)
-                    .open(file_path)
-                    .await
-                {
-                    if let Ok(json) = serde_json::to_string(&dag_result) {
-                        let _ = file.write_all(format!("{json}\n").as_bytes()).await;
+            let history = Arc::clone(history);
+            let failures = Arc::clone(&self.history_write_failures);
+            let handler = self.history_error_handler.clone();
+            tokio::task::spawn_blocking(move || {
+                if let Err(e) = history.append(&dag_result) {
+                    failures.fetch_add(1, Ordering::Relaxed);
+                    match handler {
+                        Some(handler) => handler(&e),
+                        None => println!(
+                            "Failed to record request {} in the history: {e}",
+                            dag_result.request_id
+                        ),
                     }
                 }
             });
@@ -106,7 +384,46 @@ impl Cache {
         inputs: &HashMap<String, Data>,
     ) -> Option<DAGResult> {
         let cache_key = Self::create_cache_key(ir_hash, inputs);
-        self.request_cache.get(&cache_key)
+        self.request_cache.get(&cache_key).map(|cached| cached.result)
+    }
+
+    /// Drops the cached results of the DAGs with this alias, whatever their
+    /// configuration. The history keeps them.
+    pub fn invalidate_alias(&self, alias: &str) {
+        let alias: Arc<str> = Arc::from(alias);
+        self.invalidate_results_if(move |cached| cached.alias.as_ref() == Some(&alias));
+    }
+
+    /// Drops the cached results of the DAGs with this IR hash, whatever
+    /// outputs they returned. The history keeps them.
+    pub fn invalidate_ir_hash(&self, ir_hash: u64) {
+        self.invalidate_results_if(move |cached| cached.ir_hash == ir_hash);
+    }
+
+    /// Drops the cached result of the request, by DAG inputs and
+    /// configuration and by request ID. The history keeps it, so it can
+    /// still be replayed.
+    pub fn invalidate_request_id(&self, request_id: &str) {
+        self.history_cache.invalidate(request_id);
+        let request_id = request_id.to_string();
+        self.invalidate_results_if(move |cached| cached.result.request_id == request_id);
+    }
+
+    /// Applies pending evictions now rather than as the caches are used,
+    /// mostly for tests
+    pub fn run_pending_tasks(&self) {
+        self.request_cache.run_pending_tasks();
+        self.node_results.run_pending_tasks();
+        self.history_cache.run_pending_tasks();
+    }
+
+    fn invalidate_results_if(&self, predicate: impl Fn(&CachedResult) -> bool + Send + Sync + 'static) {
+        if let Err(e) = self
+            .request_cache
+            .invalidate_entries_if(move |_, cached| predicate(cached))
+        {
+            println!("Failed to invalidate cached results: {e}");
+        }
     }
 
     #[must_use]
@@ -119,26 +436,26 @@ impl Cache {
             return Some(result);
         }
 
-        let history_file = self.history_file.as_ref()?;
-
-        let Ok(file) = tokio::fs::File::open(history_file).await else {
-            return None;
-        };
+        let history = Arc::clone(self.history.as_ref()?);
+        let id = request_id.to_string();
+        let found = tokio::task::spawn_blocking(move || history.get(&id)).await;
 
-        let reader = tokio::io::BufReader::new(file);
-        let mut lines = reader.lines();
-
-        while let Ok(Some(line)) = lines.next_line().await {
-            if let Ok(result) = serde_json::from_str::<DAGResult>(&line) {
-                if result.request_id == request_id {
-                    self.history_cache
-                        .insert(request_id.to_string(), result.clone());
-                    return Some(result);
-                }
+        match found {
+            Ok(Ok(Some(result))) => {
+                self.history_cache
+                    .insert(request_id.to_string(), result.clone());
+                Some(result)
+            }
+            Ok(Ok(None)) => None,
+            Ok(Err(e)) => {
+                println!("Failed to look up request {request_id} in the history: {e}");
+                None
+            }
+            Err(e) => {
+                println!("History lookup for request {request_id} panicked: {e}");
+                None
             }
         }
-
-        None
     }
 
     #[must_use]
@@ -151,4 +468,15 @@ impl Cache {
         self.get_cached_result(ir_hash, inputs)
             .and_then(|result| result.node_results.get(node_id).cloned())
     }
+
+    /// The memoized output of a node, unless it expired
+    #[must_use]
+    pub fn get_node_result(&self, key: &NodeCacheKey) -> Option<Data> {
+        self.node_results.get(key).map(|result| result.output)
+    }
+
+    /// Memoizes the output of a node, for `ttl` if given, or until evicted
+    pub fn store_node_result(&self, key: NodeCacheKey, output: Data, ttl: Option<Duration>) {
+        self.node_results.insert(key, NodeResult { output, ttl });
+    }
 }
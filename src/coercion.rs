@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::component::{Data, DataType};
+
+type Converter = Arc<dyn Fn(Data) -> Result<Data, String> + Send + Sync>;
+
+/// A conversion of values of one `DataType` into another, which a DAG may
+/// apply implicitly on an edge whose source output isn't compatible with
+/// its target's input
+#[derive(Clone)]
+pub struct Coercion {
+    name: String,
+    from: DataType,
+    to: DataType,
+    convert: Converter,
+}
+
+impl std::fmt::Debug for Coercion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Coercion")
+            .field("name", &self.name)
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Coercion {
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The type of the values it converts
+    #[must_use]
+    pub fn from(&self) -> &DataType {
+        &self.from
+    }
+
+    /// The type of the values it converts into
+    #[must_use]
+    pub fn to(&self) -> &DataType {
+        &self.to
+    }
+
+    /// Converts a value
+    ///
+    /// # Errors
+    /// Returns an error if this value can't be converted, e.g. JSON that
+    /// isn't a number converted into a `Float`.
+    pub fn apply(&self, data: Data) -> Result<Data, String> {
+        (self.convert)(data)
+    }
+}
+
+/// The coercions a DAG may apply on its edges, when built with
+/// `DAGSettings::implicit_coercions`
+#[derive(Debug, Clone, Default)]
+pub struct CoercionRegistry {
+    coercions: Vec<Coercion>,
+}
+
+impl CoercionRegistry {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with the built-in coercions:
+    /// - `integer_to_float`: `Integer` into `Float`
+    /// - `number_list_to_json`: a `List` of numbers into a `Json` array
+    /// - `json_number_to_float`: a `Json` number into `Float`
+    /// - `json_array_to_float_list`: a `Json` array of numbers into a `List` of `Float`
+    #[must_use]
+    pub fn with_defaults() -> Self {
+        let number = DataType::Union(vec![DataType::Integer, DataType::Int64, DataType::Float]);
+        let mut registry = Self::new();
+        registry.register("integer_to_float", DataType::Integer, DataType::Float, |data| {
+            data.as_integer()
+                .map(|i| Data::Float(f64::from(i)))
+                .ok_or_else(|| "Expected an integer".to_string())
+        });
+        registry.register(
+            "number_list_to_json",
+            DataType::List(Box::new(number)),
+            DataType::Json,
+            |data| Ok(Data::Json(data.to_json())),
+        );
+        registry.register("json_number_to_float", DataType::Json, DataType::Float, |data| {
+            json_number(&data.to_json()).map(Data::Float)
+        });
+        registry.register(
+            "json_array_to_float_list",
+            DataType::Json,
+            DataType::List(Box::new(DataType::Float)),
+            |data| match data.to_json() {
+                Value::Array(items) => items
+                    .iter()
+                    .map(|item| json_number(item).map(Data::Float))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(Data::List),
+                other => Err(format!("Expected a JSON array, got {other}")),
+            },
+        );
+        registry
+    }
+
+    /// Registers a coercion of values of type `from` into values of type
+    /// `to`. Coercions registered later take precedence.
+    pub fn register<F>(&mut self, name: &str, from: DataType, to: DataType, convert: F)
+    where
+        F: Fn(Data) -> Result<Data, String> + Send + Sync + 'static,
+    {
+        self.coercions.push(Coercion {
+            name: name.to_string(),
+            from,
+            to,
+            convert: Arc::new(convert),
+        });
+    }
+
+    /// The coercion that converts every value of type `source` into a value
+    /// `target` accepts, if any
+    #[must_use]
+    pub fn find(&self, source: &DataType, target: &DataType) -> Option<&Coercion> {
+        self.coercions.iter().rev().find(|coercion| {
+            source.is_compatible_with(&coercion.from) && coercion.to.is_compatible_with(target)
+        })
+    }
+}
+
+fn json_number(value: &Value) -> Result<f64, String> {
+    value
+        .as_f64()
+        .ok_or_else(|| format!("Expected a JSON number, got {value}"))
+}
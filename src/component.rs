@@ -1,17 +1,28 @@
 use std::sync::RwLock;
 use std::{
+    collections::BTreeMap,
     collections::HashMap,
     hash::DefaultHasher,
     hash::{Hash, Hasher},
     sync::Arc,
 };
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::future::Future;
 
+use futures::future::BoxFuture;
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::coercion::CoercionRegistry;
 use crate::dag::DAGError;
 use crate::dag::NodeExecutionContext;
+use crate::dagir::DAGConfig;
+
+mod json_schema;
+
+pub use json_schema::{JsonSchema, ObjectSchema};
 
 /// Runtime values that flow through the DAG
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +34,69 @@ pub enum Data {
     Text(String),
     List(Vec<Data>),
     Json(Value),
+    /// Values keyed by name, e.g. the outputs bound to a node's named input ports
+    Record(IndexMap<String, Data>),
+    Bool(bool),
+    Int64(i64),
+    Bytes(Vec<u8>),
+    /// Values of the same type keyed by arbitrary names, unlike a `Record`
+    /// whose fields are fixed by its type
+    Map(IndexMap<String, Data>),
+    /// An n-dimensional array of `dtype` elements, laid out in row-major order
+    /// as little-endian bytes. Build one with `Data::tensor` to check that
+    /// `data` matches `shape`.
+    Tensor {
+        shape: Vec<usize>,
+        dtype: TensorDType,
+        data: Vec<u8>,
+    },
+}
+
+/// The element type of a `Data::Tensor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TensorDType {
+    Bool,
+    UInt8,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+}
+
+impl TensorDType {
+    /// The size of one element, in bytes
+    #[must_use]
+    pub fn size(self) -> usize {
+        match self {
+            TensorDType::Bool | TensorDType::UInt8 => 1,
+            TensorDType::Int32 | TensorDType::Float32 => 4,
+            TensorDType::Int64 | TensorDType::Float64 => 8,
+        }
+    }
+
+    /// Decodes one little-endian element, which must be `size()` bytes long
+    fn decode(self, bytes: &[u8]) -> Value {
+        match self {
+            TensorDType::Bool => Value::from(bytes[0] != 0),
+            TensorDType::UInt8 => Value::from(bytes[0]),
+            TensorDType::Int32 => Value::from(i32::from_le_bytes(bytes.try_into().unwrap_or_default())),
+            TensorDType::Int64 => Value::from(i64::from_le_bytes(bytes.try_into().unwrap_or_default())),
+            TensorDType::Float32 => Value::from(f32::from_le_bytes(bytes.try_into().unwrap_or_default())),
+            TensorDType::Float64 => Value::from(f64::from_le_bytes(bytes.try_into().unwrap_or_default())),
+        }
+    }
+}
+
+/// Hashes `entries` sorted by key, as they compare equal in any order
+fn hash_entries<H: Hasher>(entries: &IndexMap<String, Data>, state: &mut H) {
+    let mut sorted: Vec<_> = entries.iter().collect();
+    sorted.sort_unstable_by_key(|(key, _)| *key);
+    sorted.len().hash(state);
+    for (key, value) in sorted {
+        key.hash(state);
+        value.hash(state);
+    }
 }
 
 impl Hash for Data {
@@ -45,6 +119,7 @@ impl Hash for Data {
             }
             Data::List(values) => {
                 "List".hash(state);
+                values.len().hash(state);
                 for value in values {
                     value.hash(state);
                 }
@@ -53,6 +128,32 @@ impl Hash for Data {
                 "Json".hash(state);
                 value.to_string().hash(state);
             }
+            Data::Record(fields) => {
+                "Record".hash(state);
+                hash_entries(fields, state);
+            }
+            Data::Bool(value) => {
+                "Bool".hash(state);
+                value.hash(state);
+            }
+            Data::Int64(value) => {
+                "Int64".hash(state);
+                value.hash(state);
+            }
+            Data::Bytes(value) => {
+                "Bytes".hash(state);
+                value.hash(state);
+            }
+            Data::Map(entries) => {
+                "Map".hash(state);
+                hash_entries(entries, state);
+            }
+            Data::Tensor { shape, dtype, data } => {
+                "Tensor".hash(state);
+                shape.hash(state);
+                dtype.hash(state);
+                data.hash(state);
+            }
         }
     }
 }
@@ -66,6 +167,22 @@ impl PartialEq for Data {
             (Data::Text(a), Data::Text(b)) => a == b,
             (Data::List(a), Data::List(b)) => a == b,
             (Data::Json(a), Data::Json(b)) => a == b,
+            (Data::Record(a), Data::Record(b)) | (Data::Map(a), Data::Map(b)) => a == b,
+            (Data::Bool(a), Data::Bool(b)) => a == b,
+            (Data::Int64(a), Data::Int64(b)) => a == b,
+            (Data::Bytes(a), Data::Bytes(b)) => a == b,
+            (
+                Data::Tensor {
+                    shape: a_shape,
+                    dtype: a_dtype,
+                    data: a_data,
+                },
+                Data::Tensor {
+                    shape: b_shape,
+                    dtype: b_dtype,
+                    data: b_data,
+                },
+            ) => a_shape == b_shape && a_dtype == b_dtype && a_data == b_data,
             _ => false,
         }
     }
@@ -82,6 +199,25 @@ pub enum DataType {
     List(Box<DataType>),
     Json,
     Union(Vec<DataType>),
+    /// A record with exactly these fields, e.g. a component's named input ports
+    Record(BTreeMap<String, DataType>),
+    /// A record or map with any field names, whose values all have the given type
+    Map(Box<DataType>),
+    Bool,
+    /// A 64-bit integer, which 32-bit `Integer` values widen into
+    Int64,
+    Bytes,
+    /// A tensor of any shape with elements of the given type
+    Tensor(TensorDType),
+    /// JSON with the given structure
+    JsonSchema(Box<JsonSchema>),
+    /// Either `Null` or the given type
+    Optional(Box<DataType>),
+    /// Any value, e.g. the input of a component that accepts anything
+    Any,
+    /// No value at all, e.g. the elements of an empty list, so it's
+    /// compatible with every type
+    Never,
 }
 
 impl DataType {
@@ -96,6 +232,18 @@ impl DataType {
     /// - **Union Compatibility**: A `DataType` is compatible with a `DataType::Union` if it is compatible
     ///   with at least one of the types in the union.
     /// - **List Compatibility**: Two `DataType::List` types are compatible if their element types are compatible.
+    /// - **Record Compatibility**: Two `DataType::Record` types are compatible if they have the same fields
+    ///   and each field's types are compatible. A `DataType::Record` is compatible with a `DataType::Map`
+    ///   if each field is compatible with the map's value type.
+    /// - **Top and Bottom**: Every type is compatible with `DataType::Any`, and `DataType::Never` is
+    ///   compatible with every type.
+    /// - **Union Sources**: A `DataType::Union` is compatible with a type if all of its types are.
+    /// - **Optional Compatibility**: `DataType::Null` and every type compatible with `T` are compatible
+    ///   with `DataType::Optional(T)`, which behaves as a union of `Null` and `T` as a source.
+    /// - **Integer Widening**: `DataType::Integer` is compatible with `DataType::Int64`, never the reverse.
+    /// - **Schema Compatibility**: A `DataType::JsonSchema` is compatible with `DataType::Json`, and with
+    ///   another `DataType::JsonSchema` if it's a structural subtype of it, e.g. an object with more
+    ///   required properties.
     /// - **Otherwise**: The types are considered incompatible.
     ///
     /// ### Parameters:
@@ -150,18 +298,133 @@ impl DataType {
         match (self, other) {
             (a, b) if a == b => true,
 
+            (_, DataType::Any)
+            | (DataType::Never, _)
+            | (DataType::Null, DataType::Optional(_))
+            | (DataType::Integer, DataType::Int64)
+            | (DataType::JsonSchema(_), DataType::Json) => true,
+
+            (DataType::Optional(a), DataType::Optional(b)) => a.is_compatible_with(b),
+
+            (DataType::Optional(a), _) => {
+                DataType::Null.is_compatible_with(other) && a.is_compatible_with(other)
+            }
+
+            (DataType::Union(source_types), _) => source_types
+                .iter()
+                .all(|s| s.is_compatible_with(other)),
+
+            (source_type, DataType::Optional(b)) => source_type.is_compatible_with(b),
+
+            (DataType::JsonSchema(a), DataType::JsonSchema(b)) => a.is_subtype_of(b),
+
             (source_type, DataType::Union(target_types)) => target_types
                 .iter()
                 .any(|t| source_type.is_compatible_with(t)),
 
-            (DataType::List(a), DataType::List(b)) => a.is_compatible_with(b),
+            (DataType::List(a), DataType::List(b)) | (DataType::Map(a), DataType::Map(b)) => {
+                a.is_compatible_with(b)
+            }
+
+            (DataType::Record(a), DataType::Record(b)) => {
+                a.len() == b.len()
+                    && a.iter().all(|(field, a_type)| {
+                        b.get(field).is_some_and(|b_type| a_type.is_compatible_with(b_type))
+                    })
+            }
+
+            (DataType::Record(fields), DataType::Map(value_type)) => fields
+                .values()
+                .all(|field_type| field_type.is_compatible_with(value_type)),
 
             _ => false,
         }
     }
+
+    /// Determines whether values of this `DataType` may be used where `target`
+    /// is expected, as checked for every edge when a DAG is built with
+    /// `DAGSettings::optimistic_type_checks`.
+    ///
+    /// This is `is_compatible_with`, except that a `DataType::Union` source
+    /// (including nested in a list) only needs one of its types to be accepted:
+    /// which one a component actually outputs is only known at runtime, where
+    /// the value is checked again. For the same reason, `DataType::Any` may
+    /// flow into any type, an `Optional` source only needs `Null` or its type
+    /// to be accepted, and `DataType::Json` may flow into a `DataType::JsonSchema`.
+    ///
+    /// ```rust
+    /// use baselard::component::DataType;
+    /// let source = DataType::Union(vec![DataType::Integer, DataType::Json]);
+    /// assert!(source.can_flow_into(&DataType::Json));
+    /// assert!(!source.can_flow_into(&DataType::Text));
+    /// ```
+    #[must_use]
+    pub fn can_flow_into(&self, target: &DataType) -> bool {
+        match (self, target) {
+            (DataType::Any, _) | (DataType::Json, DataType::JsonSchema(_)) => true,
+            (DataType::Union(sources), _) => sources.iter().any(|s| s.can_flow_into(target)),
+            (DataType::Optional(a), _) => {
+                DataType::Null.can_flow_into(target) || a.can_flow_into(target)
+            }
+            (_, DataType::Union(targets)) => targets.iter().any(|t| self.can_flow_into(t)),
+            (_, DataType::Optional(b)) => *self == DataType::Null || self.can_flow_into(b),
+            (DataType::List(a), DataType::List(b)) => a.can_flow_into(b),
+            _ => self.is_compatible_with(target),
+        }
+    }
+
+    /// The narrowest type of values of any of `types`, e.g. the elements of a
+    /// list: the type they share, `Optional` if that's all but `Null`, else a
+    /// `Union` of the distinct types; `Never` if there are none.
+    #[must_use]
+    pub fn union_of(types: impl IntoIterator<Item = DataType>) -> DataType {
+        let mut distinct: Vec<DataType> = Vec::new();
+        for data_type in types {
+            let members = match data_type {
+                DataType::Union(members) => members,
+                DataType::Never => Vec::new(),
+                other => vec![other],
+            };
+            for member in members {
+                if !distinct.contains(&member) {
+                    distinct.push(member);
+                }
+            }
+        }
+
+        match distinct.len() {
+            0 => DataType::Never,
+            1 => distinct.remove(0),
+            2 if distinct.contains(&DataType::Null) => {
+                distinct.retain(|data_type| *data_type != DataType::Null);
+                DataType::Optional(Box::new(distinct.remove(0)))
+            }
+            _ => DataType::Union(distinct),
+        }
+    }
 }
 
 impl Data {
+    /// A tensor of `dtype` elements with the given shape, from their
+    /// little-endian bytes in row-major order
+    ///
+    /// # Errors
+    /// Returns an error if `data` doesn't hold exactly as many elements as
+    /// `shape` has.
+    pub fn tensor(shape: Vec<usize>, dtype: TensorDType, data: Vec<u8>) -> Result<Data, String> {
+        let expected = shape
+            .iter()
+            .try_fold(dtype.size(), |len, dim| len.checked_mul(*dim))
+            .ok_or_else(|| format!("Tensor shape {shape:?} is too large"))?;
+        if data.len() != expected {
+            return Err(format!(
+                "Tensor of shape {shape:?} and dtype {dtype:?} needs {expected} bytes, got {}",
+                data.len()
+            ));
+        }
+        Ok(Data::Tensor { shape, dtype, data })
+    }
+
     #[must_use]
     pub fn as_integer(&self) -> Option<i32> {
         if let Data::Integer(v) = self {
@@ -189,6 +452,84 @@ impl Data {
         }
     }
 
+    #[must_use]
+    pub fn as_record(&self) -> Option<&IndexMap<String, Data>> {
+        if let Data::Record(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_bool(&self) -> Option<bool> {
+        if let Data::Bool(v) = self {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    /// The value as a 64-bit integer, widening `Data::Integer`
+    #[must_use]
+    pub fn as_int64(&self) -> Option<i64> {
+        match self {
+            Data::Int64(v) => Some(*v),
+            Data::Integer(v) => Some(i64::from(*v)),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        if let Data::Bytes(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_map(&self) -> Option<&IndexMap<String, Data>> {
+        if let Data::Map(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// The value as plain JSON, e.g. to evaluate jq expressions against it.
+    /// Lists and bytes become arrays, records and maps become objects, and
+    /// tensors become objects with their shape, dtype and decoded elements.
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        match self {
+            Data::Null => Value::Null,
+            Data::Integer(i) => Value::from(*i),
+            Data::Float(f) => Value::from(*f),
+            Data::Text(t) => Value::from(t.as_str()),
+            Data::List(items) => Value::Array(items.iter().map(Data::to_json).collect()),
+            Data::Json(value) => value.clone(),
+            Data::Record(fields) | Data::Map(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.to_json()))
+                    .collect(),
+            ),
+            Data::Bool(b) => Value::from(*b),
+            Data::Int64(i) => Value::from(*i),
+            Data::Bytes(bytes) => Value::Array(bytes.iter().map(|b| Value::from(*b)).collect()),
+            Data::Tensor { shape, dtype, data } => serde_json::json!({
+                "shape": shape,
+                "dtype": dtype,
+                "data": data
+                    .chunks_exact(dtype.size())
+                    .map(|element| dtype.decode(element))
+                    .collect::<Vec<_>>(),
+            }),
+        }
+    }
+
     #[must_use]
     pub fn get_type(&self) -> DataType {
         match self {
@@ -197,13 +538,22 @@ impl Data {
             Data::Float(_) => DataType::Float,
             Data::Text(_) => DataType::Text,
             Data::List(items) => {
-                if let Some(first) = items.first() {
-                    DataType::List(Box::new(first.get_type()))
-                } else {
-                    DataType::List(Box::new(DataType::Integer))
-                }
+                DataType::List(Box::new(DataType::union_of(items.iter().map(Data::get_type))))
             }
             Data::Json(_) => DataType::Json,
+            Data::Record(fields) => DataType::Record(
+                fields
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.get_type()))
+                    .collect(),
+            ),
+            Data::Bool(_) => DataType::Bool,
+            Data::Int64(_) => DataType::Int64,
+            Data::Bytes(_) => DataType::Bytes,
+            Data::Map(entries) => DataType::Map(Box::new(DataType::union_of(
+                entries.values().map(Data::get_type),
+            ))),
+            Data::Tensor { dtype, .. } => DataType::Tensor(*dtype),
         }
     }
 
@@ -227,10 +577,67 @@ impl Data {
             }
             DataType::Json => matches!(self, Data::Json(_)),
             DataType::Union(types) => types.iter().any(|t| self.validate_type(t)),
+            DataType::Record(field_types) => {
+                if let Data::Record(fields) = self {
+                    fields.len() == field_types.len()
+                        && fields.iter().all(|(key, value)| {
+                            field_types
+                                .get(key)
+                                .is_some_and(|field_type| value.validate_type(field_type))
+                        })
+                } else {
+                    false
+                }
+            }
+            DataType::Map(value_type) => {
+                if let Data::Record(fields) | Data::Map(fields) = self {
+                    fields.values().all(|value| value.validate_type(value_type))
+                } else {
+                    false
+                }
+            }
+            DataType::Bool => matches!(self, Data::Bool(_)),
+            DataType::Int64 => matches!(self, Data::Int64(_) | Data::Integer(_)),
+            DataType::Bytes => matches!(self, Data::Bytes(_)),
+            DataType::Tensor(element_type) => {
+                matches!(self, Data::Tensor { dtype, .. } if dtype == element_type)
+            }
+            DataType::JsonSchema(schema) => {
+                matches!(self, Data::Json(value) if schema.validate(value).is_ok())
+            }
+            DataType::Optional(value_type) => {
+                matches!(self, Data::Null) || self.validate_type(value_type)
+            }
+            DataType::Any => true,
+            DataType::Never => false,
         }
     }
 }
 
+/// Deserializes a component's typed config, e.g. in `Component::configure`
+///
+/// # Errors
+/// Returns `component::Error::ConfigurationError` naming the path of the
+/// first invalid or missing field, e.g. `Invalid config at limits.max: ...`.
+pub fn parse_config<T: DeserializeOwned>(config: Value) -> Result<T, Error> {
+    serde_path_to_error::deserialize(config).map_err(|e| {
+        let path = e.path().to_string();
+        let inner = e.into_inner();
+        if path == "." {
+            Error::ConfigurationError(format!("Invalid config: {inner}"))
+        } else {
+            Error::ConfigurationError(format!("Invalid config at {path}: {inner}"))
+        }
+    })
+}
+
+/// The JSON Schema generated for a typed config, for
+/// `Component::config_schema`
+#[must_use]
+pub fn config_schema<T: schemars::JsonSchema>() -> Value {
+    serde_json::to_value(schemars::schema_for!(T)).unwrap_or_default()
+}
+
 pub trait Component: Send + Sync + 'static {
     /// Configure a new component instance from the provided configuration
     ///
@@ -240,17 +647,140 @@ pub trait Component: Send + Sync + 'static {
     where
         Self: Sized;
 
+    /// The JSON Schema of the configuration, for components configured from
+    /// a typed config struct with `parse_config`; see `config_schema`. The
+    /// `Registry` exposes it by component type.
+    #[must_use]
+    fn config_schema() -> Option<Value>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
     /// Execute the component with the given execution context and input data
     ///
     /// # Errors
     /// Returns `DAGError` if the component execution fails.
     fn execute(&self, context: NodeExecutionContext, input: Data) -> Result<Data, DAGError>;
 
+    /// Called when the DAG aborts while this node is still executing, so the
+    /// component can release whatever it holds for the request. `execute` may
+    /// still be running on another thread; the context's cancellation token is
+    /// already cancelled by the time this is called.
+    fn on_abort(&self, _context: NodeExecutionContext) {}
+
+    fn input_type(&self) -> DataType;
+
+    fn output_type(&self) -> DataType;
+
+    /// How the DAG executes an `AsyncComponent` registered with
+    /// `Registry::register_async`; `None` for components that execute on the
+    /// blocking thread pool. There is no need to implement this.
+    fn as_async(&self) -> Option<&dyn AsyncExecution> {
+        None
+    }
+}
+
+/// A component whose execution is a future, for I/O-bound nodes such as
+/// remote calls: the DAG polls it on the tokio runtime rather than tying up
+/// a blocking-pool thread while it waits. Register it with
+/// `Registry::register_async`; CPU-bound work belongs in a `Component`, or
+/// in `tokio::task::spawn_blocking` from here.
+pub trait AsyncComponent: Send + Sync + 'static {
+    /// Configure a new component instance from the provided configuration
+    ///
+    /// # Errors
+    /// Returns `component::Error::ConfigurationError` if the configuration is invalid.
+    fn configure(config: Value) -> Result<Self, Error>
+    where
+        Self: Sized;
+
+    /// The JSON Schema of the configuration, for components configured from
+    /// a typed config struct with `parse_config`; see `config_schema`. The
+    /// `Registry` exposes it by component type.
+    #[must_use]
+    fn config_schema() -> Option<Value>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Execute the component with the given execution context and input data
+    ///
+    /// # Errors
+    /// Returns `DAGError` if the component execution fails.
+    fn execute(
+        &self,
+        context: NodeExecutionContext,
+        input: Data,
+    ) -> impl Future<Output = Result<Data, DAGError>> + Send;
+
+    /// Called when the DAG aborts while this node is still executing, like
+    /// `Component::on_abort`
+    fn on_abort(&self, _context: NodeExecutionContext) {}
+
     fn input_type(&self) -> DataType;
 
     fn output_type(&self) -> DataType;
 }
 
+/// The object-safe form of `AsyncComponent::execute`
+pub trait AsyncExecution: Send + Sync {
+    fn execute_async(
+        &self,
+        context: NodeExecutionContext,
+        input: Data,
+    ) -> BoxFuture<'_, Result<Data, DAGError>>;
+}
+
+/// Lets the registry and the DAG treat an `AsyncComponent` like any other
+/// Component
+struct AsyncComponentAdapter<C>(C);
+
+impl<C: AsyncComponent> AsyncExecution for AsyncComponentAdapter<C> {
+    fn execute_async(
+        &self,
+        context: NodeExecutionContext,
+        input: Data,
+    ) -> BoxFuture<'_, Result<Data, DAGError>> {
+        Box::pin(self.0.execute(context, input))
+    }
+}
+
+impl<C: AsyncComponent> Component for AsyncComponentAdapter<C> {
+    fn configure(config: Value) -> Result<Self, Error> {
+        C::configure(config).map(Self)
+    }
+
+    fn config_schema() -> Option<Value> {
+        C::config_schema()
+    }
+
+    /// The DAG awaits `as_async` instead; this is for callers executing the
+    /// component from a blocking thread.
+    fn execute(&self, context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
+        tokio::runtime::Handle::current().block_on(self.0.execute(context, input))
+    }
+
+    fn on_abort(&self, context: NodeExecutionContext) {
+        self.0.on_abort(context);
+    }
+
+    fn input_type(&self) -> DataType {
+        self.0.input_type()
+    }
+
+    fn output_type(&self) -> DataType {
+        self.0.output_type()
+    }
+
+    fn as_async(&self) -> Option<&dyn AsyncExecution> {
+        Some(self)
+    }
+}
+
 type ComponentType = String;
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
@@ -265,6 +795,11 @@ pub struct Registry {
     unconfigured_component_factories: HashMap<ComponentType, RegisteredComponentFactory>,
     configured_component_cache: Arc<RwLock<HashMap<ComponentKey, Arc<dyn Component>>>>,
     configured_count: AtomicUsize,
+    /// DAGs that `Map` and `SubDag` nodes can reference by alias
+    dag_configs: HashMap<String, DAGConfig>,
+    coercions: CoercionRegistry,
+    /// The JSON Schemas of the configs of component types that declare one
+    config_schemas: HashMap<ComponentType, Value>,
 }
 
 impl Default for Registry {
@@ -280,11 +815,47 @@ impl Registry {
             unconfigured_component_factories: HashMap::new(),
             configured_component_cache: Arc::new(RwLock::new(HashMap::new())),
             configured_count: AtomicUsize::new(0),
+            dag_configs: HashMap::new(),
+            coercions: CoercionRegistry::with_defaults(),
+            config_schemas: HashMap::new(),
         }
     }
 
+    /// Registers a coercion that DAGs built with
+    /// `DAGSettings::implicit_coercions` may apply on edges whose types
+    /// aren't compatible, alongside the built-in ones of
+    /// `CoercionRegistry::with_defaults`
+    pub fn register_coercion<F>(&mut self, name: &str, from: DataType, to: DataType, convert: F)
+    where
+        F: Fn(Data) -> Result<Data, String> + Send + Sync + 'static,
+    {
+        self.coercions.register(name, from, to, convert);
+    }
+
+    #[must_use]
+    pub fn coercions(&self) -> &CoercionRegistry {
+        &self.coercions
+    }
+
+    /// Registers a DAG configuration under its alias, so that it can be
+    /// used as the sub-DAG of a `Map` or `SubDag` node.
+    pub fn register_dag(&mut self, config: DAGConfig) {
+        self.dag_configs.insert(config.alias.clone(), config);
+    }
+
+    /// Gets a DAG configuration registered with `register_dag`
+    #[must_use]
+    pub fn get_dag(&self, alias: &str) -> Option<&DAGConfig> {
+        self.dag_configs.get(alias)
+    }
+
     /// Registers a new component type with the registry.
     pub fn register<C: Component + 'static>(&mut self, name: &str) {
+        if let Some(schema) = C::config_schema() {
+            self.config_schemas.insert(name.to_string(), schema);
+        } else {
+            self.config_schemas.remove(name);
+        }
         self.unconfigured_component_factories.insert(
             name.to_string(),
             Arc::new(|config| -> Result<Arc<dyn Component>, Error> {
@@ -295,6 +866,12 @@ impl Registry {
         );
     }
 
+    /// Registers a new async component type with the registry. Its nodes are
+    /// executed on the tokio runtime instead of the blocking thread pool.
+    pub fn register_async<C: AsyncComponent>(&mut self, name: &str) {
+        self.register::<AsyncComponentAdapter<C>>(name);
+    }
+
     /// Gets a configured component instance, using cache if available.
     /// This is the preferred method for getting components during DAG execution.
     ///
@@ -334,6 +911,13 @@ impl Registry {
         }
     }
 
+    /// The JSON Schema of the config of a registered component type, if it
+    /// declares one
+    #[must_use]
+    pub fn config_schema(&self, name: &str) -> Option<&Value> {
+        self.config_schemas.get(name)
+    }
+
     /// Gets the raw component factory. This is primarily for internal use
     /// or advanced cases where you need to manage component configuration yourself.
     #[must_use]
@@ -342,7 +926,7 @@ impl Registry {
     }
 
     // Add this helper method
-    fn calculate_config_hash(config: &Value) -> u64 {
+    pub(crate) fn calculate_config_hash(config: &Value) -> u64 {
         let mut hasher = DefaultHasher::new();
         config.to_string().hash(&mut hasher);
         hasher.finish()
@@ -360,6 +944,9 @@ impl std::fmt::Debug for Registry {
                 "configured_component_cache",
                 &self.configured_count.load(Ordering::Relaxed),
             )
+            .field("dag_configs", &self.dag_configs.keys().collect::<Vec<_>>())
+            .field("coercions", &self.coercions)
+            .field("config_schemas", &self.config_schemas.keys().collect::<Vec<_>>())
             .finish_non_exhaustive()
     }
 }
@@ -0,0 +1,259 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::{Map, Value};
+
+/// Keywords that only annotate a schema, which are accepted and ignored
+const ANNOTATIONS: [&str; 6] = ["$schema", "$id", "title", "description", "default", "examples"];
+
+/// The structure of JSON values, as a subset of JSON Schema: the `type` of
+/// a value, the `properties` and `required` keys of an object, and the
+/// `items` of an array.
+///
+/// As in JSON Schema, objects may have properties beyond those declared, of
+/// any type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonSchema {
+    /// Any JSON value, e.g. `{}`
+    Any,
+    Null,
+    Boolean,
+    /// A number without a fractional part
+    Integer,
+    Number,
+    String,
+    Array(Box<JsonSchema>),
+    Object(ObjectSchema),
+}
+
+/// The properties of an object, and which of them it must have
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObjectSchema {
+    pub properties: BTreeMap<String, JsonSchema>,
+    pub required: BTreeSet<String>,
+}
+
+impl ObjectSchema {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares an optional property
+    #[must_use]
+    pub fn with_property(mut self, name: impl Into<String>, schema: JsonSchema) -> Self {
+        self.properties.insert(name.into(), schema);
+        self
+    }
+
+    /// Declares a property the object must have
+    #[must_use]
+    pub fn with_required_property(mut self, name: impl Into<String>, schema: JsonSchema) -> Self {
+        let name = name.into();
+        self.required.insert(name.clone());
+        self.properties.insert(name, schema);
+        self
+    }
+
+    /// The schema of a property, which is `Any` if it isn't declared
+    #[must_use]
+    pub fn property(&self, name: &str) -> &JsonSchema {
+        self.properties.get(name).unwrap_or(&JsonSchema::Any)
+    }
+}
+
+impl JsonSchema {
+    #[must_use]
+    pub fn array(items: JsonSchema) -> Self {
+        JsonSchema::Array(Box::new(items))
+    }
+
+    /// Parses a JSON Schema document, e.g.
+    /// `{ "type": "object", "properties": { "id": { "type": "integer" } }, "required": ["id"] }`.
+    /// Annotations such as `title` and `description` are ignored.
+    ///
+    /// # Errors
+    /// Returns an error naming where in the document it uses a keyword or
+    /// type outside the supported subset, or is malformed.
+    pub fn from_json(schema: &Value) -> Result<Self, String> {
+        Self::parse(schema, "#")
+    }
+
+    fn parse(schema: &Value, path: &str) -> Result<Self, String> {
+        let keywords = match schema {
+            Value::Bool(true) => return Ok(JsonSchema::Any),
+            Value::Object(keywords) => keywords,
+            _ => return Err(format!("{path}: a schema must be an object or `true`")),
+        };
+        if let Some(keyword) = keywords.keys().find(|keyword| {
+            !ANNOTATIONS.contains(&keyword.as_str())
+                && !["type", "properties", "required", "items"].contains(&keyword.as_str())
+        }) {
+            return Err(format!("{path}: unsupported keyword `{keyword}`"));
+        }
+
+        let schema_type = match keywords.get("type") {
+            Some(Value::String(schema_type)) => schema_type.as_str(),
+            Some(other) => return Err(format!("{path}/type: expected a type name, got {other}")),
+            None if keywords.contains_key("properties") || keywords.contains_key("required") => {
+                "object"
+            }
+            None if keywords.contains_key("items") => "array",
+            None => return Ok(JsonSchema::Any),
+        };
+        match schema_type {
+            "null" => Ok(JsonSchema::Null),
+            "boolean" => Ok(JsonSchema::Boolean),
+            "integer" => Ok(JsonSchema::Integer),
+            "number" => Ok(JsonSchema::Number),
+            "string" => Ok(JsonSchema::String),
+            "array" => match keywords.get("items") {
+                Some(items) => Ok(JsonSchema::array(Self::parse(items, &format!("{path}/items"))?)),
+                None => Ok(JsonSchema::array(JsonSchema::Any)),
+            },
+            "object" => Self::parse_object(keywords, path).map(JsonSchema::Object),
+            other => Err(format!("{path}/type: unsupported type `{other}`")),
+        }
+    }
+
+    fn parse_object(keywords: &Map<String, Value>, path: &str) -> Result<ObjectSchema, String> {
+        let mut object = ObjectSchema::new();
+        match keywords.get("properties") {
+            Some(Value::Object(properties)) => {
+                for (name, schema) in properties {
+                    let schema = Self::parse(schema, &format!("{path}/properties/{name}"))?;
+                    object.properties.insert(name.clone(), schema);
+                }
+            }
+            Some(_) => return Err(format!("{path}/properties: expected an object")),
+            None => {}
+        }
+        match keywords.get("required") {
+            Some(Value::Array(required)) => {
+                for name in required {
+                    let name = name
+                        .as_str()
+                        .ok_or_else(|| format!("{path}/required: expected property names"))?;
+                    object.required.insert(name.to_string());
+                }
+            }
+            Some(_) => return Err(format!("{path}/required: expected an array")),
+            None => {}
+        }
+        Ok(object)
+    }
+
+    /// The schema as a JSON Schema document
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        match self {
+            JsonSchema::Any => Value::Object(Map::new()),
+            JsonSchema::Null => serde_json::json!({ "type": "null" }),
+            JsonSchema::Boolean => serde_json::json!({ "type": "boolean" }),
+            JsonSchema::Integer => serde_json::json!({ "type": "integer" }),
+            JsonSchema::Number => serde_json::json!({ "type": "number" }),
+            JsonSchema::String => serde_json::json!({ "type": "string" }),
+            JsonSchema::Array(items) => serde_json::json!({ "type": "array", "items": items.to_json() }),
+            JsonSchema::Object(object) => {
+                let properties: Map<String, Value> = object
+                    .properties
+                    .iter()
+                    .map(|(name, schema)| (name.clone(), schema.to_json()))
+                    .collect();
+                serde_json::json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": object.required,
+                })
+            }
+        }
+    }
+
+    /// Whether every value this schema accepts is accepted by `other` too,
+    /// e.g. an object with more required properties than `other` asks for.
+    ///
+    /// A property `other` declares must be declared by this schema with a
+    /// subtype of its schema, as undeclared properties may hold anything;
+    /// unless `other` accepts any value for it.
+    #[must_use]
+    pub fn is_subtype_of(&self, other: &JsonSchema) -> bool {
+        match (self, other) {
+            (_, JsonSchema::Any) | (JsonSchema::Integer, JsonSchema::Number) => true,
+            (JsonSchema::Array(a), JsonSchema::Array(b)) => a.is_subtype_of(b),
+            (JsonSchema::Object(a), JsonSchema::Object(b)) => {
+                b.required.is_subset(&a.required)
+                    && b
+                        .properties
+                        .iter()
+                        .all(|(name, schema)| a.property(name).is_subtype_of(schema))
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// Checks that `value` has this structure
+    ///
+    /// # Errors
+    /// Returns an error naming the first part of `value` that doesn't match,
+    /// by its path from the root `$`.
+    pub fn validate(&self, value: &Value) -> Result<(), String> {
+        self.validate_at(value, "$")
+    }
+
+    fn validate_at(&self, value: &Value, path: &str) -> Result<(), String> {
+        let matches = match (self, value) {
+            (JsonSchema::Any, _)
+            | (JsonSchema::Null, Value::Null)
+            | (JsonSchema::Boolean, Value::Bool(_))
+            | (JsonSchema::Number, Value::Number(_))
+            | (JsonSchema::String, Value::String(_)) => true,
+            (JsonSchema::Integer, Value::Number(n)) => {
+                n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0)
+            }
+            (JsonSchema::Array(items), Value::Array(values)) => {
+                for (i, value) in values.iter().enumerate() {
+                    items.validate_at(value, &format!("{path}[{i}]"))?;
+                }
+                true
+            }
+            (JsonSchema::Object(object), Value::Object(fields)) => {
+                if let Some(missing) = object.required.iter().find(|name| !fields.contains_key(*name)) {
+                    return Err(format!("{path}: missing required property `{missing}`"));
+                }
+                for (name, value) in fields {
+                    object.property(name).validate_at(value, &format!("{path}.{name}"))?;
+                }
+                true
+            }
+            _ => false,
+        };
+        if matches {
+            Ok(())
+        } else {
+            Err(format!("{path}: expected {}, got {}", self.type_name(), type_name(value)))
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            JsonSchema::Any => "any value",
+            JsonSchema::Null => "null",
+            JsonSchema::Boolean => "boolean",
+            JsonSchema::Integer => "integer",
+            JsonSchema::Number => "number",
+            JsonSchema::String => "string",
+            JsonSchema::Array(_) => "array",
+            JsonSchema::Object(_) => "object",
+        }
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
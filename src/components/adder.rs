@@ -1,17 +1,28 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
 use serde_json::Value;
 
-use crate::component::{Component, Data, DataType, Error};
+use crate::component::{config_schema, parse_config, Component, Data, DataType, Error};
 use crate::dag::{DAGError, NodeExecutionContext};
 
 pub struct Adder {
     value: i32,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AdderConfig {
+    /// Added to the input
+    pub value: i32,
+}
+
 impl Component for Adder {
     fn configure(config: Value) -> Result<Self, Error> {
-        Ok(Adder {
-            value: i32::try_from(config["value"].as_i64().unwrap()).unwrap(),
-        })
+        let AdderConfig { value } = parse_config(config)?;
+        Ok(Adder { value })
+    }
+
+    fn config_schema() -> Option<Value> {
+        Some(config_schema::<AdderConfig>())
     }
 
     fn execute(&self, context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
@@ -1,9 +1,14 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
 use serde_json::Value;
 use spin_sleep::SpinSleeper;
-use std::time::Instant;
-use crate::component::{Component, Data, DataType, Error};
+use std::time::{Duration, Instant};
+use crate::component::{config_schema, parse_config, Component, Data, DataType, Error};
 use crate::dag::{DAGError, NodeExecutionContext};
 
+/// How often a sleeping `CrashTestDummy` checks whether the DAG was aborted
+const CANCELLATION_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
 /// A test component that can be configured to fail or sleep
 pub struct CrashTestDummy {
     fail: bool,
@@ -15,14 +20,28 @@ pub struct CrashTestDummy {
     sleeper: SpinSleeper,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrashTestDummyConfig {
+    /// Fail every execution
+    #[serde(default)]
+    pub fail: bool,
+    /// Sleep this many milliseconds before succeeding or failing
+    pub sleep_duration_ms: Option<f64>,
+    #[serde(default = "default_spin_threshold_us")]
+    pub spin_threshold_us: u32,
+}
+
+fn default_spin_threshold_us() -> u32 {
+    50_000
+}
+
 impl Component for CrashTestDummy {
     fn configure(config: Value) -> Result<Self, Error> {
-        let fail = config["fail"].as_bool().unwrap_or(false);
-        let sleep_duration_ms = config["sleep_duration_ms"].as_f64();
-        #[allow(clippy::cast_possible_truncation)]
-        let spin_threshold_us = config["spin_threshold_us"]
-            .as_u64()
-            .map_or(50_000, |x| x as u32);
+        let CrashTestDummyConfig {
+            fail,
+            sleep_duration_ms,
+            spin_threshold_us,
+        } = parse_config(config)?;
 
         let sleeper = SpinSleeper::new(spin_threshold_us);
 
@@ -34,6 +53,10 @@ impl Component for CrashTestDummy {
         })
     }
 
+    fn config_schema() -> Option<Value> {
+        Some(config_schema::<CrashTestDummyConfig>())
+    }
+
     fn execute(&self, context: NodeExecutionContext, _input: Data) -> Result<Data, DAGError> {
         if let Some(duration) = self.sleep_duration_ms {
             let start_time = Instant::now();
@@ -43,7 +66,16 @@ impl Component for CrashTestDummy {
                 self.spin_threshold_us
             );
             #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-            self.sleeper.sleep(std::time::Duration::from_millis(duration as u64));
+            if !self.sleep_unless_cancelled(Duration::from_millis(duration as u64), &context) {
+                println!(
+                    "CrashTestDummy {}: Cancelled after {:.3}s",
+                    context.node_id,
+                    start_time.elapsed().as_secs_f32()
+                );
+                return Err(DAGError::Cancelled {
+                    node_id: context.node_id,
+                });
+            }
             println!(
                 "CrashTestDummy {}: Done sleeping after {:.3}s",
                 context.node_id,
@@ -62,15 +94,33 @@ impl Component for CrashTestDummy {
     }
 
     fn input_type(&self) -> DataType {
-        DataType::Union(vec![
-            DataType::Null,
-            DataType::Text,
-            DataType::Json,
-            DataType::Integer,
-        ])
+        DataType::Any
     }
 
     fn output_type(&self) -> DataType {
         DataType::Text
     }
 }
+
+impl CrashTestDummy {
+    /// Sleeps in short slices so that an aborted DAG is noticed quickly,
+    /// spinning only for the final stretch to keep sleeps precise.
+    ///
+    /// Returns `false` if the DAG was cancelled before the sleep finished.
+    fn sleep_unless_cancelled(&self, duration: Duration, context: &NodeExecutionContext) -> bool {
+        let deadline = Instant::now() + duration;
+        let spin_threshold = Duration::from_micros(u64::from(self.spin_threshold_us));
+
+        loop {
+            if context.is_cancelled() {
+                return false;
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining <= spin_threshold {
+                self.sleeper.sleep(remaining);
+                return !context.is_cancelled();
+            }
+            std::thread::sleep(remaining.saturating_sub(spin_threshold).min(CANCELLATION_POLL_INTERVAL));
+        }
+    }
+}
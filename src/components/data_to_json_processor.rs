@@ -1,10 +1,53 @@
-use serde_json::{json, Value};
+use serde_json::{json, Map, Value};
 
 use crate::component::{Component, Data, DataType, Error};
 use crate::dag::{DAGError, NodeExecutionContext};
 
+/// Converts any input to JSON tagged with its type
 pub struct DataToJsonProcessor;
 
+/// `data` as JSON tagged with its type, e.g. `{"type": "integer", "value": 1}`,
+/// with lists, records and maps holding tagged values in turn
+fn typed_json(data: Data) -> Value {
+    match data {
+        Data::Null => json!({ "type": "null" }),
+        Data::Json(value) => {
+            if let Some(num) = value.as_i64() {
+                json!({ "type": "integer", "value": num })
+            } else if let Some(num) = value.as_f64() {
+                json!({ "type": "float", "value": num })
+            } else {
+                json!({ "type": "json", "value": value })
+            }
+        }
+        Data::Integer(i) => json!({ "type": "integer", "value": i }),
+        Data::Int64(i) => json!({ "type": "integer", "value": i }),
+        Data::Bool(b) => json!({ "type": "bool", "value": b }),
+        Data::Float(f) => json!({ "type": "float", "value": f }),
+        Data::Text(t) => json!({ "type": "text", "value": t }),
+        Data::List(list) => {
+            let values: Vec<_> = list.into_iter().map(typed_json).collect();
+            json!({ "type": "list", "values": values })
+        }
+        Data::Map(entries) => {
+            let entries: Map<_, _> = entries
+                .into_iter()
+                .map(|(key, value)| (key, typed_json(value)))
+                .collect();
+            json!({ "type": "map", "entries": entries })
+        }
+        Data::Bytes(_) => json!({ "type": "bytes", "value": data.to_json() }),
+        Data::Tensor { .. } => json!({ "type": "tensor", "value": data.to_json() }),
+        Data::Record(fields) => {
+            let fields: Map<_, _> = fields
+                .into_iter()
+                .map(|(key, value)| (key, typed_json(value)))
+                .collect();
+            json!({ "type": "record", "fields": fields })
+        }
+    }
+}
+
 impl Component for DataToJsonProcessor {
     fn configure(_: Value) -> Result<Self, Error> {
         Ok(DataToJsonProcessor)
@@ -12,48 +55,11 @@ impl Component for DataToJsonProcessor {
 
     fn execute(&self, context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
         println!("DataToJsonProcessor {}: input={input:?}", context.node_id);
-        let json_input = match input {
-            Data::Null => json!({ "type": "null" }),
-            Data::Json(value) => {
-                if let Some(num) = value.as_i64() {
-                    json!({ "type": "integer", "value": num })
-                } else if let Some(num) = value.as_f64() {
-                    json!({ "type": "float", "value": num })
-                } else {
-                    json!({ "type": "json", "value": value })
-                }
-            }
-            Data::Integer(i) => json!({ "type": "integer", "value": i }),
-            Data::Float(f) => json!({ "type": "float", "value": f }),
-            Data::Text(t) => json!({ "type": "text", "value": t }),
-            Data::List(list) => {
-                let json_list: Vec<_> = list
-                    .into_iter()
-                    .map(|item| match item {
-                        Data::Integer(i) => json!({ "type": "integer", "value": i }),
-                        Data::Text(t) => json!({ "type": "text", "value": t }),
-                        _ => json!({ "type": "unknown" }),
-                    })
-                    .collect();
-                json!({ "type": "list", "values": json_list })
-            }
-        };
-
-        Ok(Data::Json(json_input))
+        Ok(Data::Json(typed_json(input)))
     }
 
     fn input_type(&self) -> DataType {
-        DataType::Union(vec![
-            DataType::Json,
-            DataType::Integer,
-            DataType::Float,
-            DataType::Text,
-            DataType::List(Box::new(DataType::Union(vec![
-                DataType::Integer,
-                DataType::Text,
-                DataType::Float,
-            ]))),
-        ])
+        DataType::Any
     }
 
     fn output_type(&self) -> DataType {
@@ -29,15 +29,34 @@ impl Component for JsonCombiner {
 
                 Ok(Data::Json(Value::Object(combined)))
             }
+            Data::Record(ports) => {
+                let mut combined = Map::new();
+
+                // Named input ports keep their names
+                for (port, item) in ports {
+                    let Data::Json(json_value) = item else {
+                        return Err(DAGError::ExecutionError {
+                            node_id: context.node_id,
+                            reason: format!("Input {port} must be JSON"),
+                        })
+                    };
+                    combined.insert(port, json_value);
+                }
+
+                Ok(Data::Json(Value::Object(combined)))
+            }
             _ => Err(DAGError::ExecutionError {
                 node_id: context.node_id,
-                reason: "JsonCombiner requires a List input of JSON values".to_string(),
+                reason: "JsonCombiner requires a List or named ports of JSON values".to_string(),
             }),
         }
     }
 
     fn input_type(&self) -> DataType {
-        DataType::List(Box::new(DataType::Json))
+        DataType::Union(vec![
+            DataType::List(Box::new(DataType::Json)),
+            DataType::Map(Box::new(DataType::Json)),
+        ])
     }
 
     fn output_type(&self) -> DataType {
@@ -5,6 +5,11 @@ use crate::dag::{DAGError, NodeExecutionContext};
 
 pub struct JsonToDataProcessor;
 
+/// An `Integer` if the value fits in 32 bits, else an `Int64`
+fn integer(value: i64) -> Data {
+    i32::try_from(value).map_or(Data::Int64(value), Data::Integer)
+}
+
 impl Component for JsonToDataProcessor {
     fn configure(_: Value) -> Result<Self, Error> {
         Ok(JsonToDataProcessor)
@@ -23,8 +28,8 @@ impl Component for JsonToDataProcessor {
         match json.get("type").and_then(Value::as_str) {
             #[allow(clippy::match_same_arms)]
             Some("null") => Ok(Data::Null),
-            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-            Some("integer") => Ok(Data::Integer(json["value"].as_i64().unwrap() as i32)),
+            Some("integer") => Ok(integer(json["value"].as_i64().unwrap())),
+            Some("bool") => Ok(Data::Bool(json["value"].as_bool().unwrap())),
             Some("float") => Ok(Data::Float(json["value"].as_f64().unwrap())),
             Some("text") => Ok(Data::Text(json["value"].as_str().unwrap().to_string())),
             Some("list") => {
@@ -32,8 +37,8 @@ impl Component for JsonToDataProcessor {
                 let list = values
                     .iter()
                     .map(|v| match v["type"].as_str().unwrap() {
-                        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-                        "integer" => Data::Integer(v["value"].as_i64().unwrap() as i32),
+                        "integer" => integer(v["value"].as_i64().unwrap()),
+                        "bool" => Data::Bool(v["value"].as_bool().unwrap()),
                         "float" => Data::Float(v["value"].as_f64().unwrap()),
                         "text" => Data::Text(v["value"].as_str().unwrap().to_string()),
                         _ => Data::Null,
@@ -53,11 +58,17 @@ impl Component for JsonToDataProcessor {
         DataType::Union(vec![
             DataType::Json,
             DataType::Integer,
+            DataType::Int64,
+            DataType::Bool,
             DataType::Float,
             DataType::Text,
             DataType::List(Box::new(DataType::Union(vec![
                 DataType::Integer,
+                DataType::Int64,
+                DataType::Bool,
+                DataType::Float,
                 DataType::Text,
+                DataType::Null,
             ]))),
         ])
     }
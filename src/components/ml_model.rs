@@ -1,8 +1,10 @@
-use crate::component::{Component, Data, DataType, Error};
+use crate::component::{config_schema, parse_config, AsyncComponent, Data, DataType, Error};
 use crate::dag::{DAGError, NodeExecutionContext};
 use ndarray::{Array, CowArray};
 use ort::{Environment, GraphOptimizationLevel, SessionBuilder, Value as OrtValue};
-use reqwest::blocking::Client;
+use reqwest::Client;
+use schemars::JsonSchema;
+use serde::Deserialize;
 use serde_json::{json, Value};
 use std::sync::Arc;
 use std::time::Instant;
@@ -18,15 +20,32 @@ lazy_static! {
     );
 }
 
+/// Runs a model, either locally through ONNX or on a remote endpoint. This is
+/// an `AsyncComponent`, so register it with `Registry::register_async`: remote
+/// predictions are awaited on the runtime, and local ones are moved to the
+/// blocking thread pool.
 pub struct MLModel {
     remote_endpoint: Option<String>,
     session: Option<Arc<ort::Session>>,
+    /// Shared by every remote prediction, so they reuse its connections
+    client: Client,
 }
 
-impl Component for MLModel {
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MLModelConfig {
+    /// URL of a remote model to run instead of a local one
+    pub remote_endpoint: Option<String>,
+    /// Path of a local ONNX model
+    pub onnx_model_path: Option<String>,
+}
+
+impl AsyncComponent for MLModel {
     fn configure(config: Value) -> Result<Self, Error> {
-        let remote_endpoint = config["remote_endpoint"].as_str().map(String::from);
-        let session = if let Some(model_path) = config["onnx_model_path"].as_str() {
+        let MLModelConfig {
+            remote_endpoint,
+            onnx_model_path,
+        } = parse_config(config)?;
+        let session = if let Some(model_path) = onnx_model_path {
             Some(Arc::new(
                 SessionBuilder::new(&ONNX_ENV)
                     .map_err(|e| Error::ConfigurationError(format!("Failed to create session builder: {e}")))?
@@ -42,10 +61,15 @@ impl Component for MLModel {
         Ok(MLModel {
             remote_endpoint,
             session,
+            client: Client::new(),
         })
     }
 
-    fn execute(&self, context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
+    fn config_schema() -> Option<Value> {
+        Some(config_schema::<MLModelConfig>())
+    }
+
+    async fn execute(&self, context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
         println!("MLModel '{}' started processing", context.node_id);
         let start_time = Instant::now();
 
@@ -70,9 +94,19 @@ impl Component for MLModel {
         };
 
         let result = if let Some(endpoint) = &self.remote_endpoint {
-            Self::handle_remote_prediction(&context.node_id, endpoint, &input_vec)?
+            self.handle_remote_prediction(&context.node_id, endpoint, &input_vec)
+                .await?
         } else {
-            self.handle_local_prediction(&context.node_id, &input_vec)?
+            let session = self.session.clone();
+            let node_id = context.node_id.clone();
+            tokio::task::spawn_blocking(move || {
+                Self::handle_local_prediction(session.as_deref(), &node_id, &input_vec)
+            })
+            .await
+            .map_err(|e| DAGError::ExecutionError {
+                node_id: context.node_id.clone(),
+                reason: format!("Local prediction task failed: {e}"),
+            })??
         };
 
         println!(
@@ -97,8 +131,12 @@ impl Component for MLModel {
 }
 
 impl MLModel {
-    fn handle_local_prediction(&self, node_id: &str, input: &[f64]) -> Result<Vec<f64>, DAGError> {
-        let session = self.session.as_ref().ok_or_else(|| DAGError::ExecutionError {
+    fn handle_local_prediction(
+        session: Option<&ort::Session>,
+        node_id: &str,
+        input: &[f64],
+    ) -> Result<Vec<f64>, DAGError> {
+        let session = session.ok_or_else(|| DAGError::ExecutionError {
             node_id: node_id.to_string(),
             reason: "No ONNX session available".to_string(),
         })?;
@@ -146,33 +184,43 @@ impl MLModel {
         Ok(result)
     }
 
-    fn handle_remote_prediction(
+    async fn handle_remote_prediction(
+        &self,
         node_id: &str,
         endpoint: &str,
         input: &[f64],
     ) -> Result<Vec<f64>, DAGError> {
-        let client = Client::new();
         let input_data = json!({ "features": input });
         println!("Sending payload: {input_data:?}");
 
-        let response = client
+        let response = self
+            .client
             .post(endpoint)
             .json(&input_data)
             .send()
-            .map_err(|e| DAGError::ExecutionError {
+            .await
+            .map_err(|e| DAGError::TransientError {
                 node_id: node_id.to_string(),
                 reason: format!("Remote request failed: {e}"),
             })?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(DAGError::TransientError {
+                node_id: node_id.to_string(),
+                reason: format!("Remote endpoint error: {status}"),
+            });
+        }
+        if !status.is_success() {
             return Err(DAGError::ExecutionError {
                 node_id: node_id.to_string(),
-                reason: format!("Remote endpoint error: {}", response.status()),
+                reason: format!("Remote endpoint error: {status}"),
             });
         }
 
         let result: Vec<f64> = response
             .json::<Value>()
+            .await
             .map_err(|e| DAGError::ExecutionError {
                 node_id: node_id.to_string(),
                 reason: format!("Failed to parse response: {e}"),
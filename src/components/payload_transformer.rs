@@ -1,7 +1,8 @@
-use crate::component::{Component, Data, DataType, Error};
+use crate::component::{config_schema, parse_config, Component, Data, DataType, Error, JsonSchema};
 use crate::dag::{DAGError, NodeExecutionContext};
 use indexmap::IndexMap;
 use jq_rs::compile;
+use serde::Deserialize;
 use serde_json::Value;
 use std::cell::RefCell;
 use std::process::{Child, Command, ExitStatus};
@@ -44,6 +45,13 @@ const VALIDATION_TIMEOUT: f32 = 0.1;
 /// You should be able to paste the expression into the `transformation_expression` field and paste the
 /// validation data into the `validation_data` field. (See `tests/resources` for many examples.)
 ///
+/// ### Schemas
+///
+/// The input and output are opaque JSON unless you declare their structure as JSON Schemas in the
+/// optional `input_schema` and `output_schema` fields, e.g. `{ "type": "object", "required": ["id"] }`.
+/// The DAG then checks them against the components wired in on either side when it's built, and
+/// checks the payloads against them when it executes.
+///
 /// ### Safety
 ///
 /// We compile and run the JQ programs **directly in the Rust process**.
@@ -62,6 +70,8 @@ const VALIDATION_TIMEOUT: f32 = 0.1;
 pub struct PayloadTransformer {
     expression: String,
     max_programs_per_thread: usize,
+    input_schema: Option<JsonSchema>,
+    output_schema: Option<JsonSchema>,
 }
 
 thread_local! {
@@ -372,6 +382,30 @@ impl PayloadTransformer {
     }
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct PayloadTransformerConfig {
+    /// The jq expression, which is the identity `.` by default
+    #[serde(default = "default_transformation_expression")]
+    pub transformation_expression: String,
+    #[serde(default = "default_max_programs_per_thread")]
+    pub max_programs_per_thread: usize,
+    /// An `input` the expression is run on when configured, and the
+    /// `expected_output` it must produce
+    pub validation_data: Value,
+    /// A JSON Schema of the input, which is otherwise any JSON
+    pub input_schema: Option<Value>,
+    /// A JSON Schema of the output, which is otherwise any JSON
+    pub output_schema: Option<Value>,
+}
+
+fn default_transformation_expression() -> String {
+    ".".to_string()
+}
+
+fn default_max_programs_per_thread() -> usize {
+    MAX_PROGRAMS_PER_THREAD
+}
+
 impl Component for PayloadTransformer {
     /// The FFI boundary for libjq means we don't have `Send` or `Sync`, so to keep pre-compiled programs
     /// (since compilation is expensive) we use a `thread_local` to keep them in. We bound this to
@@ -385,28 +419,38 @@ impl Component for PayloadTransformer {
     fn configure(config: Value) -> Result<Self, Error> {
         println!("PayloadTransformer config: {config:?}");
 
-        let expression = config["transformation_expression"]
-            .as_str()
-            .map_or_else(|| ".".to_string(), String::from);
-
-        let max_programs_per_thread = config["max_programs_per_thread"]
-            .as_u64()
-            .unwrap_or(MAX_PROGRAMS_PER_THREAD as u64)
-            .try_into()
-            .map_err(|e| Error::ConfigurationError(format!("Invalid max_programs_per_thread: {e}")))?;
-
-        let validation_data = config.get("validation_data")
-            .ok_or_else(|| Error::ConfigurationError("validation_data is required".to_string()))?;
+        let PayloadTransformerConfig {
+            transformation_expression: expression,
+            max_programs_per_thread,
+            validation_data,
+            input_schema,
+            output_schema,
+        } = parse_config(config)?;
 
-        Self::validate_expression(&expression, validation_data)
+        Self::validate_expression(&expression, &validation_data)
             .map_err(|e| Error::ConfigurationError(e.to_string()))?;
 
+        let parse_schema = |key: &str, schema: Option<Value>| {
+            schema
+                .map(|schema| {
+                    JsonSchema::from_json(&schema)
+                        .map_err(|e| Error::ConfigurationError(format!("Invalid {key}: {e}")))
+                })
+                .transpose()
+        };
+
         Ok(PayloadTransformer {
             expression,
             max_programs_per_thread,
+            input_schema: parse_schema("input_schema", input_schema)?,
+            output_schema: parse_schema("output_schema", output_schema)?,
         })
     }
 
+    fn config_schema() -> Option<Value> {
+        Some(config_schema::<PayloadTransformerConfig>())
+    }
+
     fn execute(&self, context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
         println!("PayloadTransformer {}: input={input:?}", context.node_id);
 
@@ -441,10 +485,14 @@ impl Component for PayloadTransformer {
     }
 
     fn input_type(&self) -> DataType {
-        DataType::Json
+        self.input_schema
+            .clone()
+            .map_or(DataType::Json, |schema| DataType::JsonSchema(Box::new(schema)))
     }
 
     fn output_type(&self) -> DataType {
-        DataType::Json
+        self.output_schema
+            .clone()
+            .map_or(DataType::Json, |schema| DataType::JsonSchema(Box::new(schema)))
     }
 }
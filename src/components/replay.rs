@@ -1,44 +1,51 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
 use serde_json::Value;
-use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader, Read, Seek};
-use std::sync::Mutex;
 
 use crate::cache::DAGResult;
-use crate::component::{Component, Data, DataType, Error};
-use crate::dag::{DAGError, NodeExecutionContext, RequestId};
-use moka::sync::Cache as MokaCache;
-
-pub(crate) type RequestPosition = u64;
-
-const MAX_POSITION_CACHE_SIZE: u64 = 10_000;
+use crate::component::{config_schema, parse_config, Component, Data, DataType, Error};
+use crate::dag::{target_outputs, DAGError, NodeExecutionContext, NodeID, Projection, RequestId};
+use crate::history::{HistoryError, HistoryStore, JsonlHistory};
 
 /// Replay a DAG from a history file. This essentially pre-populates the
 /// inputs to your DAG. You can then filter to the specific nodes you want.
 ///
-/// Instead of caching the history output itself, this demo Component caches
-/// the positions of the lines in the file so that the next seek is instant.
-///
-/// This Component is not optimized for read performance.
+/// Results are found through the history's request ID index, which is
+/// loaded when the Component is configured and shared with a `Cache`
+/// appending to the same file, so the lookup is a single seek whichever
+/// segment of the history they're in.
 pub struct Replay {
-    reader: Mutex<BufReader<File>>,
-    position_cache: MokaCache<RequestId, RequestPosition>,
+    history: JsonlHistory,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReplayConfig {
+    /// The history file a `Cache` appends to
+    pub history_path: String,
 }
 
 impl Component for Replay {
     fn configure(config: Value) -> Result<Self, Error> {
-        let history_path = config
-            .get("history_path")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| Error::ConfigurationError("history_path is required".to_string()))?;
+        let ReplayConfig { history_path } = parse_config(config)?;
 
-        let file = File::open(history_path).map_err(|e| {
+        let history = JsonlHistory::new(&history_path);
+        let segments = history.segments().map_err(|e| {
             Error::ConfigurationError(format!("Failed to open history file: {e}"))
         })?;
-        let reader = Mutex::new(BufReader::new(file));
-        let position_cache = MokaCache::new(MAX_POSITION_CACHE_SIZE);
+        if segments.is_empty() {
+            return Err(Error::ConfigurationError(format!(
+                "Failed to open history file: no history at {history_path}"
+            )));
+        }
+        history.load_index().map_err(|e| {
+            Error::ConfigurationError(format!("Failed to load history index: {e}"))
+        })?;
 
-        Ok(Self { reader, position_cache })
+        Ok(Self { history })
+    }
+
+    fn config_schema() -> Option<Value> {
+        Some(config_schema::<ReplayConfig>())
     }
 
     fn input_type(&self) -> DataType {
@@ -67,7 +74,7 @@ impl Component for Replay {
                 reason: "request_id is required in input".to_string(),
             })?;
 
-        let target_nodes: Option<HashSet<String>> = input
+        let target_nodes: Option<Vec<NodeID>> = input
             .get("target_nodes")
             .and_then(|v| v.as_array())
             .map(|arr| {
@@ -88,12 +95,13 @@ impl Component for Replay {
                 request_id: request_id.to_string(),
             })?;
 
+        // The same selection as `DAG::execute_targets`, over results that
+        // were already produced
         let filtered_results = match target_nodes {
-            Some(nodes) => historical_result
-                .node_results
-                .into_iter()
-                .filter(|(id, _)| nodes.contains(id))
-                .collect(),
+            Some(nodes) => {
+                let outputs = target_outputs(&nodes);
+                Projection::Outputs(&outputs).apply(historical_result.node_results)
+            }
             _ => historical_result.node_results,
         };
 
@@ -107,42 +115,11 @@ impl Component for Replay {
 }
 
 impl Replay {
-    fn get_historical_result(&self, request_id: &RequestId) -> Result<Option<DAGResult>, std::io::Error> {
-        if let Some(pos) = self.position_cache.get(request_id) {
-            let mut reader = self.reader.lock().map_err(|_| {
-                std::io::Error::new(std::io::ErrorKind::Other, "Failed to acquire lock")
-            })?;
-            reader.seek(std::io::SeekFrom::Start(pos))?;
-            let mut line = String::new();
-            reader.read_line(&mut line)?;
-            if let Ok(result) = serde_json::from_str::<DAGResult>(&line) {
-                return Ok(Some(result));
-            }
-        }
-
-        let mut reader = self.reader.lock().map_err(|_| {
-            std::io::Error::new(std::io::ErrorKind::Other, "Failed to acquire lock")
-        })?;
-        reader.seek(std::io::SeekFrom::Start(0))?;
-
-        let mut searched = 0;
-        let mut current_pos = reader.stream_position()?;
-
-        for line in reader.by_ref().lines() {
-            let line = line?;
-            searched += 1;
-
-            if let Ok(result) = serde_json::from_str::<DAGResult>(&line) {
-                if result.request_id == *request_id {
-                    println!("Found matching line: {line}, and caching position");
-                    self.position_cache.insert(request_id.to_string(), current_pos);
-                    return Ok(Some(result));
-                }
-            }
-            current_pos += line.len() as u64 + 1; // +1 for newline
+    fn get_historical_result(&self, request_id: &RequestId) -> Result<Option<DAGResult>, HistoryError> {
+        let result = self.history.get(request_id)?;
+        if result.is_none() {
+            println!("Request {request_id} is not in the history");
         }
-
-        println!("Searched {searched} lines in history file");
-        Ok(None)
+        Ok(result)
     }
 }
@@ -1,8 +1,11 @@
 use std::collections::HashSet;
 
+use serde::Deserialize;
 use serde_json::{json, Value};
 
-use crate::component::{Component, Data, DataType, Error};
+use crate::component::{
+    config_schema, parse_config, Component, Data, DataType, Error, JsonSchema, ObjectSchema,
+};
 use crate::dag::{DAGError, NodeExecutionContext};
 
 pub struct WildcardProcessor {
@@ -10,28 +13,33 @@ pub struct WildcardProcessor {
     expected_output_keys: HashSet<String>,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct WildcardProcessorConfig {
+    /// Keys the input object must have
+    #[serde(default)]
+    pub expected_input_keys: HashSet<String>,
+    /// Keys copied from the input object into the output object, as `null`
+    /// if the input lacks them
+    #[serde(default)]
+    pub expected_output_keys: HashSet<String>,
+}
+
 impl Component for WildcardProcessor {
     fn configure(config: Value) -> Result<Self, Error> {
-        let expected_input_keys = config["expected_input_keys"]
-            .as_array()
-            .unwrap_or(&vec![])
-            .iter()
-            .filter_map(|v| v.as_str().map(String::from))
-            .collect();
-
-        let expected_output_keys = config["expected_output_keys"]
-            .as_array()
-            .unwrap_or(&vec![])
-            .iter()
-            .filter_map(|v| v.as_str().map(String::from))
-            .collect();
-
+        let WildcardProcessorConfig {
+            expected_input_keys,
+            expected_output_keys,
+        } = parse_config(config)?;
         Ok(WildcardProcessor {
             expected_input_keys,
             expected_output_keys,
         })
     }
 
+    fn config_schema() -> Option<Value> {
+        Some(config_schema::<WildcardProcessorConfig>())
+    }
+
     fn execute(&self, context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
         println!("WildcardProcessor {}: input={input:?}", context.node_id);
         match input {
@@ -69,10 +77,21 @@ impl Component for WildcardProcessor {
     }
 
     fn input_type(&self) -> DataType {
-        DataType::Json
+        object_with_keys(&self.expected_input_keys)
     }
 
     fn output_type(&self) -> DataType {
-        DataType::Json
+        object_with_keys(&self.expected_output_keys)
+    }
+}
+
+/// An object with the given keys, or any JSON if there are none
+fn object_with_keys(keys: &HashSet<String>) -> DataType {
+    if keys.is_empty() {
+        return DataType::Json;
     }
+    let object = keys.iter().fold(ObjectSchema::new(), |object, key| {
+        object.with_required_property(key.clone(), JsonSchema::Any)
+    });
+    DataType::JsonSchema(Box::new(JsonSchema::Object(object)))
 }
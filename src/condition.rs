@@ -0,0 +1,94 @@
+use indexmap::IndexMap;
+use jq_rs::compile;
+use serde_json::Value;
+use std::cell::RefCell;
+
+use crate::component::Data;
+
+/// The maximum number of predicates to keep compiled per thread.
+const MAX_PREDICATES_PER_THREAD: usize = 100;
+
+thread_local! {
+    static COMPILED_PREDICATES: RefCell<IndexMap<String, jq_rs::JqProgram>> = RefCell::new(IndexMap::new());
+}
+
+/// A node's `when` predicate: a jq expression evaluated against the node's
+/// input (as plain JSON, see `Data::to_json`) right before it would execute.
+///
+/// As in jq itself, the predicate holds unless it yields `false` or `null`.
+/// An expression yielding no value at all (e.g. `select(...)` filtering the
+/// input out) doesn't hold either.
+///
+/// Unlike `PayloadTransformer` programs, predicates aren't validated against
+/// sample data for termination, so keep them to simple tests on the input;
+/// the node's timeout still applies while they are evaluated.
+#[derive(Debug, Clone)]
+pub struct Condition {
+    expression: String,
+}
+
+impl Condition {
+    /// Compiles the expression once, so that syntax errors surface when the
+    /// DAG is built rather than when the node runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression is not a valid jq program.
+    pub fn new(expression: impl Into<String>) -> Result<Self, String> {
+        let expression = expression.into();
+        compile(&expression).map_err(|e| format!("Invalid condition {expression:?}: {e}"))?;
+        Ok(Self { expression })
+    }
+
+    #[must_use]
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Evaluates the predicate against `input`.
+    ///
+    /// Compiled programs aren't `Send`, so like `PayloadTransformer` we keep
+    /// them in a bounded per-thread cache.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if jq fails on the input or yields more than one value.
+    pub fn evaluate(&self, input: &Data) -> Result<bool, String> {
+        let input_str = serde_json::to_string(&input.to_json())
+            .map_err(|e| format!("Failed to serialize condition input: {e}"))?;
+
+        let output_str = COMPILED_PREDICATES.with(|programs| {
+            let mut programs = programs.borrow_mut();
+            if !programs.contains_key(&self.expression) {
+                if programs.len() >= MAX_PREDICATES_PER_THREAD {
+                    programs.shift_remove_index(0);
+                }
+                let program = compile(&self.expression)
+                    .map_err(|e| format!("Failed to compile condition: {e}"))?;
+                programs.insert(self.expression.clone(), program);
+            }
+
+            let program = programs
+                .get_mut(&self.expression)
+                .ok_or_else(|| "Condition not found after insertion".to_string())?;
+            program
+                .run(&input_str)
+                .map_err(|e| format!("Failed to evaluate condition: {e}"))
+        })?;
+
+        let mut outputs = serde_json::Deserializer::from_str(&output_str).into_iter::<Value>();
+        let holds = match outputs.next() {
+            Some(Ok(value)) => !matches!(value, Value::Null | Value::Bool(false)),
+            Some(Err(e)) => return Err(format!("Failed to parse condition output: {e}")),
+            None => false,
+        };
+        if outputs.next().is_some() {
+            return Err(format!(
+                "Condition {:?} must yield a single value, got: {}",
+                self.expression,
+                output_str.trim()
+            ));
+        }
+        Ok(holds)
+    }
+}
@@ -1,11 +1,15 @@
 use futures::StreamExt;
 use indexmap::IndexMap;
+use rand::Rng;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::sync::RwLock;
 use std::time::Duration;
 use std::time::Instant;
@@ -16,16 +20,35 @@ use uuid::Uuid;
 
 use crate::cache::Cache;
 use crate::cache::DAGResult;
+use crate::cache::NodeCacheKey;
+use crate::cache::ResultKey;
+use crate::coercion::{Coercion, CoercionRegistry};
 use crate::component::Registry;
 use crate::component::{Component, Data, DataType};
+use crate::condition::Condition;
 use crate::dagir::Edge;
+use crate::dagir::NodeIR;
 use crate::dagir::DAGIR;
+use crate::dagir::in_namespace;
+
+mod map;
+mod retention;
+mod scheduling;
+mod sub_dag;
+use map::MapNode;
+pub use map::MAP_COMPONENT_TYPE;
+use retention::OutputRetention;
+pub(crate) use retention::{target_outputs, Projection};
+use scheduling::{ConcurrencyLimiter, Permit, SchedulingKey};
+pub use scheduling::set_global_node_limit;
+use sub_dag::SubDagNode;
+pub use sub_dag::SUB_DAG_COMPONENT_TYPE;
 
 pub type RequestId = String;
 
 pub(crate) type NodeID = String;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum DAGError {
     /// Represents a type mismatch error.
     TypeMismatch {
@@ -56,6 +79,30 @@ pub enum DAGError {
         expected: DataType,
         received: DataType,
     },
+    /// Represents a node that was cancelled because the DAG aborted.
+    Cancelled { node_id: NodeID },
+    /// Represents a failure that may succeed if the node is retried,
+    /// e.g. a dropped connection to a remote endpoint.
+    TransientError { node_id: NodeID, reason: String },
+    /// Represents a node that still failed after being retried.
+    FailedAfterRetries {
+        node_id: NodeID,
+        attempts: u32,
+        last_error: Box<DAGError>,
+    },
+    /// Represents a node that was skipped because a dependency didn't succeed.
+    DependencyFailed {
+        node_id: NodeID,
+        dependency_id: NodeID,
+    },
+    /// Represents a node that ran longer than its timeout.
+    NodeTimeout { node_id: NodeID, timeout_ms: u64 },
+    /// Represents a DAG that didn't finish before its deadline; the nodes
+    /// that hadn't completed were cancelled.
+    DeadlineExceeded {
+        deadline_ms: u64,
+        pending_nodes: Vec<NodeID>,
+    },
 }
 
 impl std::fmt::Display for DAGError {
@@ -104,17 +151,110 @@ impl std::fmt::Display for DAGError {
                     "Type system failure in component {component}: Expected {expected:?}, received {received:?}"
                 )
             }
+            DAGError::Cancelled { node_id } => {
+                write!(f, "Node {node_id}: Cancelled because the DAG was aborted")
+            }
+            DAGError::TransientError { node_id, reason } => {
+                write!(f, "Node {node_id}: Transient failure. Reason: {reason}")
+            }
+            DAGError::FailedAfterRetries {
+                node_id,
+                attempts,
+                last_error,
+            } => {
+                write!(
+                    f,
+                    "Node {node_id}: Failed after {attempts} attempts. Last error: {last_error}"
+                )
+            }
+            DAGError::DependencyFailed {
+                node_id,
+                dependency_id,
+            } => {
+                write!(
+                    f,
+                    "Node {node_id}: Skipped because dependency {dependency_id} did not succeed"
+                )
+            }
+            DAGError::NodeTimeout {
+                node_id,
+                timeout_ms,
+            } => {
+                write!(
+                    f,
+                    "Node {node_id}: Node execution timed out after {timeout_ms}ms"
+                )
+            }
+            DAGError::DeadlineExceeded {
+                deadline_ms,
+                pending_nodes,
+            } => {
+                write!(
+                    f,
+                    "DAG deadline of {deadline_ms}ms exceeded with pending nodes: {}",
+                    pending_nodes.join(", ")
+                )
+            }
         }
     }
 }
 
 impl std::error::Error for DAGError {}
 
+impl DAGError {
+    /// Whether retrying the failed node could plausibly succeed.
+    #[must_use]
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            DAGError::TransientError { .. } | DAGError::NodeTimeout { .. }
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct DAGSettings {
+    /// Timeout for every node that doesn't set its own `timeout_ms`
     pub per_node_timeout_ms: Option<u64>,
     pub enable_memory_cache: bool,
     pub enable_history: bool,
+    /// Retry policy for every node that doesn't set its own
+    #[serde(default)]
+    pub retry_policy: Option<RetryPolicy>,
+    /// Deadline for a whole execution, after which remaining nodes are cancelled
+    #[serde(default)]
+    pub dag_deadline_ms: Option<u64>,
+    /// Keep executing independent branches when a node fails, skipping only
+    /// its dependents, instead of aborting the whole DAG
+    #[serde(default)]
+    pub continue_on_error: bool,
+    /// How many nodes of one execution may execute at once, counting those
+    /// of the sub-DAGs of `Map` and `SubDag` nodes. Nodes waiting for their
+    /// turn go by `priority`, then by `critical_path_first`, then by
+    /// topological order.
+    #[serde(default)]
+    pub max_concurrent_nodes: Option<usize>,
+    /// Among nodes of equal `priority` waiting to execute, favor those with
+    /// the longest chain of dependents
+    #[serde(default)]
+    pub critical_path_first: bool,
+    /// Return every node's output, keyed by node ID, even if the DAG
+    /// declares its `outputs`. Intermediate outputs are then kept until the
+    /// end of the execution, and results aren't cached.
+    #[serde(default)]
+    pub debug_all_nodes: bool,
+    /// Apply the registry's coercions on edges whose source output isn't
+    /// compatible with the input of their target, instead of rejecting the
+    /// DAG. They're listed by `DAG::implicit_conversions`.
+    #[serde(default)]
+    pub implicit_coercions: bool,
+    /// Accept edges whose source output may only sometimes fit the input of
+    /// their target, e.g. a `Union` of which one type does, `Any`, or `Json`
+    /// into a `JsonSchema`; values are checked when the node runs instead.
+    /// See `DataType::can_flow_into`.
+    #[serde(default)]
+    pub optimistic_type_checks: bool,
 }
 
 impl DAGSettings {
@@ -133,12 +273,60 @@ impl DAGSettings {
         self.per_node_timeout_ms
     }
 
+    #[must_use]
+    pub fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.retry_policy.as_ref()
+    }
+
+    #[must_use]
+    pub fn dag_deadline_ms(&self) -> Option<u64> {
+        self.dag_deadline_ms
+    }
+
+    #[must_use]
+    pub fn continue_on_error(&self) -> bool {
+        self.continue_on_error
+    }
+
+    #[must_use]
+    pub fn max_concurrent_nodes(&self) -> Option<usize> {
+        self.max_concurrent_nodes
+    }
+
+    #[must_use]
+    pub fn critical_path_first(&self) -> bool {
+        self.critical_path_first
+    }
+
+    #[must_use]
+    pub fn debug_all_nodes(&self) -> bool {
+        self.debug_all_nodes
+    }
+
+    #[must_use]
+    pub fn implicit_coercions(&self) -> bool {
+        self.implicit_coercions
+    }
+
+    #[must_use]
+    pub fn optimistic_type_checks(&self) -> bool {
+        self.optimistic_type_checks
+    }
+
     #[must_use]
     pub fn cache_off() -> Self {
         Self {
             per_node_timeout_ms: Some(200),
             enable_memory_cache: false,
             enable_history: false,
+            retry_policy: None,
+            dag_deadline_ms: None,
+            continue_on_error: false,
+            max_concurrent_nodes: None,
+            critical_path_first: false,
+            debug_all_nodes: false,
+            implicit_coercions: false,
+            optimistic_type_checks: false,
         }
     }
 }
@@ -149,21 +337,490 @@ impl Default for DAGSettings {
             per_node_timeout_ms: Some(200),
             enable_memory_cache: true,
             enable_history: true,
+            retry_policy: None,
+            dag_deadline_ms: None,
+            continue_on_error: false,
+            max_concurrent_nodes: None,
+            critical_path_first: false,
+            debug_all_nodes: false,
+            implicit_coercions: false,
+            optimistic_type_checks: false,
+        }
+    }
+}
+
+/// How a failing node is retried. Each attempt gets the full per-node
+/// timeout, and the backoff delay is waited between attempts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+    #[serde(default)]
+    pub backoff: Backoff,
+    /// Randomizes each delay to between half and all of its computed value,
+    /// so that nodes failing together don't retry together
+    #[serde(default)]
+    pub jitter: bool,
+    #[serde(default)]
+    pub retry_on: RetryOn,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Backoff {
+    Fixed {
+        delay_ms: u64,
+    },
+    Exponential {
+        initial_delay_ms: u64,
+        multiplier: f64,
+        max_delay_ms: u64,
+    },
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff::Exponential {
+            initial_delay_ms: 10,
+            multiplier: 2.0,
+            max_delay_ms: 1_000,
+        }
+    }
+}
+
+/// Which errors are worth retrying
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryOn {
+    /// Only errors where `DAGError::is_transient` holds (timeouts, `TransientError`)
+    #[default]
+    Transient,
+    /// Transient errors plus any `ExecutionError` reported by a Component
+    ExecutionErrors,
+}
+
+impl RetryPolicy {
+    #[must_use]
+    pub fn new(max_attempts: u32, backoff: Backoff) -> Self {
+        Self {
+            max_attempts,
+            backoff,
+            jitter: false,
+            retry_on: RetryOn::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_jitter(mut self) -> Self {
+        self.jitter = true;
+        self
+    }
+
+    #[must_use]
+    pub fn with_retry_on(mut self, retry_on: RetryOn) -> Self {
+        self.retry_on = retry_on;
+        self
+    }
+
+    /// Whether a node that failed `attempt` times with `error` should run again.
+    #[must_use]
+    pub fn should_retry(&self, attempt: u32, error: &DAGError) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+        match self.retry_on {
+            RetryOn::Transient => error.is_transient(),
+            RetryOn::ExecutionErrors => {
+                error.is_transient() || matches!(error, DAGError::ExecutionError { .. })
+            }
+        }
+    }
+
+    /// The delay to wait after the given (1-based) failed attempt.
+    #[must_use]
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let delay_ms = match &self.backoff {
+            Backoff::Fixed { delay_ms } => *delay_ms,
+            Backoff::Exponential {
+                initial_delay_ms,
+                multiplier,
+                max_delay_ms,
+            } => {
+                let exponent = i32::try_from(attempt.saturating_sub(1)).unwrap_or(i32::MAX);
+                #[allow(
+                    clippy::cast_precision_loss,
+                    clippy::cast_possible_truncation,
+                    clippy::cast_sign_loss
+                )]
+                let delay = (*initial_delay_ms as f64 * multiplier.powi(exponent))
+                    .min(*max_delay_ms as f64) as u64;
+                delay
+            }
+        };
+
+        let delay = Duration::from_millis(delay_ms);
+        if self.jitter {
+            delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+        } else {
+            delay
+        }
+    }
+}
+
+/// Which dependencies a node needs outputs from before it executes. A
+/// dependency may have no output because its `when` condition didn't hold
+/// (or one of its own dependencies was bypassed), or because it failed while
+/// continuing on errors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JoinPolicy {
+    /// Every dependency must produce an output, otherwise the node is
+    /// bypassed (or skipped, if a dependency failed)
+    #[default]
+    All,
+    /// Waits for every dependency to finish, then executes with the outputs
+    /// of those that produced one, as long as at least one did
+    Any,
+    /// Executes as soon as the first dependency produces an output, with that
+    /// output alone, without waiting for the others
+    FirstAvailable,
+}
+
+/// Per-node execution settings, resolved from the node's own configuration
+/// and the DAG-wide `DAGSettings` when the DAG is built.
+#[derive(Debug, Clone, Default)]
+struct NodeSettings {
+    timeout_ms: Option<u64>,
+    retry_policy: Option<RetryPolicy>,
+    condition: Option<Condition>,
+    join: JoinPolicy,
+    priority: i32,
+    /// Whether the node counts against concurrency limits. `Map` and
+    /// `SubDag` nodes don't, as the nodes of their sub-DAGs do.
+    limited: bool,
+}
+
+impl NodeSettings {
+    /// The node's own settings, falling back to the DAG-wide ones
+    fn resolve(node: &NodeIR, settings: &DAGSettings) -> Result<Self, String> {
+        let condition = node
+            .when
+            .as_ref()
+            .map(Condition::new)
+            .transpose()
+            .map_err(|e| format!("Node {}: {e}", node.id))?;
+        Ok(NodeSettings {
+            timeout_ms: node.timeout_ms.or(settings.per_node_timeout_ms),
+            retry_policy: node.retry.clone().or_else(|| settings.retry_policy.clone()),
+            condition,
+            join: node.join,
+            priority: node.priority,
+            limited: node.component_type != MAP_COMPONENT_TYPE
+                && node.component_type != SUB_DAG_COMPONENT_TYPE,
+        })
+    }
+}
+
+/// What a `cacheable` node's outputs are memoized by, besides its input
+#[derive(Debug, Clone)]
+struct NodeMemo {
+    component_type: String,
+    config_hash: u64,
+    ttl: Option<Duration>,
+}
+
+impl NodeMemo {
+    /// The memo of `node`, whose config only names its sub-DAG, if any, so
+    /// that `sub_dag_hash` is memoized by too
+    fn new(node: &NodeIR, sub_dag_hash: Option<u64>) -> Self {
+        let config_hash = Registry::calculate_config_hash(&node.config);
+        Self {
+            component_type: node.component_type.clone(),
+            config_hash: sub_dag_hash.map_or(config_hash, |sub_dag_hash| {
+                DAG::with_sub_dag_hash(config_hash, sub_dag_hash)
+            }),
+            ttl: node.cache_ttl_ms.map(Duration::from_millis),
+        }
+    }
+}
+
+/// The memoized outputs of `cacheable` nodes, for an execution with the
+/// memory cache enabled
+#[derive(Clone)]
+struct NodeCache {
+    cache: Arc<Cache>,
+    memos: Arc<HashMap<NodeID, NodeMemo>>,
+}
+
+/// The coercions found for edges while type checking a DAG
+struct EdgeCoercions<'a> {
+    registry: Option<&'a CoercionRegistry>,
+    found: HashMap<Edge, Coercion>,
+    conversions: Vec<ImplicitConversion>,
+}
+
+impl<'a> EdgeCoercions<'a> {
+    fn new(registry: Option<&'a CoercionRegistry>) -> Self {
+        Self {
+            registry,
+            found: HashMap::new(),
+            conversions: Vec::new(),
         }
     }
+
+    fn find(&self, source: &DataType, target: &DataType) -> Option<&'a Coercion> {
+        self.registry?.find(source, target)
+    }
+
+    /// Records the coercion of the edge's output into `target`, returning
+    /// whether there is one
+    fn coerce(&mut self, edge: &Edge, source: &DataType, target: &DataType) -> bool {
+        let Some(coercion) = self.find(source, target) else {
+            return false;
+        };
+        println!(
+            "Implicit conversion on {} -> {}: {source:?} into {target:?} with {}",
+            edge.source,
+            edge.target,
+            coercion.name()
+        );
+        self.found.insert(edge.clone(), coercion.clone());
+        self.conversions.push(ImplicitConversion {
+            source: edge.source.clone(),
+            target: edge.target.clone(),
+            port: edge.port.clone(),
+            coercion: coercion.name().to_string(),
+            from: source.clone(),
+            to: target.clone(),
+        });
+        true
+    }
+}
+
+/// An edge on which the DAG converts the output of its source to fit the
+/// input of its target, as allowed by `DAGSettings::implicit_coercions`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImplicitConversion {
+    pub source: NodeID,
+    pub target: NodeID,
+    /// The named input port of the target, if the edge is bound to one
+    pub port: Option<String>,
+    /// The name of the coercion applied
+    pub coercion: String,
+    pub from: DataType,
+    pub to: DataType,
+}
+
+/// The dependencies whose outputs make up a node's input
+#[derive(Debug, Default)]
+struct InputEdges {
+    edges: Vec<Edge>,
+    /// Whether the outputs are passed as a `List` even if only one of the
+    /// node's dependencies produced one
+    as_list: bool,
+}
+
+/// Whether a node executes once its dependencies have settled
+enum Readiness {
+    Ready(InputEdges),
+    /// Not executed, because its join policy needed the output of a
+    /// dependency that was bypassed
+    Bypass { skipped_dependency: NodeID },
+}
+
+/// How a node's task ended, short of an error
+enum NodeOutcome {
+    Executed((NodeID, Data)),
+    NotExecuted(NodeStatus),
+}
+
+/// How a single node's execution ended
+#[derive(Debug, Clone)]
+pub enum NodeStatus {
+    Succeeded,
+    Failed(DAGError),
+    /// Not executed because a dependency didn't succeed
+    Skipped { failed_dependency: NodeID },
+    TimedOut { timeout_ms: u64 },
+    /// Not executed because its `when` condition didn't hold
+    ConditionFalse,
+    /// Not executed because a dependency was bypassed and the node's join
+    /// policy needed its output
+    Bypassed { skipped_dependency: NodeID },
+}
+
+impl NodeStatus {
+    fn from_error(error: &DAGError) -> Self {
+        match error {
+            DAGError::NodeTimeout { timeout_ms, .. } => NodeStatus::TimedOut {
+                timeout_ms: *timeout_ms,
+            },
+            DAGError::DependencyFailed { dependency_id, .. } => NodeStatus::Skipped {
+                failed_dependency: dependency_id.clone(),
+            },
+            error => NodeStatus::Failed(error.clone()),
+        }
+    }
+
+    #[must_use]
+    pub fn is_succeeded(&self) -> bool {
+        matches!(self, NodeStatus::Succeeded)
+    }
+
+    /// Whether the node was deliberately not executed, as opposed to failing
+    #[must_use]
+    pub fn is_bypassed(&self) -> bool {
+        matches!(self, NodeStatus::ConditionFalse | NodeStatus::Bypassed { .. })
+    }
+}
+
+/// The outcome of a DAG execution, with per-node details beyond the
+/// outputs themselves.
+#[derive(Debug, Clone)]
+pub struct ExecutionReport {
+    /// Outputs of the nodes that succeeded
+    pub results: IndexMap<NodeID, Data>,
+    /// How each node's execution ended. With `continue_on_error`, this is
+    /// where failed and skipped nodes are reported.
+    pub statuses: IndexMap<NodeID, NodeStatus>,
+    /// How many times each executed node ran (more than once if retried).
+    /// Empty when the results came from the cache.
+    pub attempts: IndexMap<NodeID, u32>,
+}
+
+impl ExecutionReport {
+    /// Whether every node either succeeded or was bypassed by a condition
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.statuses
+            .values()
+            .all(|status| status.is_succeeded() || status.is_bypassed())
+    }
+
+    /// The outputs of the nodes in `namespace`, including its nested
+    /// namespaces
+    #[must_use]
+    pub fn results_in_namespace(&self, namespace: &str) -> IndexMap<NodeID, Data> {
+        self.results
+            .iter()
+            .filter(|(node_id, _)| in_namespace(node_id, namespace))
+            .map(|(node_id, data)| (node_id.clone(), data.clone()))
+            .collect()
+    }
 }
 
 pub type Notifiers = Arc<RwLock<HashMap<NodeID, watch::Sender<()>>>>;
 pub type SharedResults = Arc<RwLock<IndexMap<NodeID, Data>>>;
+/// Whether an output type fits an input type, when type-checking edges
+type TypeFit = fn(&DataType, &DataType) -> bool;
+
+pub type RunningNodes = Arc<RwLock<HashSet<NodeID>>>;
+pub type NodeAttempts = Arc<RwLock<IndexMap<NodeID, u32>>>;
+pub type NodeStatuses = Arc<RwLock<IndexMap<NodeID, NodeStatus>>>;
+
+/// State shared by every node task of a single DAG execution.
+#[derive(Clone)]
+struct ExecutionState {
+    request_id: RequestId,
+    initial_inputs: Arc<HashMap<NodeID, Data>>,
+    notifiers: Notifiers,
+    shared_results: SharedResults,
+    /// Nodes whose Component is currently executing
+    running: RunningNodes,
+    /// How many times each node has been attempted so far
+    attempts: NodeAttempts,
+    statuses: NodeStatuses,
+    cancellation: CancellationToken,
+    start_time: Instant,
+    scheduling: Arc<Scheduling>,
+    /// When only some outputs are returned, frees the others early
+    retention: Option<Arc<OutputRetention>>,
+    node_cache: Option<NodeCache>,
+    /// The edges whose output is converted to fit their target's input
+    coercions: Arc<HashMap<Edge, Coercion>>,
+}
+
+/// The concurrency limits a single DAG execution is subject to
+#[derive(Default)]
+struct Scheduling {
+    dag_limiter: Option<Arc<ConcurrencyLimiter>>,
+    global_limiter: Option<Arc<ConcurrencyLimiter>>,
+    keys: HashMap<NodeID, SchedulingKey>,
+}
+
+/// An attempt at executing a node, started on its own task. It's stopped
+/// when dropped, e.g. when the DAG aborts or passes its deadline while it
+/// executes, so that an `AsyncComponent` doesn't keep running in the
+/// background.
+struct NodeAttempt {
+    handle: task::JoinHandle<Result<(NodeID, Data), DAGError>>,
+    /// Cancels this attempt only, and is cancelled along with the DAG
+    cancellation: CancellationToken,
+    /// Released when the attempt's Component returns, or it's stopped
+    permits: Arc<Mutex<Vec<Permit>>>,
+}
+
+impl NodeAttempt {
+    /// Signals the attempt's Component to stop, aborts its task and
+    /// releases its permits. Components executing on the blocking thread
+    /// pool keep running until they observe the cancellation.
+    fn stop(&self) {
+        self.cancellation.cancel();
+        self.handle.abort();
+        self.permits.lock().unwrap().clear();
+    }
+
+    /// Resolves once the attempt's task has finished
+    async fn stopped(&mut self) {
+        // Its result may have been awaited already
+        if !self.handle.is_finished() {
+            let _ = (&mut self.handle).await;
+        }
+    }
+}
+
+impl Drop for NodeAttempt {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Tracks a node as running until dropped, even when its task is aborted
+struct RunningNode<'a> {
+    node_id: &'a NodeID,
+    running: &'a RunningNodes,
+}
+
+impl<'a> RunningNode<'a> {
+    fn new(node_id: &'a NodeID, running: &'a RunningNodes) -> Self {
+        running.write().unwrap().insert(node_id.clone());
+        Self { node_id, running }
+    }
+}
+
+impl Drop for RunningNode<'_> {
+    fn drop(&mut self) {
+        self.running.write().unwrap().remove(self.node_id);
+    }
+}
 
 pub struct DAG {
     nodes: Arc<HashMap<NodeID, Arc<dyn Component>>>,
     edges: Arc<HashMap<NodeID, Vec<Edge>>>,
     initial_inputs: Arc<HashMap<NodeID, Data>>,
+    node_settings: Arc<HashMap<NodeID, NodeSettings>>,
+    /// The `cacheable` nodes
+    node_memos: Arc<HashMap<NodeID, NodeMemo>>,
     settings: DAGSettings,
     cache: Option<Arc<Cache>>,
     ir_hash: u64,
     alias: String,
+    /// The declared outputs, as the node and the name its output is
+    /// returned under
+    outputs: Vec<(NodeID, String)>,
+    /// The edges whose output is converted to fit their target's input
+    coercions: Arc<HashMap<Edge, Coercion>>,
+    implicit_conversions: Vec<ImplicitConversion>,
 }
 
 impl std::fmt::Debug for DAG {
@@ -174,8 +831,13 @@ impl std::fmt::Debug for DAG {
             .field("nodes", &self.nodes.keys().collect::<Vec<_>>())
             .field("edge_count", &self.edges.len())
             .field("initial_inputs", &self.initial_inputs)
+            .field("node_settings", &self.node_settings)
+            .field("node_memos", &self.node_memos)
             .field("has_cache", &self.cache.is_some())
             .field("ir_hash", &self.ir_hash)
+            .field("outputs", &self.outputs)
+            .field("coercions", &self.coercions)
+            .field("implicit_conversions", &self.implicit_conversions)
             .finish()
     }
 }
@@ -195,17 +857,34 @@ impl DAG {
         registry: &Registry,
         settings: DAGSettings,
         cache: Option<Arc<Cache>>,
+    ) -> Result<Self, String> {
+        Self::from_ir_within(ir, registry, settings, cache, &[])
+    }
+
+    /// Creates a DAG like `from_ir`, as the sub-DAG of `Map` or `SubDag`
+    /// nodes nested in the DAGs with `enclosing_aliases`.
+    fn from_ir_within(
+        ir: &DAGIR,
+        registry: &Registry,
+        settings: DAGSettings,
+        cache: Option<Arc<Cache>>,
+        enclosing_aliases: &[String],
     ) -> Result<Self, String> {
         let start = Instant::now();
         println!("DAGSettings: {settings:?}");
+        if settings.max_concurrent_nodes == Some(0) {
+            return Err("max_concurrent_nodes must be a positive integer".to_string());
+        }
 
         let hash_start = Instant::now();
-        let ir_hash = ir.calculate_hash();
+        let mut ir_hash = ir.calculate_hash();
         println!("Hash calculation took {:?}", hash_start.elapsed());
 
         let mut nodes = HashMap::new();
         let mut edges: HashMap<NodeID, Vec<Edge>> = HashMap::new();
         let mut initial_inputs = HashMap::new();
+        let mut node_settings = HashMap::new();
+        let mut node_memos = HashMap::new();
         let mut node_ids = HashSet::new();
 
         let dup_start = Instant::now();
@@ -225,10 +904,12 @@ impl DAG {
             println!("Starting component creation for node {}", node.id);
             let component_start = Instant::now();
 
-            println!("Getting component from registry for node {}", node.id);
-            let component = registry
-                .get_configured(&node.component_type, &node.config)
-                .map_err(|e| format!("Failed to get component for node {}: {}", node.id, e))?;
+            let enclosing_aliases = [enclosing_aliases, std::slice::from_ref(&ir.alias)].concat();
+            let (component, sub_dag_hash) =
+                Self::create_component(node, registry, &settings, &enclosing_aliases)?;
+            if let Some(sub_dag_hash) = sub_dag_hash {
+                ir_hash = Self::with_sub_dag_hash(ir_hash, sub_dag_hash);
+            }
 
             println!("Component creation successful for node {}", node.id);
 
@@ -260,6 +941,10 @@ impl DAG {
                 }
             }
 
+            node_settings.insert(node.id.clone(), NodeSettings::resolve(node, &settings)?);
+            if node.cacheable {
+                node_memos.insert(node.id.clone(), NodeMemo::new(node, sub_dag_hash));
+            }
             nodes.insert(node.id.clone(), component);
             println!(
                 "Node {} setup took {:?}",
@@ -269,22 +954,342 @@ impl DAG {
         }
         println!("Total component creation took {:?}", comp_start.elapsed());
 
+        let type_check_start = Instant::now();
+        let (coercions, implicit_conversions) =
+            Self::type_check_edges(&edges, &nodes, &node_settings, registry, &settings)?;
+        println!("Edge type check took {:?}", type_check_start.elapsed());
+
         let dag = Self {
             nodes: Arc::new(nodes),
             edges: Arc::new(edges),
             initial_inputs: Arc::new(initial_inputs),
+            node_settings: Arc::new(node_settings),
+            node_memos: Arc::new(node_memos),
             settings,
             cache,
             ir_hash,
             alias: ir.alias.clone(),
+            outputs: ir.outputs.clone(),
+            coercions: Arc::new(coercions),
+            implicit_conversions,
         };
 
         println!("Total DAG setup took {:?}", start.elapsed());
         Ok(dag)
     }
 
+    /// Gets the node's Component from the registry, or builds it if it's
+    /// one of the built-in nodes, along with the hash of its sub-DAG if any
+    fn create_component(
+        node: &NodeIR,
+        registry: &Registry,
+        settings: &DAGSettings,
+        enclosing_aliases: &[String],
+    ) -> Result<(Arc<dyn Component>, Option<u64>), String> {
+        if node.component_type == MAP_COMPONENT_TYPE {
+            println!("Building sub-DAG for Map node {}", node.id);
+            let map = MapNode::build(&node.id, &node.config, registry, settings, enclosing_aliases)?;
+            let dag_hash = map.dag_hash();
+            return Ok((Arc::new(map), Some(dag_hash)));
+        }
+        if node.component_type == SUB_DAG_COMPONENT_TYPE {
+            println!("Building sub-DAG for SubDag node {}", node.id);
+            let sub_dag =
+                SubDagNode::build(&node.id, &node.config, registry, settings, enclosing_aliases)?;
+            let dag_hash = sub_dag.dag_hash();
+            return Ok((Arc::new(sub_dag), Some(dag_hash)));
+        }
+
+        println!("Getting component from registry for node {}", node.id);
+        registry
+            .get_configured(&node.component_type, &node.config)
+            .map(|component| (component, None))
+            .map_err(|e| format!("Failed to get component for node {}: {}", node.id, e))
+    }
+
+    /// Combines `hash` with that of a sub-DAG, whose registered alias may
+    /// stand for another DAG than before
+    fn with_sub_dag_hash(hash: u64, sub_dag_hash: u64) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hash.hash(&mut hasher);
+        sub_dag_hash.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Nodes without dependencies, sorted
+    fn root_nodes(&self) -> Vec<&NodeID> {
+        let mut roots: Vec<_> = self
+            .nodes
+            .keys()
+            .filter(|id| !self.edges.contains_key(*id))
+            .collect();
+        roots.sort();
+        roots
+    }
+
+    /// Nodes that no other node depends on, sorted
+    fn leaf_nodes(&self) -> Vec<&NodeID> {
+        let mut leaves: Vec<_> = self
+            .nodes
+            .keys()
+            .filter(|id| !self.edges.values().flatten().any(|edge| &edge.source == *id))
+            .collect();
+        leaves.sort();
+        leaves
+    }
+
+    /// Statically checks every edge of the DAG, comparing the output type of
+    /// each dependency with the input type of the node that consumes it, so
+    /// that a mis-wired DAG is rejected before any node runs.
+    ///
+    /// Every incompatible edge is reported, not just the first one.
+    ///
+    /// An output fits an input if it `is_compatible_with` it, or if it
+    /// `can_flow_into` it with `DAGSettings::optimistic_type_checks`. With
+    /// `DAGSettings::implicit_coercions`, an edge that doesn't fit is accepted
+    /// if one of the registry's coercions converts its output into the
+    /// target's input instead; the coercions found are returned by edge, and
+    /// as the conversions they make.
+    fn type_check_edges(
+        edges: &HashMap<NodeID, Vec<Edge>>,
+        nodes: &HashMap<NodeID, Arc<dyn Component>>,
+        node_settings: &HashMap<NodeID, NodeSettings>,
+        registry: &Registry,
+        settings: &DAGSettings,
+    ) -> Result<(HashMap<Edge, Coercion>, Vec<ImplicitConversion>), String> {
+        let mut targets: Vec<_> = edges.keys().collect();
+        targets.sort();
+
+        let coercions = settings.implicit_coercions.then(|| registry.coercions());
+        let fits: TypeFit = if settings.optimistic_type_checks {
+            DataType::can_flow_into
+        } else {
+            DataType::is_compatible_with
+        };
+        let mut errors = Vec::new();
+        let mut edge_coercions = EdgeCoercions::new(coercions);
+        for node_id in targets {
+            let node_edges = &edges[node_id];
+            let join = node_settings
+                .get(node_id)
+                .map_or(JoinPolicy::default(), |settings| settings.join);
+            if node_edges.iter().any(|edge| edge.port.is_some()) {
+                Self::type_check_input_ports(
+                    node_id,
+                    node_edges,
+                    join,
+                    nodes,
+                    fits,
+                    &mut edge_coercions,
+                    &mut errors,
+                );
+            } else if join == JoinPolicy::FirstAvailable {
+                // Whichever dependency comes first, its output is passed as is
+                for edge in node_edges {
+                    Self::type_check_positional_inputs(
+                        node_id,
+                        std::slice::from_ref(edge),
+                        nodes,
+                        fits,
+                        &mut edge_coercions,
+                        &mut errors,
+                    );
+                }
+            } else {
+                Self::type_check_positional_inputs(
+                    node_id,
+                    node_edges,
+                    nodes,
+                    fits,
+                    &mut edge_coercions,
+                    &mut errors,
+                );
+            }
+        }
+
+        if errors.is_empty() {
+            Ok((edge_coercions.found, edge_coercions.conversions))
+        } else {
+            Err(format!(
+                "DAG has {} type error(s):\n  - {}",
+                errors.len(),
+                errors.join("\n  - ")
+            ))
+        }
+    }
+
+    /// A single dependency's output is passed as is, while the outputs of
+    /// several dependencies are passed as a `List` in edge order.
+    fn type_check_positional_inputs(
+        node_id: &NodeID,
+        edges: &[Edge],
+        nodes: &HashMap<NodeID, Arc<dyn Component>>,
+        fits: TypeFit,
+        coercions: &mut EdgeCoercions,
+        errors: &mut Vec<String>,
+    ) {
+        let input_type = nodes[node_id].input_type();
+
+        if let [edge] = edges {
+            let source_type = nodes[&edge.source].output_type();
+            if !fits(&source_type, &input_type)
+                && !coercions.coerce(edge, &source_type, &input_type)
+            {
+                errors.push(format!(
+                    "{} -> {node_id}: output {source_type:?} is incompatible with input {input_type:?}",
+                    edge.source
+                ));
+            }
+            return;
+        }
+
+        let list_element_types: Vec<&DataType> = match &input_type {
+            DataType::List(element_type) => vec![element_type.as_ref()],
+            DataType::Any => vec![&input_type],
+            DataType::Union(types) => types
+                .iter()
+                .filter_map(|t| match t {
+                    DataType::List(element_type) => Some(element_type.as_ref()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        if list_element_types.is_empty() {
+            errors.push(format!(
+                "{node_id}: has {} dependencies, so its input is a List, but it only accepts {input_type:?}",
+                edges.len()
+            ));
+            return;
+        }
+
+        let source_types: Vec<(&NodeID, DataType)> = edges
+            .iter()
+            .map(|edge| (&edge.source, nodes[&edge.source].output_type()))
+            .collect();
+        let accepts_all = list_element_types.iter().any(|element_type| {
+            source_types
+                .iter()
+                .all(|(_, source_type)| fits(source_type, element_type))
+        });
+        if accepts_all {
+            return;
+        }
+
+        // The first list type that each output flows or converts into
+        let coercible = list_element_types.iter().find(|element_type| {
+            source_types.iter().all(|(_, source_type)| {
+                fits(source_type, element_type)
+                    || coercions.find(source_type, element_type).is_some()
+            })
+        });
+        if let Some(element_type) = coercible {
+            for (edge, (_, source_type)) in edges.iter().zip(&source_types) {
+                if !fits(source_type, element_type) {
+                    coercions.coerce(edge, source_type, element_type);
+                }
+            }
+            return;
+        }
+
+        let errors_before = errors.len();
+        for (source, source_type) in source_types {
+            if !list_element_types
+                .iter()
+                .any(|element_type| fits(&source_type, element_type))
+            {
+                errors.push(format!(
+                    "{source} -> {node_id}: output {source_type:?} is incompatible with list input {input_type:?}"
+                ));
+            }
+        }
+        // Each output fits some list type, but no list type fits them all
+        if errors.len() == errors_before {
+            errors.push(format!(
+                "{node_id}: no single list type in {input_type:?} accepts all of its dependencies' outputs"
+            ));
+        }
+    }
+
+    /// Checks the dependencies bound to a node's named input ports against
+    /// the ports its Component declares (a `DataType::Record` input type, or a
+    /// `DataType::Map` accepting any port names, possibly within a `Union`).
+    fn type_check_input_ports(
+        node_id: &NodeID,
+        edges: &[Edge],
+        join: JoinPolicy,
+        nodes: &HashMap<NodeID, Arc<dyn Component>>,
+        fits: TypeFit,
+        coercions: &mut EdgeCoercions,
+        errors: &mut Vec<String>,
+    ) {
+        let component_input_type = nodes[node_id].input_type();
+        let input_type = match &component_input_type {
+            DataType::Union(types) => types
+                .iter()
+                .find(|t| matches!(t, DataType::Record(_) | DataType::Map(_)))
+                .unwrap_or(&component_input_type),
+            other => other,
+        };
+        for edge in edges {
+            let port = edge.port.as_deref().unwrap_or(&edge.source);
+            let port_type = match input_type {
+                DataType::Record(ports) => {
+                    if let Some(port_type) = ports.get(port) {
+                        port_type
+                    } else {
+                        errors.push(format!(
+                            "{} -> {node_id}: unknown input port {port}. Expected ports: {:?}",
+                            edge.source,
+                            ports.keys().collect::<Vec<_>>()
+                        ));
+                        continue;
+                    }
+                }
+                DataType::Map(value_type) => value_type.as_ref(),
+                DataType::Any => input_type,
+                other => {
+                    errors.push(format!(
+                        "{} -> {node_id}: bound to input port {port}, but the component has no named input ports (input type {other:?})",
+                        edge.source
+                    ));
+                    continue;
+                }
+            };
+
+            let source_type = nodes[&edge.source].output_type();
+            if !fits(&source_type, port_type)
+                && !coercions.coerce(edge, &source_type, port_type)
+            {
+                errors.push(format!(
+                    "{} -> {node_id}: input port {port} expects {port_type:?}, but the output is {source_type:?}",
+                    edge.source
+                ));
+            }
+        }
+
+        if let DataType::Record(ports) = input_type {
+            let missing: Vec<_> = ports
+                .keys()
+                .filter(|port| !edges.iter().any(|edge| edge.port.as_ref() == Some(*port)))
+                .collect();
+            if !missing.is_empty() {
+                errors.push(format!("{node_id}: unbound input ports: {missing:?}"));
+            } else if join == JoinPolicy::Any {
+                errors.push(format!(
+                    "{node_id}: joins on any dependency, which may leave some of its input ports {:?} unbound",
+                    ports.keys().collect::<Vec<_>>()
+                ));
+            }
+        }
+    }
+
     /// Execute the DAG with the given request ID, or a random UUID if none is provided.
     ///
+    /// Returns the outputs the DAG declares, under their names, executing
+    /// only the nodes they need; or every node's output keyed by node ID if
+    /// it declares none or `debug_all_nodes` is set.
+    ///
     /// # Errors
     ///
     /// Returns a `DAGError` if:
@@ -296,6 +1301,196 @@ impl DAG {
         &self,
         request_id: Option<RequestId>,
     ) -> Result<IndexMap<NodeID, Data>, DAGError> {
+        self.execute_with_report(request_id)
+            .await
+            .map(|report| report.results)
+    }
+
+    /// Execute the DAG like `execute`, additionally reporting the status of
+    /// each node and how many attempts it took.
+    ///
+    /// With `continue_on_error` set in the `DAGSettings`, node failures
+    /// don't fail the execution: the report's results hold the outputs of
+    /// the nodes that succeeded and its statuses say which nodes failed,
+    /// timed out or were skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DAGError` under the same conditions as `execute`.
+    pub async fn execute_with_report(
+        &self,
+        request_id: Option<RequestId>,
+    ) -> Result<ExecutionReport, DAGError> {
+        self.execute_with_initial_inputs(
+            request_id,
+            Arc::clone(&self.initial_inputs),
+            self.projection(),
+        )
+        .await
+    }
+
+    /// Execute the DAG like `execute`, with `inputs` for this request only:
+    /// they take the place of the `inputs` configured for the nodes they're
+    /// keyed by, while other nodes keep theirs. A single DAG can serve many
+    /// requests, concurrently too, without being rebuilt for each payload.
+    /// Cached results are keyed on these inputs.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DAGError` if:
+    /// - An input is keyed by a node that isn't in the DAG
+    /// - An input is for a node with dependencies, which get their input
+    ///   from those
+    /// - An input doesn't match the node's input type
+    /// - Execution fails as with `execute`
+    pub async fn execute_with_inputs(
+        &self,
+        request_id: Option<RequestId>,
+        inputs: HashMap<NodeID, Data>,
+    ) -> Result<IndexMap<NodeID, Data>, DAGError> {
+        self.execute_with_inputs_report(request_id, inputs)
+            .await
+            .map(|report| report.results)
+    }
+
+    /// Execute the DAG like `execute_with_inputs`, reporting on each node
+    /// like `execute_with_report`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DAGError` under the same conditions as `execute_with_inputs`.
+    pub async fn execute_with_inputs_report(
+        &self,
+        request_id: Option<RequestId>,
+        inputs: HashMap<NodeID, Data>,
+    ) -> Result<ExecutionReport, DAGError> {
+        let initial_inputs = self.runtime_inputs(inputs)?;
+        self.execute_with_initial_inputs(request_id, Arc::new(initial_inputs), self.projection())
+            .await
+    }
+
+    /// Execute only the `targets` and the nodes they depend on, directly or
+    /// not, returning the outputs of the targets keyed by node ID. Results
+    /// are cached apart from those of whole executions.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DAGError` if:
+    /// - No targets are given
+    /// - A target isn't a node of the DAG
+    /// - Execution fails as with `execute`
+    pub async fn execute_targets(
+        &self,
+        request_id: Option<RequestId>,
+        targets: &[NodeID],
+    ) -> Result<IndexMap<NodeID, Data>, DAGError> {
+        if targets.is_empty() {
+            return Err(DAGError::InvalidConfiguration(
+                "No target nodes given".to_string(),
+            ));
+        }
+        if let Some(missing) = targets.iter().find(|target| !self.nodes.contains_key(*target)) {
+            return Err(DAGError::NodeNotFound {
+                node: missing.clone(),
+            });
+        }
+
+        let outputs = target_outputs(targets);
+        self.execute_with_initial_inputs(
+            request_id,
+            Arc::clone(&self.initial_inputs),
+            Projection::Outputs(&outputs),
+        )
+        .await
+        .map(|report| report.results)
+    }
+
+    /// The outputs executions return, unless a sub-DAG's enclosing node
+    /// wants a particular one
+    fn projection(&self) -> Projection<'_> {
+        if self.outputs.is_empty() || self.settings.debug_all_nodes() {
+            Projection::AllNodes
+        } else {
+            Projection::Outputs(&self.outputs)
+        }
+    }
+
+    /// The hash that results of executions with `projection` are cached
+    /// under. Those returning other outputs than the DAG's own are cached
+    /// apart.
+    fn cache_hash(&self, projection: Projection) -> u64 {
+        if projection == self.projection() {
+            return self.ir_hash;
+        }
+        let mut hasher = DefaultHasher::new();
+        self.ir_hash.hash(&mut hasher);
+        projection.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Validates `inputs` for the DAG's nodes and puts them in place of the
+    /// ones the DAG was built with
+    fn runtime_inputs(
+        &self,
+        inputs: HashMap<NodeID, Data>,
+    ) -> Result<HashMap<NodeID, Data>, DAGError> {
+        let mut inputs: Vec<(NodeID, Data)> = inputs.into_iter().collect();
+        inputs.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut initial_inputs = (*self.initial_inputs).clone();
+        for (node_id, input) in inputs {
+            let Some(component) = self.nodes.get(&node_id) else {
+                return Err(DAGError::NodeNotFound { node: node_id });
+            };
+            if self.edges.contains_key(&node_id) {
+                return Err(DAGError::InvalidConfiguration(format!(
+                    "Node {node_id} gets its input from its dependencies, so it can't be given one"
+                )));
+            }
+            let expected = component.input_type();
+            if !input.validate_type(&expected) {
+                return Err(DAGError::TypeMismatch {
+                    node_id,
+                    expected,
+                    actual: input.get_type(),
+                });
+            }
+            initial_inputs.insert(node_id, input);
+        }
+        Ok(initial_inputs)
+    }
+
+    /// Execute the DAG like `execute_with_report`, with `initial_inputs` in
+    /// place of the inputs it was built with, returning the outputs in
+    /// `projection`.
+    pub(crate) async fn execute_with_initial_inputs(
+        &self,
+        request_id: Option<RequestId>,
+        initial_inputs: Arc<HashMap<NodeID, Data>>,
+        projection: Projection<'_>,
+    ) -> Result<ExecutionReport, DAGError> {
+        self.execute_within(
+            request_id,
+            initial_inputs,
+            projection,
+            CancellationToken::new(),
+            None,
+        )
+        .await
+    }
+
+    /// Execute the DAG like `execute_with_initial_inputs`, as the sub-DAG of
+    /// a node: cancelling the node's `cancellation` cancels every node of
+    /// the sub-DAG, and its nodes share the enclosing execution's
+    /// `dag_limiter`, if any.
+    pub(crate) async fn execute_within(
+        &self,
+        request_id: Option<RequestId>,
+        initial_inputs: Arc<HashMap<NodeID, Data>>,
+        projection: Projection<'_>,
+        cancellation: CancellationToken,
+        dag_limiter: Option<Arc<ConcurrencyLimiter>>,
+    ) -> Result<ExecutionReport, DAGError> {
         let start_time = Instant::now();
         let request_id = request_id.unwrap_or_else(|| Uuid::new_v4().to_string());
         println!(
@@ -304,38 +1499,83 @@ impl DAG {
             request_id
         );
 
-        if self.settings.enable_memory_cache() {
+        // Debugging returns outputs that the cache doesn't hold
+        let cacheable = self.outputs.is_empty() || !self.settings.debug_all_nodes();
+        let cache_hash = self.cache_hash(projection);
+        if self.settings.enable_memory_cache() && cacheable {
             if let Some(cache) = &self.cache {
-                if let Some(cached_result) =
-                    cache.get_cached_result(self.ir_hash, &self.initial_inputs)
-                {
+                if let Some(cached_result) = cache.get_cached_result(cache_hash, &initial_inputs) {
                     println!(
                         "[{:.3}s] Cache hit! Returning cached result",
                         start_time.elapsed().as_secs_f32()
                     );
-                    return Ok(cached_result.node_results);
+                    let statuses = projection
+                        .source_nodes(&cached_result.node_results)
+                        .into_iter()
+                        .map(|node_id| (node_id, NodeStatus::Succeeded))
+                        .collect();
+                    return Ok(ExecutionReport {
+                        results: cached_result.node_results,
+                        statuses,
+                        attempts: IndexMap::new(),
+                    });
                 }
             }
         }
 
-        let sorted_nodes = self.compute_execution_order(start_time.elapsed().as_secs_f32())?;
-
-        let (notifiers, shared_results) =
-            self.setup_execution_state(start_time.elapsed().as_secs_f32());
+        let mut sorted_nodes = self.compute_execution_order(start_time.elapsed().as_secs_f32())?;
+        let needed_nodes = projection.needed_nodes(&self.edges);
+        if let Some(needed_nodes) = &needed_nodes {
+            sorted_nodes.retain(|node_id| needed_nodes.contains(node_id));
+            println!(
+                "[{:.3}s] Executing the {} nodes the outputs need: {sorted_nodes:?}",
+                start_time.elapsed().as_secs_f32(),
+                sorted_nodes.len()
+            );
+        }
 
-        let final_results = self
-            .execute_nodes(
-                sorted_nodes,
-                notifiers,
-                shared_results,
-                start_time,
-                request_id.clone(),
-            )
-            .await?;
+        let scheduling = self.setup_scheduling(&sorted_nodes, dag_limiter);
+        let retention = needed_nodes
+            .and_then(|needed_nodes| OutputRetention::new(projection, &self.edges, &needed_nodes))
+            .map(Arc::new);
+        let state = Self::setup_execution_state(
+            request_id.clone(),
+            Arc::clone(&initial_inputs),
+            scheduling,
+            retention,
+            self.node_cache(),
+            Arc::clone(&self.coercions),
+            cancellation,
+            start_time,
+        );
 
-        if let Some(cache) = &self.cache {
-            self.handle_caching(
+        let attempts = Arc::clone(&state.attempts);
+        let statuses = Arc::clone(&state.statuses);
+        let final_results = projection.apply(self.execute_nodes(sorted_nodes, state).await?);
+        let attempts = attempts
+            .read()
+            .map(|attempts| attempts.clone())
+            .unwrap_or_default();
+        let statuses: IndexMap<NodeID, NodeStatus> = statuses
+            .read()
+            .map(|statuses| statuses.clone())
+            .unwrap_or_default();
+
+        // Partial results from a DAG that continued past errors aren't cached,
+        // so that a later request gets another chance at the failed nodes
+        let is_complete = statuses
+            .values()
+            .all(|status| status.is_succeeded() || status.is_bypassed());
+        if let (Some(cache), true) = (&self.cache, is_complete && cacheable) {
+            let key = ResultKey {
+                alias: self.alias.clone(),
+                ir_hash: self.ir_hash,
+                cache_hash,
+            };
+            Self::handle_caching(
                 cache,
+                key,
+                initial_inputs,
                 &final_results,
                 &request_id,
                 start_time,
@@ -346,7 +1586,11 @@ impl DAG {
             "[{:.3}s] DAG execution completed",
             start_time.elapsed().as_secs_f32()
         );
-        Ok(final_results)
+        Ok(ExecutionReport {
+            results: final_results,
+            statuses,
+            attempts,
+        })
     }
 
     fn compute_execution_order(&self, elapsed_secs: f32) -> Result<Vec<NodeID>, DAGError> {
@@ -406,21 +1650,90 @@ impl DAG {
         Ok(sorted_nodes)
     }
 
-    fn setup_execution_state(&self, elapsed_secs: f32) -> (Notifiers, SharedResults) {
+    /// The limits on how many nodes execute at once, with the enclosing
+    /// execution's `dag_limiter` for a sub-DAG. Without any, there is no
+    /// need for scheduling keys, as nodes never wait for each other.
+    fn setup_scheduling(
+        &self,
+        sorted_nodes: &[NodeID],
+        dag_limiter: Option<Arc<ConcurrencyLimiter>>,
+    ) -> Scheduling {
+        let dag_limiter = dag_limiter.or_else(|| {
+            self.settings
+                .max_concurrent_nodes()
+                .map(ConcurrencyLimiter::new)
+        });
+        let global_limiter = scheduling::global_limiter();
+        if dag_limiter.is_none() && global_limiter.is_none() {
+            return Scheduling::default();
+        }
+
+        let priorities = self
+            .node_settings
+            .iter()
+            .map(|(node_id, settings)| (node_id.clone(), settings.priority))
+            .collect();
+        Scheduling {
+            dag_limiter,
+            global_limiter,
+            keys: scheduling::scheduling_keys(
+                sorted_nodes,
+                &self.edges,
+                &priorities,
+                self.settings.critical_path_first(),
+            ),
+        }
+    }
+
+    /// The node cache, if any node is `cacheable` and the memory cache is
+    /// enabled
+    fn node_cache(&self) -> Option<NodeCache> {
+        if self.node_memos.is_empty() || !self.settings.enable_memory_cache() {
+            return None;
+        }
+        self.cache.as_ref().map(|cache| NodeCache {
+            cache: Arc::clone(cache),
+            memos: Arc::clone(&self.node_memos),
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn setup_execution_state(
+        request_id: RequestId,
+        initial_inputs: Arc<HashMap<NodeID, Data>>,
+        scheduling: Scheduling,
+        retention: Option<Arc<OutputRetention>>,
+        node_cache: Option<NodeCache>,
+        coercions: Arc<HashMap<Edge, Coercion>>,
+        cancellation: CancellationToken,
+        start_time: Instant,
+    ) -> ExecutionState {
+        let elapsed_secs = start_time.elapsed().as_secs_f32();
         println!("[{elapsed_secs:.3}s] Setting up notification channels");
 
         let mut results = IndexMap::new();
-        results.extend((*self.initial_inputs).clone());
+        results.extend((*initial_inputs).clone());
         println!(
             "[{:.3}s] Initialized with {} initial inputs",
             elapsed_secs,
-            self.initial_inputs.len()
+            initial_inputs.len()
         );
 
-        let notifiers = Arc::new(RwLock::new(HashMap::new()));
-        let shared_results = Arc::new(RwLock::new(results));
-
-        (notifiers, shared_results)
+        ExecutionState {
+            request_id,
+            initial_inputs,
+            notifiers: Arc::new(RwLock::new(HashMap::new())),
+            shared_results: Arc::new(RwLock::new(results)),
+            running: Arc::new(RwLock::new(HashSet::new())),
+            attempts: Arc::new(RwLock::new(IndexMap::new())),
+            statuses: Arc::new(RwLock::new(IndexMap::new())),
+            cancellation,
+            start_time,
+            scheduling: Arc::new(scheduling),
+            retention,
+            node_cache,
+            coercions,
+        }
     }
 
     /// Driving method that:
@@ -428,17 +1741,15 @@ impl DAG {
     /// - Awaits all tasks to completion
     /// - Collects results from shared results
     /// - Handles any errors that occurred during execution
-    /// - Aborts remaining nodes if a node fails
+    /// - Aborts remaining nodes if a node fails or the DAG deadline passes
     ///
     /// Returns the final results of the DAG execution.
     async fn execute_nodes(
         &self,
         sorted_nodes: Vec<NodeID>,
-        notifiers: Notifiers,
-        shared_results: SharedResults,
-        start_time: Instant,
-        request_id: RequestId,
+        state: ExecutionState,
     ) -> Result<IndexMap<NodeID, Data>, DAGError> {
+        let start_time = state.start_time;
         println!(
             "[{:.3}s] Spawning tasks for {} nodes",
             start_time.elapsed().as_secs_f32(),
@@ -448,7 +1759,7 @@ impl DAG {
         let setup_start = Instant::now();
         for node_id in &sorted_nodes {
             let (tx, _) = watch::channel(());
-            notifiers.write().unwrap().insert(node_id.clone(), tx);
+            state.notifiers.write().unwrap().insert(node_id.clone(), tx);
         }
         println!(
             "[{:.3}s] Notification channels setup took {:.3}s",
@@ -456,17 +1767,12 @@ impl DAG {
             setup_start.elapsed().as_secs_f32()
         );
 
+        let pending_nodes: HashSet<NodeID> = sorted_nodes.iter().cloned().collect();
         let spawn_start = Instant::now();
         let handles: Vec<_> = sorted_nodes
             .into_iter()
             .map(|node_id| {
-                let handle = self.spawn_node_task(
-                    &node_id,
-                    &request_id,
-                    &notifiers,
-                    &shared_results,
-                    start_time,
-                );
+                let handle = self.spawn_node_task(&node_id, &state);
                 (node_id, handle)
             })
             .collect();
@@ -481,10 +1787,11 @@ impl DAG {
         let mut abort_handles = Vec::new();
 
         for (id, handle) in handles {
+            abort_handles.push(handle.abort_handle());
             let id_for_future = id.clone();
             futures.push(async move {
                 match handle.await {
-                    Ok(Ok(())) => Ok(()),
+                    Ok(Ok(())) => Ok(id_for_future),
                     Ok(Err(e)) => Err((id_for_future, e)),
                     Err(e) => Err((
                         id_for_future.clone(),
@@ -495,7 +1802,6 @@ impl DAG {
                     )),
                 }
             });
-            abort_handles.push(id);
         }
         println!(
             "[{:.3}s] Future preparation took {:.3}s",
@@ -503,25 +1809,17 @@ impl DAG {
             futures_start.elapsed().as_secs_f32()
         );
 
-        let mut all_tasks = futures::stream::FuturesUnordered::from_iter(futures);
+        let all_tasks = futures::stream::FuturesUnordered::from_iter(futures);
         println!(
             "[{:.3}s] Starting parallel execution of {} tasks",
             start_time.elapsed().as_secs_f32(),
             abort_handles.len()
         );
 
-        while let Some(result) = all_tasks.next().await {
-            if let Err((failed_node, error)) = result {
-                println!(
-                    "[{:.3}s] Node {} failed, aborting remaining tasks",
-                    start_time.elapsed().as_secs_f32(),
-                    failed_node
-                );
-                return Err(error);
-            }
-        }
+        self.await_node_tasks(all_tasks, pending_nodes, &state, &abort_handles)
+            .await?;
 
-        let final_results = (*shared_results.read().unwrap()).clone();
+        let final_results = std::mem::take(&mut *state.shared_results.write().unwrap());
         println!(
             "[{:.3}s] All tasks completed successfully",
             start_time.elapsed().as_secs_f32(),
@@ -530,66 +1828,392 @@ impl DAG {
         Ok(final_results)
     }
 
-    /// Driving method that:
-    /// - Sets up dependency receivers
-    /// - Starts a blocking task to execute the node
-    /// - Awaits the result of the node execution with a timeout
-    /// - Stores the result in shared results, notifies receivers
-    ///
-    /// Returns a handle to the node execution task.
-    fn spawn_node_task(
-        &self,
-        node_id: &NodeID,
-        request_id: &RequestId,
-        notifiers: &Notifiers,
-        shared_results: &SharedResults,
-        start_time: Instant,
-    ) -> tokio::task::JoinHandle<Result<(), DAGError>> {
-        let node_id = node_id.to_string();
-        let request_id = request_id.to_string();
-        let notifiers = Arc::clone(notifiers);
-        let shared_results = Arc::clone(shared_results);
+    /// Awaits node tasks as they complete, aborting the remaining ones as
+    /// soon as a node fails or the DAG deadline (if any) passes.
+    async fn await_node_tasks<F>(
+        &self,
+        mut all_tasks: futures::stream::FuturesUnordered<F>,
+        mut pending_nodes: HashSet<NodeID>,
+        state: &ExecutionState,
+        abort_handles: &[task::AbortHandle],
+    ) -> Result<(), DAGError>
+    where
+        F: std::future::Future<Output = Result<NodeID, (NodeID, DAGError)>>,
+    {
+        let deadline = self.settings.dag_deadline_ms().map(|ms| {
+            (ms, tokio::time::Instant::from_std(state.start_time) + Duration::from_millis(ms))
+        });
+
+        loop {
+            let next = match deadline {
+                Some((deadline_ms, deadline)) => {
+                    if let Ok(next) = tokio::time::timeout_at(deadline, all_tasks.next()).await {
+                        next
+                    } else {
+                        println!(
+                            "[{:.3}s] DAG deadline of {}ms exceeded, aborting remaining tasks",
+                            state.start_time.elapsed().as_secs_f32(),
+                            deadline_ms
+                        );
+                        self.abort_execution(state, abort_handles).await;
+                        let mut pending_nodes: Vec<NodeID> = pending_nodes.into_iter().collect();
+                        pending_nodes.sort();
+                        return Err(DAGError::DeadlineExceeded {
+                            deadline_ms,
+                            pending_nodes,
+                        });
+                    }
+                }
+                None => all_tasks.next().await,
+            };
+
+            match next {
+                Some(Ok(completed_node)) => {
+                    pending_nodes.remove(&completed_node);
+                }
+                Some(Err((failed_node, error))) => {
+                    println!(
+                        "[{:.3}s] Node {} failed, aborting remaining tasks",
+                        state.start_time.elapsed().as_secs_f32(),
+                        failed_node
+                    );
+                    self.abort_execution(state, abort_handles).await;
+                    return Err(error);
+                }
+                None => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// A node failed or the deadline passed, so we:
+    /// - Signal cancellation to every node (running Components can poll it)
+    /// - Abort all node tasks, so nodes still waiting on dependencies never
+    ///   start, and `AsyncComponent`s stop executing
+    /// - Give Components that were mid-execution a chance to clean up
+    async fn abort_execution(&self, state: &ExecutionState, abort_handles: &[task::AbortHandle]) {
+        let abort_start = Instant::now();
+        // Before aborting, which stops `AsyncComponent`s from running
+        let in_flight: Vec<NodeID> = state.running.read().unwrap().iter().cloned().collect();
+        state.cancellation.cancel();
+        for handle in abort_handles {
+            handle.abort();
+        }
+
+        let cleanups = in_flight.into_iter().filter_map(|node_id| {
+            let component = Arc::clone(self.nodes.get(&node_id)?);
+            let context = NodeExecutionContext::new(node_id, state.request_id.clone())
+                .with_cancellation(state.cancellation.clone());
+            Some(task::spawn_blocking(move || component.on_abort(context)))
+        });
+        let cleanup_count = futures::future::join_all(cleanups).await.len();
+
+        println!(
+            "[{:.3}s] Aborted execution, cleaned up {} in-flight nodes in {:.3}s",
+            state.start_time.elapsed().as_secs_f32(),
+            cleanup_count,
+            abort_start.elapsed().as_secs_f32()
+        );
+    }
+
+    /// Driving method that:
+    /// - Sets up dependency receivers
+    /// - Starts a task to execute the node, on the blocking thread pool
+    ///   unless its Component is an `AsyncComponent`
+    /// - Awaits the result of the node execution with a timeout
+    /// - Retries the node according to its retry policy, if it has one
+    /// - Stores the result in shared results, notifies receivers
+    ///
+    /// Returns a handle to the node execution task.
+    fn spawn_node_task(
+        &self,
+        node_id: &NodeID,
+        state: &ExecutionState,
+    ) -> tokio::task::JoinHandle<Result<(), DAGError>> {
+        let node_id = node_id.clone();
+        let state = state.clone();
+
+        let mut receivers =
+            Self::setup_dependency_receivers(&self.edges, &node_id, &state.notifiers);
+        let nodes = Arc::clone(&self.nodes);
+        let edges = self.edges.get(&node_id).cloned().unwrap_or_default();
+        let initial_inputs = Arc::clone(&state.initial_inputs);
+        let node_settings = self
+            .node_settings
+            .get(&node_id)
+            .cloned()
+            .unwrap_or_default();
+        let continue_on_error = self.settings.continue_on_error();
+
+        tokio::spawn(async move {
+            let start_time = state.start_time;
+            println!(
+                "[{:.3}s] Node {} waiting for dependencies",
+                start_time.elapsed().as_secs_f32(),
+                node_id
+            );
+            let outcome = async {
+                let inputs = if receivers.is_empty() {
+                    InputEdges::default()
+                } else {
+                    let readiness = Self::wait_for_dependencies(
+                        &mut receivers,
+                        &node_id,
+                        &state,
+                        &edges,
+                        node_settings.join,
+                    )
+                    .await?;
+                    match readiness {
+                        Readiness::Ready(inputs) => inputs,
+                        Readiness::Bypass { skipped_dependency } => {
+                            return Ok(NodeOutcome::NotExecuted(NodeStatus::Bypassed {
+                                skipped_dependency,
+                            }));
+                        }
+                    }
+                };
+                let inputs = Arc::new(inputs);
+
+                if node_settings.condition.is_some()
+                    && !Self::evaluate_condition(
+                        &node_id,
+                        Arc::clone(&nodes),
+                        Arc::clone(&inputs),
+                        Arc::clone(&initial_inputs),
+                        &state,
+                        &node_settings,
+                    )
+                    .await?
+                {
+                    return Ok(NodeOutcome::NotExecuted(NodeStatus::ConditionFalse));
+                }
+
+                Self::execute_with_retries(
+                    node_id.clone(),
+                    nodes,
+                    inputs,
+                    initial_inputs,
+                    &state,
+                    node_settings,
+                )
+                .await
+                .map(NodeOutcome::Executed)
+            }
+            .await;
+
+            if let Some(retention) = &state.retention {
+                retention.release(&edges, &mut state.shared_results.write().unwrap());
+            }
+
+            match outcome {
+                Ok(NodeOutcome::Executed(result)) => {
+                    Self::process_node_execution_result(result, &state);
+                    Ok(())
+                }
+                Ok(NodeOutcome::NotExecuted(status)) => {
+                    Self::process_node_without_output(&node_id, status, &state);
+                    Ok(())
+                }
+                Err(error) if continue_on_error && !matches!(error, DAGError::Cancelled { .. }) => {
+                    Self::process_node_failure(&node_id, &error, &state);
+                    Ok(())
+                }
+                Err(error) => {
+                    state
+                        .statuses
+                        .write()
+                        .unwrap()
+                        .insert(node_id, NodeStatus::from_error(&error));
+                    Err(error)
+                }
+            }
+        })
+    }
+
+    /// Evaluates the node's `when` condition against the input it would
+    /// execute with. Like the node itself, this is subject to its timeout.
+    async fn evaluate_condition(
+        node_id: &NodeID,
+        nodes: Arc<HashMap<NodeID, Arc<dyn Component>>>,
+        inputs: Arc<InputEdges>,
+        initial_inputs: Arc<HashMap<NodeID, Data>>,
+        state: &ExecutionState,
+        node_settings: &NodeSettings,
+    ) -> Result<bool, DAGError> {
+        let start_time = state.start_time;
+        let Some(condition) = node_settings.condition.clone() else {
+            return Ok(true);
+        };
+        let expression = condition.expression().to_string();
+
+        let mut evaluation = task::spawn_blocking({
+            let node_id = node_id.clone();
+            let state = state.clone();
+            move || {
+                let input_data = {
+                    let results_guard = state.shared_results.read().unwrap();
+                    Self::prepare_input_data(
+                        &node_id,
+                        &inputs,
+                        &results_guard,
+                        &initial_inputs,
+                        &nodes.get(&node_id).unwrap().input_type(),
+                        &state.coercions,
+                        start_time,
+                    )?
+                };
+                condition
+                    .evaluate(&input_data)
+                    .map_err(|reason| DAGError::ExecutionError { node_id, reason })
+            }
+        });
+
+        let holds = Self::await_node_execution_with_timeout(
+            &mut evaluation,
+            node_settings.timeout_ms,
+            node_id,
+            start_time,
+        )
+        .await?;
+        println!(
+            "[{:.3}s] Node {} condition {} {}",
+            start_time.elapsed().as_secs_f32(),
+            node_id,
+            expression,
+            if holds { "holds" } else { "doesn't hold, bypassing node" }
+        );
+        Ok(holds)
+    }
+
+    /// Runs the node until it succeeds, its retry policy gives up, or
+    /// the DAG is cancelled between attempts. An attempt that times out is
+    /// stopped, and only retried once its Component has returned.
+    async fn execute_with_retries(
+        node_id: NodeID,
+        nodes: Arc<HashMap<NodeID, Arc<dyn Component>>>,
+        inputs: Arc<InputEdges>,
+        initial_inputs: Arc<HashMap<NodeID, Data>>,
+        state: &ExecutionState,
+        node_settings: NodeSettings,
+    ) -> Result<(NodeID, Data), DAGError> {
+        let start_time = state.start_time;
+        let mut attempt = 1;
+        loop {
+            state
+                .attempts
+                .write()
+                .unwrap()
+                .insert(node_id.clone(), attempt);
+
+            let permits = if node_settings.limited {
+                Self::acquire_permits(&node_id, state).await?
+            } else {
+                Vec::new()
+            };
+            let mut execution = Self::start_node_execution(
+                node_id.clone(),
+                attempt,
+                permits,
+                Arc::clone(&nodes),
+                Arc::clone(&inputs),
+                Arc::clone(&initial_inputs),
+                state.clone(),
+            );
+
+            let error = match Self::await_node_execution_with_timeout(
+                &mut execution.handle,
+                node_settings.timeout_ms,
+                &node_id,
+                start_time,
+            )
+            .await
+            {
+                Ok(result) => return Ok(result),
+                Err(error) => error,
+            };
+            if matches!(error, DAGError::NodeTimeout { .. }) {
+                execution.stop();
+            }
+
+            let Some(policy) = &node_settings.retry_policy else {
+                return Err(error);
+            };
+            if !policy.should_retry(attempt, &error) {
+                if attempt == 1 {
+                    return Err(error);
+                }
+                return Err(DAGError::FailedAfterRetries {
+                    node_id,
+                    attempts: attempt,
+                    last_error: Box::new(error),
+                });
+            }
 
-        let mut receivers = Self::setup_dependency_receivers(&self.edges, &node_id, &notifiers);
-        let nodes = Arc::clone(&self.nodes);
-        let edges = Arc::clone(&self.edges);
-        let initial_inputs = Arc::clone(&self.initial_inputs);
-        let timeout_ms = self.settings.per_node_timeout_ms();
+            // A timed-out Component may still be running, and mustn't run
+            // alongside its retry
+            tokio::select! {
+                () = execution.stopped() => {}
+                () = state.cancellation.cancelled() => {
+                    return Err(DAGError::Cancelled { node_id });
+                }
+            }
 
-        tokio::spawn(async move {
+            let delay = policy.delay_for(attempt);
             println!(
-                "[{:.3}s] Node {} waiting for dependencies",
+                "[{:.3}s] Node {} attempt {} failed ({}), retrying in {:.3}s",
                 start_time.elapsed().as_secs_f32(),
-                node_id
+                node_id,
+                attempt,
+                error,
+                delay.as_secs_f32()
             );
-            if !receivers.is_empty() {
-                Self::wait_for_dependencies(
-                    &mut receivers,
-                    &node_id,
-                    start_time,
-                    &shared_results,
-                    &edges,
-                )
-                .await?;
+            tokio::select! {
+                () = tokio::time::sleep(delay) => {}
+                () = state.cancellation.cancelled() => {
+                    return Err(DAGError::Cancelled { node_id });
+                }
             }
+            attempt += 1;
+        }
+    }
 
-            let node_execution_handle = Self::start_blocking_node_execution(
-                node_id.clone(),
-                request_id,
-                nodes,
-                edges,
-                initial_inputs,
-                shared_results.clone(),
-                start_time,
-            );
-
-            let result =
-                Self::await_node_execution_with_timeout(node_execution_handle, timeout_ms, &node_id, start_time).await?;
-
-            Self::process_node_execution_result(result, &shared_results, &notifiers, start_time);
+    /// Waits for the node's turn to execute under the DAG's and the
+    /// process-wide concurrency limits, if any
+    async fn acquire_permits(
+        node_id: &NodeID,
+        state: &ExecutionState,
+    ) -> Result<Vec<Permit>, DAGError> {
+        let scheduling = &state.scheduling;
+        let key = scheduling.keys.get(node_id).copied().unwrap_or_default();
+        let wait_start = Instant::now();
+        let mut permits = Vec::new();
+        // Always in the same order, so that executions can't hold each
+        // other's permits while waiting for their own
+        for limiter in [&scheduling.dag_limiter, &scheduling.global_limiter]
+            .into_iter()
+            .flatten()
+        {
+            tokio::select! {
+                permit = limiter.acquire(key) => permits.push(permit),
+                () = state.cancellation.cancelled() => {
+                    return Err(DAGError::Cancelled {
+                        node_id: node_id.clone(),
+                    });
+                }
+            }
+        }
 
-            Ok(())
-        })
+        if !permits.is_empty() {
+            println!(
+                "[{:.3}s] Node {} waited {:.3}s for its turn to execute",
+                state.start_time.elapsed().as_secs_f32(),
+                node_id,
+                wait_start.elapsed().as_secs_f32()
+            );
+        }
+        Ok(permits)
     }
 
     fn setup_dependency_receivers(
@@ -609,28 +2233,73 @@ impl DAG {
     }
 
     /// We've determined that a node has dependencies, so we need to wait
-    /// for them to be satisfied before we can execute the node.
+    /// for them to settle before we can decide, according to the node's
+    /// join policy, whether it executes and with which outputs.
     async fn wait_for_dependencies(
         receivers: &mut HashMap<NodeID, watch::Receiver<()>>,
         node_id: &NodeID,
-        start_time: Instant,
-        shared_results: &SharedResults,
-        edges: &HashMap<NodeID, Vec<Edge>>,
-    ) -> Result<(), DAGError> {
+        state: &ExecutionState,
+        edges: &[Edge],
+        join: JoinPolicy,
+    ) -> Result<Readiness, DAGError> {
+        let start_time = state.start_time;
         let wait_start = Instant::now();
 
         println!(
-            "[{:.3}s] Node {} waiting for {} dependencies",
+            "[{:.3}s] Node {} waiting for {} dependencies (join: {:?})",
             start_time.elapsed().as_secs_f32(),
             node_id,
-            receivers.len()
+            receivers.len(),
+            join
         );
-        for receiver in receivers.values_mut() {
-            if let Err(e) = receiver.changed().await {
-                return Err(DAGError::ExecutionError {
-                    node_id: node_id.to_string(),
-                    reason: format!("Failed to receive dependency notification: {e}"),
-                });
+        let mut settled: futures::stream::FuturesUnordered<_> = receivers
+            .drain()
+            .map(|(dependency, mut receiver)| {
+                let statuses = Arc::clone(&state.statuses);
+                async move {
+                    // Dependency tasks may run while dependents are still being
+                    // spawned, so a dependency may have settled before we
+                    // subscribed to its notifications
+                    let settled = statuses.read().unwrap().contains_key(&dependency);
+                    let changed = if settled {
+                        Ok(())
+                    } else {
+                        receiver.changed().await
+                    };
+                    (dependency, changed)
+                }
+            })
+            .collect();
+
+        let mut first_available = None;
+        loop {
+            tokio::select! {
+                next = settled.next() => match next {
+                    Some((dependency, Ok(()))) => {
+                        let produced = state
+                            .statuses
+                            .read()
+                            .unwrap()
+                            .get(&dependency)
+                            .is_some_and(NodeStatus::is_succeeded);
+                        if produced && join == JoinPolicy::FirstAvailable {
+                            first_available = Some(dependency);
+                            break;
+                        }
+                    }
+                    Some((_, Err(e))) => {
+                        return Err(DAGError::ExecutionError {
+                            node_id: node_id.clone(),
+                            reason: format!("Failed to receive dependency notification: {e}"),
+                        });
+                    }
+                    None => break,
+                },
+                () = state.cancellation.cancelled() => {
+                    return Err(DAGError::Cancelled {
+                        node_id: node_id.clone(),
+                    });
+                }
             }
         }
 
@@ -638,14 +2307,15 @@ impl DAG {
             "[{:.3}s] Node {} verifying {} dependency results",
             start_time.elapsed().as_secs_f32(),
             node_id,
-            edges.get(node_id).map_or(0, Vec::len)
+            edges.len()
         );
-        if let Some(edges) = edges.get(node_id) {
-            let results = shared_results.read().unwrap();
-            for edge in edges {
+        let readiness = Self::resolve_join(node_id, edges, join, first_available, state)?;
+        if let Readiness::Ready(inputs) = &readiness {
+            let results = state.shared_results.read().unwrap();
+            for edge in &inputs.edges {
                 if !results.contains_key(&edge.source) {
                     return Err(DAGError::MissingDependency {
-                        node_id: node_id.to_string(),
+                        node_id: node_id.clone(),
                         dependency_id: edge.source.clone(),
                     });
                 }
@@ -658,64 +2328,281 @@ impl DAG {
             node_id,
             wait_start.elapsed().as_secs_f32()
         );
-        Ok(())
+        Ok(readiness)
+    }
+
+    /// Decides from the statuses of a node's settled dependencies whether
+    /// the node executes, and with which of their outputs. A failed
+    /// dependency takes precedence over a bypassed one when the node can't
+    /// execute.
+    fn resolve_join(
+        node_id: &NodeID,
+        edges: &[Edge],
+        join: JoinPolicy,
+        first_available: Option<NodeID>,
+        state: &ExecutionState,
+    ) -> Result<Readiness, DAGError> {
+        if let Some(first_available) = first_available {
+            let edges = edges
+                .iter()
+                .filter(|edge| edge.source == first_available)
+                .take(1)
+                .cloned()
+                .collect();
+            return Ok(Readiness::Ready(InputEdges {
+                edges,
+                as_list: false,
+            }));
+        }
+
+        let statuses = state.statuses.read().unwrap();
+        let produced: Vec<Edge> = edges
+            .iter()
+            .filter(|edge| {
+                statuses
+                    .get(&edge.source)
+                    .is_some_and(NodeStatus::is_succeeded)
+            })
+            .cloned()
+            .collect();
+        let ready = match join {
+            JoinPolicy::All => produced.len() == edges.len(),
+            JoinPolicy::Any | JoinPolicy::FirstAvailable => !produced.is_empty(),
+        };
+        if ready {
+            return Ok(Readiness::Ready(InputEdges {
+                edges: produced,
+                as_list: join == JoinPolicy::Any && edges.len() > 1,
+            }));
+        }
+
+        if let Some(edge) = edges.iter().find(|edge| {
+            statuses
+                .get(&edge.source)
+                .is_some_and(|status| !status.is_succeeded() && !status.is_bypassed())
+        }) {
+            return Err(DAGError::DependencyFailed {
+                node_id: node_id.clone(),
+                dependency_id: edge.source.clone(),
+            });
+        }
+
+        match edges.iter().find(|edge| {
+            statuses
+                .get(&edge.source)
+                .is_some_and(NodeStatus::is_bypassed)
+        }) {
+            Some(edge) => Ok(Readiness::Bypass {
+                skipped_dependency: edge.source.clone(),
+            }),
+            None => Err(DAGError::MissingDependency {
+                node_id: node_id.clone(),
+                dependency_id: edges
+                    .iter()
+                    .find(|edge| !statuses.contains_key(&edge.source))
+                    .map_or_else(String::new, |edge| edge.source.clone()),
+            }),
+        }
     }
 
     /// We've determined that a node's dependencies are satisfied, so we
-    /// start a blocking task to execute the node. We'll return a handle
-    /// to this task so we can await its result later.
-    fn start_blocking_node_execution(
+    /// start a task to execute the node: a blocking task for a Component,
+    /// or a task on the runtime for an `AsyncComponent`. We'll return a
+    /// handle to this task so we can await its result later.
+    ///
+    /// The node is tracked as running for as long as its Component executes,
+    /// so that it can be cleaned up if the DAG aborts in the meantime. It
+    /// holds its concurrency `permits` for as long, unless the attempt is
+    /// stopped first.
+    #[allow(clippy::too_many_arguments)]
+    fn start_node_execution(
         node_id: NodeID,
-        request_id: RequestId,
+        attempt: u32,
+        permits: Vec<Permit>,
         nodes: Arc<HashMap<NodeID, Arc<dyn Component>>>,
-        edges: Arc<HashMap<NodeID, Vec<Edge>>>,
+        inputs: Arc<InputEdges>,
         initial_inputs: Arc<HashMap<NodeID, Data>>,
-        shared_results: SharedResults,
-        start_time: Instant,
+        state: ExecutionState,
+    ) -> NodeAttempt {
+        let cancellation = state.cancellation.child_token();
+        let permits = Arc::new(Mutex::new(permits));
+        let handle = Self::spawn_node_execution(
+            node_id,
+            attempt,
+            cancellation.clone(),
+            Arc::clone(&permits),
+            nodes,
+            inputs,
+            initial_inputs,
+            state,
+        );
+        NodeAttempt {
+            handle,
+            cancellation,
+            permits,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_node_execution(
+        node_id: NodeID,
+        attempt: u32,
+        cancellation: CancellationToken,
+        permits: Arc<Mutex<Vec<Permit>>>,
+        nodes: Arc<HashMap<NodeID, Arc<dyn Component>>>,
+        inputs: Arc<InputEdges>,
+        initial_inputs: Arc<HashMap<NodeID, Data>>,
+        state: ExecutionState,
     ) -> task::JoinHandle<Result<(NodeID, Data), DAGError>> {
+        if nodes[&node_id].as_async().is_some() {
+            return tokio::spawn(async move {
+                let input_data =
+                    Self::prepare_node_input(&node_id, &nodes, &inputs, &initial_inputs, &state)?;
+                let memo_key = Self::memo_key(&node_id, &input_data, &state);
+                if let Some(output) = Self::memoized_output(&node_id, memo_key.as_ref(), &state) {
+                    return Ok((node_id, output));
+                }
+                let Some(component) = nodes[&node_id].as_async() else {
+                    unreachable!("checked before spawning")
+                };
+
+                let execution_start = Instant::now();
+                let execution_context =
+                    Self::node_execution_context(&node_id, attempt, cancellation, &state);
+                let running = RunningNode::new(&node_id, &state.running);
+                let output = component.execute_async(execution_context, input_data).await;
+                drop(running);
+                permits.lock().unwrap().clear();
+
+                Self::log_node_execution(&node_id, execution_start, &state);
+                let output = output?;
+                Self::memoize_output(&node_id, memo_key, &output, &state);
+                Ok((node_id, output))
+            });
+        }
+
         task::spawn_blocking(move || {
-            let input_data = {
-                let prep_start = Instant::now();
-                let results_guard = shared_results.read().unwrap();
-                let result = Self::prepare_input_data(
-                    &node_id,
-                    edges.get(&node_id).map_or(&[], Vec::as_slice),
-                    &results_guard,
-                    &initial_inputs,
-                    &nodes.get(&node_id).unwrap().input_type(),
-                    start_time,
-                )?;
-                println!(
-                    "[{:.3}s] Input preparation for node {} took {:.3}s",
-                    start_time.elapsed().as_secs_f32(),
-                    node_id,
-                    prep_start.elapsed().as_secs_f32()
-                );
-                result
-            };
+            let input_data =
+                Self::prepare_node_input(&node_id, &nodes, &inputs, &initial_inputs, &state)?;
+            let memo_key = Self::memo_key(&node_id, &input_data, &state);
+            if let Some(output) = Self::memoized_output(&node_id, memo_key.as_ref(), &state) {
+                return Ok((node_id, output));
+            }
+            let component = nodes.get(&node_id).unwrap();
 
             let execution_start = Instant::now();
-            let execution_context = NodeExecutionContext::new(node_id.clone(), request_id);
-            let component = nodes.get(&node_id).unwrap();
-            let output = component.execute(execution_context, input_data)?;
-            println!(
-                "[{:.3}s] Node {} execution took {:.3}s",
-                start_time.elapsed().as_secs_f32(),
-                node_id,
-                execution_start.elapsed().as_secs_f32()
-            );
+            let execution_context = Self::node_execution_context(&node_id, attempt, cancellation, &state);
+            let running = RunningNode::new(&node_id, &state.running);
+            let output = component.execute(execution_context, input_data);
+            drop(running);
+            permits.lock().unwrap().clear();
+
+            Self::log_node_execution(&node_id, execution_start, &state);
+            let output = output?;
+            Self::memoize_output(&node_id, memo_key, &output, &state);
             Ok((node_id, output))
         })
     }
 
-    /// We've started a blocking task to execute a node, and we want to
+    /// The key the node's output for `input` is memoized under, if the node
+    /// is `cacheable`
+    fn memo_key(node_id: &NodeID, input: &Data, state: &ExecutionState) -> Option<NodeCacheKey> {
+        let memo = state.node_cache.as_ref()?.memos.get(node_id)?;
+        Some(NodeCacheKey::new(&memo.component_type, memo.config_hash, input))
+    }
+
+    /// The node's memoized output under `key`, which saves executing it
+    fn memoized_output(
+        node_id: &NodeID,
+        key: Option<&NodeCacheKey>,
+        state: &ExecutionState,
+    ) -> Option<Data> {
+        let output = state.node_cache.as_ref()?.cache.get_node_result(key?)?;
+        println!(
+            "[{:.3}s] Node {} cache hit, not executing it",
+            state.start_time.elapsed().as_secs_f32(),
+            node_id
+        );
+        Some(output)
+    }
+
+    fn memoize_output(
+        node_id: &NodeID,
+        key: Option<NodeCacheKey>,
+        output: &Data,
+        state: &ExecutionState,
+    ) {
+        if let (Some(node_cache), Some(key)) = (&state.node_cache, key) {
+            let ttl = node_cache.memos.get(node_id).and_then(|memo| memo.ttl);
+            node_cache.cache.store_node_result(key, output.clone(), ttl);
+        }
+    }
+
+    /// Gathers the node's input from its dependencies' outputs, unless the
+    /// DAG was cancelled in the meantime
+    fn prepare_node_input(
+        node_id: &NodeID,
+        nodes: &HashMap<NodeID, Arc<dyn Component>>,
+        inputs: &InputEdges,
+        initial_inputs: &HashMap<NodeID, Data>,
+        state: &ExecutionState,
+    ) -> Result<Data, DAGError> {
+        let start_time = state.start_time;
+        if state.cancellation.is_cancelled() {
+            return Err(DAGError::Cancelled {
+                node_id: node_id.clone(),
+            });
+        }
+
+        let prep_start = Instant::now();
+        let results_guard = state.shared_results.read().unwrap();
+        let result = Self::prepare_input_data(
+            node_id,
+            inputs,
+            &results_guard,
+            initial_inputs,
+            &nodes.get(node_id).unwrap().input_type(),
+            &state.coercions,
+            start_time,
+        )?;
+        println!(
+            "[{:.3}s] Input preparation for node {} took {:.3}s",
+            start_time.elapsed().as_secs_f32(),
+            node_id,
+            prep_start.elapsed().as_secs_f32()
+        );
+        Ok(result)
+    }
+
+    fn node_execution_context(
+        node_id: &NodeID,
+        attempt: u32,
+        cancellation: CancellationToken,
+        state: &ExecutionState,
+    ) -> NodeExecutionContext {
+        NodeExecutionContext::new(node_id.clone(), state.request_id.clone())
+            .with_cancellation(cancellation)
+            .with_attempt(attempt)
+            .with_dag_limiter(state.scheduling.dag_limiter.clone())
+    }
+
+    fn log_node_execution(node_id: &NodeID, execution_start: Instant, state: &ExecutionState) {
+        println!(
+            "[{:.3}s] Node {} execution took {:.3}s",
+            state.start_time.elapsed().as_secs_f32(),
+            node_id,
+            execution_start.elapsed().as_secs_f32()
+        );
+    }
+
+    /// We've started a task to execute a node, and we want to
     /// await its result with a timeout, handling errors appropriately.
-    async fn await_node_execution_with_timeout(
-        execution: task::JoinHandle<Result<(NodeID, Data), DAGError>>,
+    async fn await_node_execution_with_timeout<T>(
+        execution: &mut task::JoinHandle<Result<T, DAGError>>,
         timeout_ms: Option<u64>,
         node_id: &NodeID,
         start_time: Instant,
-    ) -> Result<(NodeID, Data), DAGError> {
+    ) -> Result<T, DAGError> {
         let execution_start = Instant::now();
 
         if let Some(ms) = timeout_ms {
@@ -728,18 +2615,18 @@ impl DAG {
                         execution_start.elapsed().as_secs_f32()
                     );
                     result.map_err(|e| DAGError::ExecutionError {
-                        node_id: node_id.to_string(),
+                        node_id: node_id.clone(),
                         reason: format!("Task join error: {e}"),
                     })?
                 }
-                Err(_) => Err(DAGError::ExecutionError {
-                    node_id: node_id.to_string(),
-                    reason: format!("Node execution timed out after {ms}ms"),
+                Err(_) => Err(DAGError::NodeTimeout {
+                    node_id: node_id.clone(),
+                    timeout_ms: ms,
                 }),
             }
         } else {
             execution.await.map_err(|e| DAGError::ExecutionError {
-                node_id: node_id.to_string(),
+                node_id: node_id.clone(),
                 reason: format!("Task join error: {e}"),
             })?
         }
@@ -747,15 +2634,29 @@ impl DAG {
 
     /// We've received the result of a node execution, so we need to
     /// store it in the shared results and notify any dependent nodes.
-    fn process_node_execution_result(
-        result: (NodeID, Data),
-        shared_results: &SharedResults,
-        notifiers: &Notifiers,
-        start_time: Instant,
-    ) {
+    fn process_node_execution_result(result: (NodeID, Data), state: &ExecutionState) {
+        let start_time = state.start_time;
         let (id, output) = result;
         let store_start = Instant::now();
-        shared_results.write().unwrap().insert(id.clone(), output);
+        {
+            let mut results = state.shared_results.write().unwrap();
+            // Outputs nothing consumes are freed right away, along with any
+            // initial input stored under the node's ID
+            if state
+                .retention
+                .as_ref()
+                .is_none_or(|retention| retention.is_needed(&id))
+            {
+                results.insert(id.clone(), output);
+            } else {
+                results.shift_remove(&id);
+            }
+        }
+        state
+            .statuses
+            .write()
+            .unwrap()
+            .insert(id.clone(), NodeStatus::Succeeded);
         println!(
             "[{:.3}s] Node {} result storage took {:.3}s",
             start_time.elapsed().as_secs_f32(),
@@ -764,7 +2665,7 @@ impl DAG {
         );
 
         let notify_start = Instant::now();
-        if let Some(sender) = notifiers.read().unwrap().get(&id) {
+        if let Some(sender) = state.notifiers.read().unwrap().get(&id) {
             println!(
                 "[{:.3}s] Notifying dependents of node {} completion",
                 start_time.elapsed().as_secs_f32(),
@@ -780,12 +2681,46 @@ impl DAG {
         );
     }
 
+    /// A node failed (or was skipped) while continuing on errors.
+    fn process_node_failure(node_id: &NodeID, error: &DAGError, state: &ExecutionState) {
+        println!(
+            "[{:.3}s] Node {} did not succeed ({}), continuing with independent nodes",
+            state.start_time.elapsed().as_secs_f32(),
+            node_id,
+            error
+        );
+        Self::process_node_without_output(node_id, NodeStatus::from_error(error), state);
+    }
+
+    /// A node didn't produce an output, so we record its status, drop any
+    /// initial input stored under its ID so it isn't mistaken for an output,
+    /// and notify dependents so they can apply their join policies.
+    fn process_node_without_output(node_id: &NodeID, status: NodeStatus, state: &ExecutionState) {
+        println!(
+            "[{:.3}s] Node {} has no output: {:?}",
+            state.start_time.elapsed().as_secs_f32(),
+            node_id,
+            status
+        );
+        state.shared_results.write().unwrap().shift_remove(node_id);
+        state
+            .statuses
+            .write()
+            .unwrap()
+            .insert(node_id.clone(), status);
+
+        if let Some(sender) = state.notifiers.read().unwrap().get(node_id) {
+            let _ = sender.send(());
+        }
+    }
+
     fn prepare_input_data(
         node_id: &NodeID,
-        edges: &[Edge],
+        inputs: &InputEdges,
         results: &IndexMap<NodeID, Data>,
         initial_inputs: &HashMap<NodeID, Data>,
         expected_type: &DataType,
+        coercions: &HashMap<Edge, Coercion>,
         start_time: Instant,
     ) -> Result<Data, DAGError> {
         println!(
@@ -793,45 +2728,81 @@ impl DAG {
             start_time.elapsed().as_secs_f32()
         );
 
-        if !edges.is_empty() {
-            if edges.len() == 1 {
-                let edge = &edges[0];
-                if let Some(output) = results.get(&edge.source) {
-                    if !output.validate_type(expected_type) {
-                        return Err(DAGError::TypeMismatch {
-                            node_id: node_id.to_string(),
-                            expected: expected_type.clone(),
-                            actual: output.get_type(),
-                        });
-                    }
-                    return Ok(output.clone());
-                }
-                return Err(DAGError::MissingDependency {
+        let edges = inputs.edges.as_slice();
+        if edges.iter().any(|edge| edge.port.is_some()) {
+            let mut ports = IndexMap::with_capacity(edges.len());
+            for edge in edges {
+                let output = Self::edge_output(node_id, edge, results, coercions)?;
+                let port = edge.port.clone().unwrap_or_else(|| edge.source.clone());
+                ports.insert(port, output);
+            }
+
+            let input = Data::Record(ports);
+            if !input.validate_type(expected_type) {
+                return Err(DAGError::TypeMismatch {
                     node_id: node_id.to_string(),
-                    dependency_id: edge.source.clone(),
+                    expected: expected_type.clone(),
+                    actual: input.get_type(),
                 });
             }
+            return Ok(input);
+        }
 
-            let mut valid_inputs = Vec::with_capacity(edges.len());
-            for edge in edges {
-                if let Some(output) = results.get(&edge.source) {
-                    valid_inputs.push(output.clone());
-                } else {
-                    return Err(DAGError::MissingDependency {
+        if !edges.is_empty() {
+            if edges.len() == 1 && !inputs.as_list {
+                let output = Self::edge_output(node_id, &edges[0], results, coercions)?;
+                if !output.validate_type(expected_type) {
+                    return Err(DAGError::TypeMismatch {
                         node_id: node_id.to_string(),
-                        dependency_id: edge.source.clone(),
+                        expected: expected_type.clone(),
+                        actual: output.get_type(),
                     });
                 }
+                return Ok(output);
             }
+
+            let valid_inputs = edges
+                .iter()
+                .map(|edge| Self::edge_output(node_id, edge, results, coercions))
+                .collect::<Result<Vec<_>, _>>()?;
             return Ok(Data::List(valid_inputs));
         }
 
         Ok(initial_inputs.get(node_id).cloned().unwrap_or(Data::Null))
     }
 
+    /// The output of the edge's source, converted if the edge has a coercion
+    fn edge_output(
+        node_id: &NodeID,
+        edge: &Edge,
+        results: &IndexMap<NodeID, Data>,
+        coercions: &HashMap<Edge, Coercion>,
+    ) -> Result<Data, DAGError> {
+        let Some(output) = results.get(&edge.source) else {
+            return Err(DAGError::MissingDependency {
+                node_id: node_id.to_string(),
+                dependency_id: edge.source.clone(),
+            });
+        };
+        let Some(coercion) = coercions.get(edge) else {
+            return Ok(output.clone());
+        };
+        coercion
+            .apply(output.clone())
+            .map_err(|reason| DAGError::ExecutionError {
+                node_id: node_id.to_string(),
+                reason: format!(
+                    "Implicit conversion {} of the output of {} failed: {reason}",
+                    coercion.name(),
+                    edge.source
+                ),
+            })
+    }
+
     fn handle_caching(
-        &self,
         cache: &Arc<Cache>,
+        key: ResultKey,
+        inputs: Arc<HashMap<NodeID, Data>>,
         final_results: &IndexMap<NodeID, Data>,
         request_id: &RequestId,
         start_time: Instant,
@@ -843,12 +2814,10 @@ impl DAG {
         let cache_start = Instant::now();
         let cache = Arc::clone(cache);
         let results_copy = final_results.clone();
-        let inputs = self.initial_inputs.clone();
         let request_id = request_id.to_string();
-        let ir_hash = self.ir_hash;
 
         tokio::spawn(async move {
-            cache.store_result(ir_hash, &inputs, &results_copy, &request_id);
+            cache.store_dag_result(&key, &inputs, &results_copy, &request_id);
             println!(
                 "[{:.3}s] Cache storage spawned, setup took {:.3}s",
                 start_time.elapsed().as_secs_f32(),
@@ -920,6 +2889,37 @@ impl DAG {
         }
     }
 
+    /// The edges on which outputs are converted to fit the input of their
+    /// target, as found when the DAG was built with
+    /// `DAGSettings::implicit_coercions`, sorted by target
+    #[must_use]
+    pub fn implicit_conversions(&self) -> &[ImplicitConversion] {
+        &self.implicit_conversions
+    }
+
+    /// Drops the cached results of this DAG, for any inputs and outputs
+    pub fn invalidate_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate_ir_hash(self.ir_hash);
+        }
+    }
+
+    /// Drops the cached results of every DAG with this DAG's alias, such as
+    /// those of its earlier configurations
+    pub fn invalidate_alias(&self) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate_alias(&self.alias);
+        }
+    }
+
+    /// Drops the cached result of the request, which can still be replayed
+    /// from the history
+    pub fn invalidate_request_id(&self, request_id: &RequestId) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate_request_id(request_id);
+        }
+    }
+
     pub async fn get_historical_result(&self, request_id: &RequestId) -> Option<DAGResult> {
         if !self.settings.enable_history {
             return None;
@@ -946,10 +2946,21 @@ impl DAG {
 ///
 /// Additionally, a Node runs as part of a specific request through
 /// a DAG, so it is useful to have the request ID in the context.
+///
+/// Long-running Components should poll `is_cancelled` and return early
+/// once the DAG has aborted, since blocking work can't be interrupted.
+///
+/// A retried node is executed again with an incremented `attempt`.
 #[derive(Debug, Clone)]
 pub struct NodeExecutionContext {
     pub node_id: NodeID,
     pub request_id: RequestId,
+    pub cancellation: CancellationToken,
+    /// 1-based attempt number of this execution
+    pub attempt: u32,
+    /// The `max_concurrent_nodes` limit of the execution the node is part
+    /// of, which the nodes of its sub-DAG, if any, are subject to as well
+    pub(crate) dag_limiter: Option<Arc<ConcurrencyLimiter>>,
 }
 
 impl NodeExecutionContext {
@@ -958,8 +2969,100 @@ impl NodeExecutionContext {
         Self {
             node_id,
             request_id,
+            cancellation: CancellationToken::new(),
+            attempt: 1,
+            dag_limiter: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_attempt(mut self, attempt: u32) -> Self {
+        self.attempt = attempt;
+        self
+    }
+
+    /// Shares the given token with this context, so that cancelling the
+    /// token is observed by the node.
+    #[must_use]
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    #[must_use]
+    pub(crate) fn with_dag_limiter(mut self, dag_limiter: Option<Arc<ConcurrencyLimiter>>) -> Self {
+        self.dag_limiter = dag_limiter;
+        self
+    }
+}
+
+/// A cooperative cancellation signal shared by every node of a single
+/// DAG execution. Cloning the token shares the underlying signal.
+///
+/// A child token is cancelled along with its parent, but can also be
+/// cancelled on its own, e.g. to stop a single attempt at a node.
+#[derive(Debug, Clone)]
+pub struct CancellationToken {
+    sender: Arc<watch::Sender<bool>>,
+    parent: Option<Box<CancellationToken>>,
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CancellationToken {
+    #[must_use]
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self {
+            sender: Arc::new(sender),
+            parent: None,
         }
     }
+
+    /// A token that is cancelled when this one is, and that can be
+    /// cancelled without cancelling this one
+    #[must_use]
+    pub fn child_token(&self) -> Self {
+        Self {
+            parent: Some(Box::new(self.clone())),
+            ..Self::new()
+        }
+    }
+
+    /// Cancels this token and its children, but not its parent
+    pub fn cancel(&self) {
+        self.sender.send_replace(true);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.lineage().any(|token| *token.sender.borrow())
+    }
+
+    /// Resolves once the token, or one of its ancestors, has been cancelled.
+    pub async fn cancelled(&self) {
+        let mut receivers: Vec<_> = self.lineage().map(|token| token.sender.subscribe()).collect();
+        let waits = receivers.iter_mut().map(|receiver| {
+            Box::pin(async move {
+                let _ = receiver.wait_for(|cancelled| *cancelled).await;
+            })
+        });
+        futures::future::select_all(waits).await;
+    }
+
+    /// This token and its ancestors
+    fn lineage(&self) -> impl Iterator<Item = &CancellationToken> {
+        std::iter::successors(Some(self), |token| token.parent.as_deref())
+    }
 }
 
 #[cfg(test)]
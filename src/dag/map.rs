@@ -0,0 +1,163 @@
+use futures::{StreamExt, TryStreamExt};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::sub_dag::{build_sub_dag, execute_for_output, resolve_input_node, resolve_output_node};
+use crate::component::{Component, Data, DataType, Error, Registry};
+use crate::dag::{DAGError, DAGSettings, NodeExecutionContext, NodeID, DAG};
+
+/// The `component_type` of the built-in `Map` node
+pub const MAP_COMPONENT_TYPE: &str = "Map";
+
+/// How many elements a `Map` node runs its sub-DAG on at once, by default
+const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+/// A built-in node that executes a sub-DAG once per element of its input
+/// `List`, gathering the outputs into a `List` in element order.
+///
+/// Its `config` holds:
+/// - `dag`: the alias of a DAG registered with `Registry::register_dag`, or
+///   an inline `DAGConfig`
+/// - `input` (optional): the sub-DAG node receiving each element as its
+///   input; defaults to the sub-DAG's only root node
+/// - `output` (optional): the sub-DAG node whose output is gathered;
+///   defaults to the sub-DAG's only declared output, or else its only leaf
+///   node
+/// - `max_concurrency` (optional): how many elements are processed at once
+///
+/// The node's input type is a `List` of whatever the `input` node accepts, and
+/// its output type a `List` of what the `output` node produces, so elements
+/// are type-checked like any other edge when the DAG is built.
+pub(crate) struct MapNode {
+    dag: DAG,
+    input_node: NodeID,
+    output_node: NodeID,
+    element_type: DataType,
+    output_type: DataType,
+    max_concurrency: usize,
+}
+
+impl MapNode {
+    /// Builds the sub-DAG of the `Map` node `node_id`. `enclosing_aliases`
+    /// are the DAGs the node is nested in, which its sub-DAG may not be.
+    pub(crate) fn build(
+        node_id: &NodeID,
+        config: &Value,
+        registry: &Registry,
+        settings: &DAGSettings,
+        enclosing_aliases: &[String],
+    ) -> Result<Self, String> {
+        let max_concurrency = match config.get("max_concurrency") {
+            None => DEFAULT_MAX_CONCURRENCY,
+            Some(value) => value
+                .as_u64()
+                .and_then(|n| usize::try_from(n).ok())
+                .filter(|&n| n > 0)
+                .ok_or_else(|| {
+                    format!("Map node {node_id}: max_concurrency must be a positive integer")
+                })?,
+        };
+
+        let dag = build_sub_dag(
+            MAP_COMPONENT_TYPE,
+            node_id,
+            config,
+            registry,
+            settings,
+            enclosing_aliases,
+        )?;
+        let input_node = resolve_input_node(MAP_COMPONENT_TYPE, node_id, config, &dag)?;
+        let output_node = resolve_output_node(MAP_COMPONENT_TYPE, node_id, config, &dag)?;
+
+        Ok(Self {
+            element_type: dag.nodes[&input_node].input_type(),
+            output_type: dag.nodes[&output_node].output_type(),
+            dag,
+            input_node,
+            output_node,
+            max_concurrency,
+        })
+    }
+
+    /// The hash of the sub-DAG, which may change with its registered alias
+    pub(crate) fn dag_hash(&self) -> u64 {
+        self.dag.ir_hash
+    }
+
+    async fn execute_element(
+        &self,
+        context: &NodeExecutionContext,
+        index: usize,
+        element: Data,
+    ) -> Result<Data, DAGError> {
+        if !element.validate_type(&self.element_type) {
+            return Err(DAGError::TypeMismatch {
+                node_id: context.node_id.clone(),
+                expected: self.element_type.clone(),
+                actual: element.get_type(),
+            });
+        }
+
+        let mut inputs: HashMap<NodeID, Data> = (*self.dag.initial_inputs).clone();
+        inputs.insert(self.input_node.clone(), element);
+        let request_id = format!("{}/{}[{index}]", context.request_id, context.node_id);
+        execute_for_output(
+            &self.dag,
+            context,
+            request_id,
+            &format!("Element {index}"),
+            inputs,
+            &self.output_node,
+        )
+        .await
+    }
+}
+
+impl Component for MapNode {
+    fn configure(_: Value) -> Result<Self, Error> {
+        Err(Error::ConfigurationError(format!(
+            "{MAP_COMPONENT_TYPE} nodes are built with their DAG, not configured through the registry"
+        )))
+    }
+
+    /// Runs on a blocking thread like any Component, so we drive the
+    /// sub-DAG executions on the runtime from here. Once the node is
+    /// cancelled, so are the executions in flight, and no more are started.
+    fn execute(&self, context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
+        let Data::List(elements) = input else {
+            return Err(DAGError::TypeMismatch {
+                node_id: context.node_id,
+                expected: self.input_type(),
+                actual: input.get_type(),
+            });
+        };
+        println!(
+            "Map {}: executing sub-DAG on {} elements, {} at a time",
+            context.node_id,
+            elements.len(),
+            self.max_concurrency
+        );
+
+        let outputs = tokio::runtime::Handle::current().block_on(async {
+            let executions = futures::stream::iter(elements.into_iter().enumerate())
+                .map(|(index, element)| self.execute_element(&context, index, element))
+                .buffered(self.max_concurrency)
+                .try_collect();
+            tokio::select! {
+                outputs = executions => outputs,
+                () = context.cancellation.cancelled() => Err(DAGError::Cancelled {
+                    node_id: context.node_id.clone(),
+                }),
+            }
+        })?;
+        Ok(Data::List(outputs))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::List(Box::new(self.element_type.clone()))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::List(Box::new(self.output_type.clone()))
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use indexmap::IndexMap;
+
+use crate::component::Data;
+use crate::dag::NodeID;
+use crate::dagir::Edge;
+
+/// Which node outputs an execution returns, and so which of its nodes it
+/// needs to execute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Projection<'a> {
+    /// Every node's output, keyed by node ID
+    AllNodes,
+    /// The outputs of these nodes, under the names they're returned as
+    Outputs(&'a [(NodeID, String)]),
+}
+
+/// The outputs of `targets` under their own IDs, each once
+pub(crate) fn target_outputs(targets: &[NodeID]) -> Vec<(NodeID, String)> {
+    let mut seen = HashSet::new();
+    targets
+        .iter()
+        .filter(|target| seen.insert(*target))
+        .map(|target| (target.clone(), target.clone()))
+        .collect()
+}
+
+impl Projection<'_> {
+    /// The nodes producing the projected outputs and all the nodes they
+    /// depend on, directly or not; `None` if every node's output is
+    /// returned. Other nodes needn't execute.
+    pub(crate) fn needed_nodes(self, edges: &HashMap<NodeID, Vec<Edge>>) -> Option<HashSet<NodeID>> {
+        let Projection::Outputs(outputs) = self else {
+            return None;
+        };
+
+        let mut needed = HashSet::new();
+        let mut pending: Vec<&NodeID> = outputs.iter().map(|(node, _)| node).collect();
+        while let Some(node) = pending.pop() {
+            if needed.insert(node.clone()) {
+                pending.extend(edges.get(node).into_iter().flatten().map(|edge| &edge.source));
+            }
+        }
+        Some(needed)
+    }
+
+    /// Keeps the outputs in `results` that the projection returns, in its
+    /// order and under its names
+    pub(crate) fn apply(self, mut results: IndexMap<NodeID, Data>) -> IndexMap<NodeID, Data> {
+        let Projection::Outputs(outputs) = self else {
+            return results;
+        };
+
+        let mut projected = IndexMap::with_capacity(outputs.len());
+        for (i, (node, name)) in outputs.iter().enumerate() {
+            // Only nodes returned under several names need their output copied
+            let output = if outputs[i + 1..].iter().any(|(other, _)| other == node) {
+                results.get(node).cloned()
+            } else {
+                results.shift_remove(node)
+            };
+            if let Some(output) = output {
+                projected.insert(name.clone(), output);
+            }
+        }
+        projected
+    }
+
+    /// The nodes that `results`, as returned by the projection, came from
+    pub(crate) fn source_nodes(self, results: &IndexMap<NodeID, Data>) -> Vec<NodeID> {
+        match self {
+            Projection::AllNodes => results.keys().cloned().collect(),
+            Projection::Outputs(outputs) => outputs
+                .iter()
+                .filter(|(_, name)| results.contains_key(name))
+                .map(|(node, _)| node.clone())
+                .collect(),
+        }
+    }
+}
+
+/// Tracks which outputs of an execution are still needed, so that those of
+/// intermediate nodes are freed once every dependent has settled rather than
+/// at the end of the execution.
+pub(crate) struct OutputRetention {
+    /// The nodes whose outputs are returned
+    retained: HashSet<NodeID>,
+    /// How many dependents of each other node have yet to settle
+    pending_consumers: Mutex<HashMap<NodeID, usize>>,
+}
+
+impl OutputRetention {
+    /// Retains only the outputs `projection` returns, if it doesn't return
+    /// all of them, out of those of the `executed` nodes
+    pub(crate) fn new(
+        projection: Projection,
+        edges: &HashMap<NodeID, Vec<Edge>>,
+        executed: &HashSet<NodeID>,
+    ) -> Option<Self> {
+        let Projection::Outputs(outputs) = projection else {
+            return None;
+        };
+
+        let retained: HashSet<NodeID> = outputs.iter().map(|(node, _)| node.clone()).collect();
+        let mut pending_consumers: HashMap<NodeID, usize> = HashMap::new();
+        let executed_edges = edges
+            .iter()
+            .filter(|(target, _)| executed.contains(*target))
+            .flat_map(|(_, edges)| edges);
+        for edge in executed_edges {
+            if !retained.contains(&edge.source) {
+                *pending_consumers.entry(edge.source.clone()).or_default() += 1;
+            }
+        }
+        Some(Self {
+            retained,
+            pending_consumers: Mutex::new(pending_consumers),
+        })
+    }
+
+    /// Whether a dependent or the projection still needs the output of
+    /// `node_id`. Callers hold the results lock, so that an output isn't
+    /// stored after its last consumer released it.
+    pub(crate) fn is_needed(&self, node_id: &NodeID) -> bool {
+        self.retained.contains(node_id)
+            || self
+                .pending_consumers
+                .lock()
+                .unwrap()
+                .get(node_id)
+                .is_some_and(|pending| *pending > 0)
+    }
+
+    /// A node with dependencies `edges` settled, so it's done with their
+    /// outputs: frees those from `results` that nothing needs anymore
+    pub(crate) fn release(&self, edges: &[Edge], results: &mut IndexMap<NodeID, Data>) {
+        let mut pending_consumers = self.pending_consumers.lock().unwrap();
+        for edge in edges {
+            if let Some(pending) = pending_consumers.get_mut(&edge.source) {
+                *pending = pending.saturating_sub(1);
+                if *pending == 0 {
+                    results.shift_remove(&edge.source);
+                }
+            }
+        }
+    }
+}
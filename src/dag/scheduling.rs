@@ -0,0 +1,204 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+use tokio::sync::oneshot;
+
+use crate::dag::NodeID;
+use crate::dagir::Edge;
+
+/// Shared by every DAG execution in the process, when set
+static GLOBAL_LIMITER: RwLock<Option<Arc<ConcurrencyLimiter>>> = RwLock::new(None);
+
+/// Limits how many nodes execute at once across all DAG executions in the
+/// process, on top of each DAG's `max_concurrent_nodes`. `None` removes the
+/// limit. Executions that already started keep the limit they started with.
+pub fn set_global_node_limit(limit: Option<NonZeroUsize>) {
+    *GLOBAL_LIMITER
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) =
+        limit.map(|limit| ConcurrencyLimiter::new(limit.get()));
+}
+
+pub(crate) fn global_limiter() -> Option<Arc<ConcurrencyLimiter>> {
+    GLOBAL_LIMITER
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Which of the nodes waiting for a permit gets it first: the greatest key
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct SchedulingKey {
+    /// The node's own `priority`
+    priority: i32,
+    /// With `critical_path_first`, how many nodes the longest path from
+    /// this node to a leaf has
+    critical_path: usize,
+    /// Otherwise, the earlier in the topological order the better
+    order: Reverse<usize>,
+}
+
+/// The scheduling keys of the nodes in `sorted_nodes`, their topological
+/// order
+pub(crate) fn scheduling_keys(
+    sorted_nodes: &[NodeID],
+    edges: &HashMap<NodeID, Vec<Edge>>,
+    priorities: &HashMap<NodeID, i32>,
+    critical_path_first: bool,
+) -> HashMap<NodeID, SchedulingKey> {
+    let mut critical_paths: HashMap<&NodeID, usize> = HashMap::new();
+    if critical_path_first {
+        let mut dependents: HashMap<&NodeID, Vec<&NodeID>> = HashMap::new();
+        for edge in edges.values().flatten() {
+            dependents.entry(&edge.source).or_default().push(&edge.target);
+        }
+        // Dependents come later in the order, so they're done first
+        for node_id in sorted_nodes.iter().rev() {
+            let longest_dependent = dependents
+                .get(node_id)
+                .into_iter()
+                .flatten()
+                .filter_map(|dependent| critical_paths.get(dependent))
+                .max()
+                .copied()
+                .unwrap_or(0);
+            critical_paths.insert(node_id, longest_dependent + 1);
+        }
+    }
+
+    sorted_nodes
+        .iter()
+        .enumerate()
+        .map(|(order, node_id)| {
+            let key = SchedulingKey {
+                priority: priorities.get(node_id).copied().unwrap_or(0),
+                critical_path: critical_paths.get(node_id).copied().unwrap_or(0),
+                order: Reverse(order),
+            };
+            (node_id.clone(), key)
+        })
+        .collect()
+}
+
+/// A semaphore handing its permits to waiters by `SchedulingKey` rather
+/// than first come, first served
+pub(crate) struct ConcurrencyLimiter {
+    state: Mutex<LimiterState>,
+}
+
+struct LimiterState {
+    available: usize,
+    waiting: BinaryHeap<Waiter>,
+    /// Breaks ties between equal keys in favor of the earlier waiter
+    next_arrival: u64,
+}
+
+struct Waiter {
+    key: SchedulingKey,
+    arrival: Reverse<u64>,
+    sender: oneshot::Sender<Permit>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiter {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.key, self.arrival).cmp(&(other.key, other.arrival))
+    }
+}
+
+/// Held while a node executes; gives its permit back when dropped
+pub(crate) struct Permit {
+    limiter: Option<Arc<ConcurrencyLimiter>>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if let Some(limiter) = self.limiter.take() {
+            limiter.release();
+        }
+    }
+}
+
+impl fmt::Debug for ConcurrencyLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        f.debug_struct("ConcurrencyLimiter")
+            .field("available", &state.available)
+            .field("waiting", &state.waiting.len())
+            .finish()
+    }
+}
+
+impl ConcurrencyLimiter {
+    pub(crate) fn new(permits: usize) -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(LimiterState {
+                available: permits,
+                waiting: BinaryHeap::new(),
+                next_arrival: 0,
+            }),
+        })
+    }
+
+    /// Waits for a permit. If the returned future is dropped after the
+    /// permit was handed over, the permit is released with it.
+    pub(crate) async fn acquire(self: &Arc<Self>, key: SchedulingKey) -> Permit {
+        let receiver = {
+            let mut state = self.state.lock().unwrap();
+            if state.available > 0 && state.waiting.is_empty() {
+                state.available -= 1;
+                return Permit {
+                    limiter: Some(Arc::clone(self)),
+                };
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            let arrival = Reverse(state.next_arrival);
+            state.next_arrival += 1;
+            state.waiting.push(Waiter {
+                key,
+                arrival,
+                sender,
+            });
+            receiver
+        };
+
+        receiver
+            .await
+            .expect("waiters are only dropped when handed a permit")
+    }
+
+    fn release(self: &Arc<Self>) {
+        let mut state = self.state.lock().unwrap();
+        while let Some(waiter) = state.waiting.pop() {
+            let permit = Permit {
+                limiter: Some(Arc::clone(self)),
+            };
+            match waiter.sender.send(permit) {
+                Ok(()) => return,
+                // The waiter gave up: the permit is still ours to hand over,
+                // and dropping it as is would release it again
+                Err(mut permit) => {
+                    permit.limiter = None;
+                }
+            }
+        }
+        state.available += 1;
+    }
+}
@@ -0,0 +1,272 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::component::{Component, Data, DataType, Error, Registry};
+use super::retention::Projection;
+use crate::dag::{DAGError, DAGSettings, NodeExecutionContext, NodeID, RequestId, DAG};
+use crate::dagir::{SubDagRef, DAGIR};
+
+/// The `component_type` of the built-in `SubDag` node
+pub const SUB_DAG_COMPONENT_TYPE: &str = "SubDag";
+
+/// A built-in node that executes another DAG as a unit, so that pipelines
+/// can be shared between DAGs without merging them into each other.
+///
+/// Its `config` holds:
+/// - `dag`: the alias of a DAG registered with `Registry::register_dag`, or
+///   an inline `DAGConfig`
+/// - `output` (optional): the sub-DAG node whose output is this node's
+///   output; defaults to the sub-DAG's only declared output, or else its
+///   only leaf node
+/// - `input` (optional): the sub-DAG node that receives this node's input,
+///   if it has dependencies; defaults to the sub-DAG's only root node
+///
+/// The sub-DAG's node IDs are its own: they may repeat IDs of the enclosing
+/// DAG, and only the output node's output is visible from it.
+///
+/// The sub-DAG is cancelled along with the node, e.g. when the enclosing DAG
+/// aborts or passes its deadline, or the node times out.
+pub(crate) struct SubDagNode {
+    dag: DAG,
+    input_node: Option<NodeID>,
+    output_node: NodeID,
+    input_type: DataType,
+    output_type: DataType,
+}
+
+impl SubDagNode {
+    pub(crate) fn build(
+        node_id: &NodeID,
+        config: &Value,
+        registry: &Registry,
+        settings: &DAGSettings,
+        enclosing_aliases: &[String],
+    ) -> Result<Self, String> {
+        let dag = build_sub_dag(
+            SUB_DAG_COMPONENT_TYPE,
+            node_id,
+            config,
+            registry,
+            settings,
+            enclosing_aliases,
+        )?;
+
+        // Without dependencies, the sub-DAG runs on its own inputs, so it
+        // only needs an input node to be bound to dependencies
+        let input_node = if config.get("input").is_some() || dag.root_nodes().len() == 1 {
+            Some(resolve_input_node(SUB_DAG_COMPONENT_TYPE, node_id, config, &dag)?)
+        } else {
+            None
+        };
+        let output_node = resolve_output_node(SUB_DAG_COMPONENT_TYPE, node_id, config, &dag)?;
+
+        let input_type = input_node.as_ref().map_or(DataType::Null, |input_node| {
+            DataType::Optional(Box::new(dag.nodes[input_node].input_type()))
+        });
+        Ok(Self {
+            input_type,
+            output_type: dag.nodes[&output_node].output_type(),
+            dag,
+            input_node,
+            output_node,
+        })
+    }
+
+    /// The hash of the sub-DAG, which may change with its registered alias
+    pub(crate) fn dag_hash(&self) -> u64 {
+        self.dag.ir_hash
+    }
+}
+
+impl Component for SubDagNode {
+    fn configure(_: Value) -> Result<Self, Error> {
+        Err(Error::ConfigurationError(format!(
+            "{SUB_DAG_COMPONENT_TYPE} nodes are built with their DAG, not configured through the registry"
+        )))
+    }
+
+    /// Runs on a blocking thread like any Component, so we drive the
+    /// sub-DAG execution on the runtime from here.
+    fn execute(&self, context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
+        let mut inputs: HashMap<NodeID, Data> = (*self.dag.initial_inputs).clone();
+        if let (Some(input_node), false) = (&self.input_node, matches!(input, Data::Null)) {
+            inputs.insert(input_node.clone(), input);
+        }
+        println!(
+            "SubDag {}: executing DAG {} for its node {}",
+            context.node_id, self.dag.alias, self.output_node
+        );
+
+        let request_id = format!("{}/{}", context.request_id, context.node_id);
+        tokio::runtime::Handle::current().block_on(execute_for_output(
+            &self.dag,
+            &context,
+            request_id,
+            &format!("DAG {}", self.dag.alias),
+            inputs,
+            &self.output_node,
+        ))
+    }
+
+    fn input_type(&self) -> DataType {
+        self.input_type.clone()
+    }
+
+    fn output_type(&self) -> DataType {
+        self.output_type.clone()
+    }
+}
+
+/// Builds the DAG that the `kind` node `node_id` refers to in its config.
+/// `enclosing_aliases` are the DAGs the node is nested in, which its sub-DAG
+/// may not be.
+pub(crate) fn build_sub_dag(
+    kind: &str,
+    node_id: &NodeID,
+    config: &Value,
+    registry: &Registry,
+    settings: &DAGSettings,
+    enclosing_aliases: &[String],
+) -> Result<DAG, String> {
+    let dag_config = SubDagRef::from_node_config(config)
+        .and_then(|dag_ref| dag_ref.resolve(registry))
+        .map_err(|e| format!("{kind} node {node_id}: {e}"))?;
+    if enclosing_aliases.contains(&dag_config.alias) {
+        return Err(format!(
+            "{kind} node {node_id}: DAG {} would contain itself",
+            dag_config.alias
+        ));
+    }
+
+    // Caching, the deadline and the concurrency limit apply to the enclosing
+    // DAG as a whole: sub-DAG executions share its execution's limiter
+    let sub_settings = DAGSettings {
+        enable_memory_cache: false,
+        enable_history: false,
+        dag_deadline_ms: None,
+        max_concurrent_nodes: None,
+        ..settings.clone()
+    };
+    DAGIR::from_config(dag_config)
+        .and_then(|ir| DAG::from_ir_within(&ir, registry, sub_settings, None, enclosing_aliases))
+        .map_err(|e| format!("{kind} node {node_id}: sub-DAG: {e}"))
+}
+
+/// The sub-DAG node named by `config["input"]`, or else its only root node
+pub(crate) fn resolve_input_node(
+    kind: &str,
+    node_id: &NodeID,
+    config: &Value,
+    dag: &DAG,
+) -> Result<NodeID, String> {
+    let input_node = resolve_node(kind, node_id, config, "input", dag, &dag.root_nodes())?;
+    if dag.edges.contains_key(&input_node) {
+        return Err(format!(
+            "{kind} node {node_id}: input node {input_node} of the sub-DAG must not have dependencies"
+        ));
+    }
+    Ok(input_node)
+}
+
+/// The sub-DAG node named by `config["output"]`, or else its only declared
+/// output, or else its only leaf node
+pub(crate) fn resolve_output_node(
+    kind: &str,
+    node_id: &NodeID,
+    config: &Value,
+    dag: &DAG,
+) -> Result<NodeID, String> {
+    let candidates = if dag.outputs.is_empty() {
+        dag.leaf_nodes()
+    } else {
+        dag.outputs.iter().map(|(node, _)| node).collect()
+    };
+    resolve_node(kind, node_id, config, "output", dag, &candidates)
+}
+
+fn resolve_node(
+    kind: &str,
+    node_id: &NodeID,
+    config: &Value,
+    key: &str,
+    dag: &DAG,
+    candidates: &[&NodeID],
+) -> Result<NodeID, String> {
+    if let Some(name) = config.get(key) {
+        let name = name
+            .as_str()
+            .ok_or_else(|| format!("{kind} node {node_id}: `{key}` must be a node ID"))?;
+        if !dag.nodes.contains_key(name) {
+            return Err(format!(
+                "{kind} node {node_id}: {key} node {name} is not in the sub-DAG"
+            ));
+        }
+        return Ok(name.to_string());
+    }
+
+    match candidates {
+        [only] => Ok((*only).clone()),
+        _ => Err(format!(
+            "{kind} node {node_id}: the sub-DAG has several candidate {key} nodes {candidates:?}, so `{key}` must name one"
+        )),
+    }
+}
+
+/// Executes the sub-DAG with `inputs`, returning the output of `output_node`
+/// as the output of the node in `context`. Errors are prefixed with `label`.
+///
+/// Cancelling the node cancels the sub-DAG, which then fails as `Cancelled`.
+/// The sub-DAG has a token of its own, so that it can abort without
+/// cancelling the node.
+pub(crate) async fn execute_for_output(
+    dag: &DAG,
+    context: &NodeExecutionContext,
+    request_id: RequestId,
+    label: &str,
+    inputs: HashMap<NodeID, Data>,
+    output_node: &NodeID,
+) -> Result<Data, DAGError> {
+    if context.is_cancelled() {
+        return Err(DAGError::Cancelled {
+            node_id: context.node_id.clone(),
+        });
+    }
+
+    let outputs = [(output_node.clone(), output_node.clone())];
+    let mut report = dag
+        .execute_within(
+            Some(request_id),
+            Arc::new(inputs),
+            Projection::Outputs(&outputs),
+            context.cancellation.child_token(),
+            context.dag_limiter.clone(),
+        )
+        .await
+        .map_err(|e| {
+            let reason = format!("{label}: {e}");
+            if context.is_cancelled() {
+                DAGError::Cancelled {
+                    node_id: context.node_id.clone(),
+                }
+            } else if e.is_transient() {
+                DAGError::TransientError {
+                    node_id: context.node_id.clone(),
+                    reason,
+                }
+            } else {
+                DAGError::ExecutionError {
+                    node_id: context.node_id.clone(),
+                    reason,
+                }
+            }
+        })?;
+
+    report
+        .results
+        .shift_remove(output_node)
+        .ok_or_else(|| DAGError::ExecutionError {
+            node_id: context.node_id.clone(),
+            reason: format!("{label}: sub-DAG has no output for node {output_node}"),
+        })
+}
@@ -193,4 +193,43 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_output_retention_frees_consumed_outputs() {
+        use crate::dag::retention::{OutputRetention, Projection};
+        use crate::dagir::Edge;
+        use indexmap::IndexMap;
+        use std::collections::HashMap;
+
+        let edge = |source: &str, target: &str| Edge {
+            source: source.to_string(),
+            target: target.to_string(),
+            port: None,
+        };
+        let edges = HashMap::from([
+            ("b".to_string(), vec![edge("a", "b")]),
+            ("c".to_string(), vec![edge("a", "c"), edge("b", "c")]),
+        ]);
+        let outputs = [("c".to_string(), "c".to_string())];
+        let projection = Projection::Outputs(&outputs);
+        let needed = projection.needed_nodes(&edges).expect("Some outputs");
+        assert_eq!(needed.len(), 3);
+        let retention = OutputRetention::new(projection, &edges, &needed).expect("Some outputs");
+        assert!(OutputRetention::new(Projection::AllNodes, &edges, &needed).is_none());
+
+        let mut results: IndexMap<String, Data> = ["a", "b", "c"]
+            .into_iter()
+            .map(|id| (id.to_string(), Data::Null))
+            .collect();
+        assert!(retention.is_needed(&"a".to_string()));
+
+        // `a` is still needed by `c`
+        retention.release(&edges["b"], &mut results);
+        assert!(results.contains_key("a"));
+
+        retention.release(&edges["c"], &mut results);
+        assert_eq!(results.keys().collect::<Vec<_>>(), vec!["c"]);
+        assert!(!retention.is_needed(&"a".to_string()));
+        assert!(retention.is_needed(&"c".to_string()));
+    }
 }
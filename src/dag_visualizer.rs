@@ -2,7 +2,9 @@ use std::collections::{HashMap, HashSet};
 
 use ascii_tree::Tree;
 
-use crate::dagir::DAGIR;
+use crate::component::Registry;
+use crate::dag::{MAP_COMPONENT_TYPE, SUB_DAG_COMPONENT_TYPE};
+use crate::dagir::{SubDagRef, DAGIR};
 
 
 #[derive(Debug, Clone, Copy)]
@@ -14,93 +16,144 @@ pub enum TreeView {
 }
 
 impl DAGIR {
+    /// Builds a tree of the DAG's nodes. `Map` and `SubDag` nodes with an
+    /// inline sub-DAG get it as a subtree of their own, which can be
+    /// collapsed; those referring to a registered DAG by alias are only
+    /// labelled with it (see `build_tree_with_registry`).
     #[must_use]
     pub fn build_tree(&self, view: TreeView) -> Tree {
-        match view {
-            TreeView::Execution => {
-                // Build a map of each node to its children
-                let mut node_map: HashMap<&String, Vec<&String>> = HashMap::new();
-
-                // For each node, look at its dependencies to build parent->child relationships
-                for node in self.nodes.iter() {
-                    if let Some(edges) = self.edges.get(&node.id) {
-                        node_map.entry(&node.id).or_default(); // Ensure every node has an entry
-                        for edge in edges {
-                            node_map.entry(&edge.source).or_default().push(&edge.target);
-                        }
-                    } else {
-                        node_map.entry(&node.id).or_default(); // Handle nodes with no edges
-                    }
-                }
+        self.build_tree_within(view, None, &[])
+    }
 
-                // Find start nodes (roots for execution, leaves for dependency)
-                let all_targets: HashSet<_> = self
-                    .edges
-                    .values()
-                    .flat_map(|edges| edges.iter().map(|edge| &edge.target))
-                    .collect();
-                let start_nodes: Vec<_> = self
-                    .nodes
-                    .iter()
-                    .map(|node| &node.id)
-                    .filter(|id| !all_targets.contains(id))
-                    .collect();
+    /// Like `build_tree`, additionally expanding the sub-DAGs that `Map` and
+    /// `SubDag` nodes refer to by alias, as registered in `registry`.
+    #[must_use]
+    pub fn build_tree_with_registry(&self, view: TreeView, registry: &Registry) -> Tree {
+        self.build_tree_within(view, Some(registry), &[])
+    }
 
-                Self::build_subtree(&format!("DAG:{view:?}"), &start_nodes, &node_map, view)
-            }
-            TreeView::Dependency => {
-                // Build a map of each node to its parents
-                let mut node_map: HashMap<&String, Vec<&String>> = HashMap::new();
+    /// Like `build_tree`, for the nodes in `namespace` only
+    #[must_use]
+    pub fn build_namespace_tree(&self, view: TreeView, namespace: &str) -> Tree {
+        self.filter_namespace(namespace).build_tree(view)
+    }
 
-                for node in self.nodes.iter() {
-                    node_map.entry(&node.id).or_default();
-                    if let Some(edges) = self.edges.get(&node.id) {
-                        for edge in edges {
+    fn build_tree_within(
+        &self,
+        view: TreeView,
+        registry: Option<&Registry>,
+        enclosing_aliases: &[String],
+    ) -> Tree {
+        // Map each node to its children (execution) or parents (dependency)
+        let mut node_map: HashMap<&String, Vec<&String>> = HashMap::new();
+        for node in self.nodes.iter() {
+            node_map.entry(&node.id).or_default(); // Ensure every node has an entry
+            if let Some(edges) = self.edges.get(&node.id) {
+                for edge in edges {
+                    match view {
+                        TreeView::Execution => {
+                            node_map.entry(&edge.source).or_default().push(&edge.target);
+                        }
+                        TreeView::Dependency => {
                             node_map.entry(&edge.target).or_default().push(&edge.source);
                         }
                     }
                 }
-
-                // Find leaf nodes (nodes with no children)
-                let all_sources: HashSet<_> = self
-                    .edges
-                    .values()
-                    .flat_map(|edges| edges.iter().map(|edge| &edge.source))
-                    .collect();
-                let start_nodes: Vec<_> = self
-                    .nodes
-                    .iter()
-                    .map(|node| &node.id)
-                    .filter(|id| !all_sources.contains(id))
-                    .collect();
-
-                Self::build_subtree(&format!("DAG:{view:?}"), &start_nodes, &node_map, view)
             }
         }
+
+        // Start from roots for execution, leaves for dependency
+        let non_start_nodes: HashSet<_> = self
+            .edges
+            .values()
+            .flat_map(|edges| {
+                edges.iter().map(|edge| match view {
+                    TreeView::Execution => &edge.target,
+                    TreeView::Dependency => &edge.source,
+                })
+            })
+            .collect();
+        let start_nodes: Vec<_> = self
+            .nodes
+            .iter()
+            .map(|node| &node.id)
+            .filter(|id| !non_start_nodes.contains(id))
+            .collect();
+
+        let enclosing_aliases = [enclosing_aliases, std::slice::from_ref(&self.alias)].concat();
+        let sub_dags = self.sub_dag_trees(view, registry, &enclosing_aliases);
+
+        let children = start_nodes
+            .iter()
+            .map(|id| Self::build_subtree(id, &node_map, &sub_dags))
+            .collect();
+        Tree::Node(format!("DAG:{view:?}"), children)
+    }
+
+    /// Labels for the `Map` and `SubDag` nodes, along with the trees of their
+    /// sub-DAGs where these are known
+    fn sub_dag_trees(
+        &self,
+        view: TreeView,
+        registry: Option<&Registry>,
+        enclosing_aliases: &[String],
+    ) -> HashMap<&String, (String, Option<Tree>)> {
+        self.nodes
+            .iter()
+            .filter(|node| {
+                node.component_type == MAP_COMPONENT_TYPE
+                    || node.component_type == SUB_DAG_COMPONENT_TYPE
+            })
+            .filter_map(|node| {
+                let dag_ref = SubDagRef::from_node_config(&node.config).ok()?;
+                let label = format!("{} [{} {}]", node.id, node.component_type, dag_ref.alias());
+                // A DAG that would contain itself is only labelled
+                let tree = if enclosing_aliases.iter().any(|alias| alias == dag_ref.alias()) {
+                    None
+                } else {
+                    let config = match dag_ref {
+                        SubDagRef::Inline(config) => Some(*config),
+                        SubDagRef::Alias(alias) => {
+                            registry.and_then(|registry| registry.get_dag(&alias).cloned())
+                        }
+                    };
+                    config
+                        .and_then(|config| DAGIR::from_config(config).ok())
+                        .map(|ir| {
+                            let Tree::Node(_, children) =
+                                ir.build_tree_within(view, registry, enclosing_aliases)
+                            else {
+                                unreachable!("DAG trees have a root node")
+                            };
+                            Tree::Node(format!("sub-DAG {}", ir.alias), children)
+                        })
+                };
+                Some((&node.id, (label, tree)))
+            })
+            .collect()
     }
 
-    #[allow(clippy::items_after_statements)]
     fn build_subtree<'a>(
-        node_id: &str,
-        start_nodes: &[&'a String],
+        node_id: &'a String,
         node_map: &HashMap<&'a String, Vec<&'a String>>,
-        view: TreeView,
+        sub_dags: &HashMap<&'a String, (String, Option<Tree>)>,
     ) -> Tree {
-        if node_id == format!("DAG:{view:?}") {
-            let children = start_nodes
-                .iter()
-                .map(|id| Self::build_subtree(id, start_nodes, node_map, view))
-                .collect();
-            return Tree::Node(node_id.to_string(), children);
-        }
+        let (label, sub_dag) = match sub_dags.get(node_id) {
+            Some((label, sub_dag)) => (label.clone(), sub_dag.clone()),
+            None => (node_id.clone(), None),
+        };
 
-        let children = node_map
-            .get(&node_id.to_string())
-            .unwrap_or(&vec![])
-            .iter()
-            .map(|child_id| Self::build_subtree(child_id, start_nodes, node_map, view))
+        let children = sub_dag
+            .into_iter()
+            .chain(
+                node_map
+                    .get(node_id)
+                    .unwrap_or(&vec![])
+                    .iter()
+                    .map(|child_id| Self::build_subtree(child_id, node_map, sub_dags)),
+            )
             .collect::<Vec<Tree>>();
 
-        Tree::Node(node_id.to_string(), children)
+        Tree::Node(label, children)
     }
 }
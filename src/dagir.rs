@@ -9,7 +9,10 @@ use std::collections::VecDeque;
 use std::hash::Hash;
 use std::hash::Hasher;
 
-use crate::{component::Data, dag::NodeID};
+use crate::{
+    component::{Data, Registry},
+    dag::{JoinPolicy, NodeID, RetryPolicy},
+};
 
 #[derive(Debug, Clone)]
 pub(crate) struct NodeIR {
@@ -18,6 +21,13 @@ pub(crate) struct NodeIR {
     pub(crate) component_type: String,
     pub(crate) config: Value,
     pub(crate) inputs: Option<Data>,
+    pub(crate) retry: Option<RetryPolicy>,
+    pub(crate) timeout_ms: Option<u64>,
+    pub(crate) when: Option<String>,
+    pub(crate) join: JoinPolicy,
+    pub(crate) priority: i32,
+    pub(crate) cacheable: bool,
+    pub(crate) cache_ttl_ms: Option<u64>,
 }
 
 impl Eq for NodeIR {}
@@ -45,12 +55,17 @@ pub struct DAGIR {
     pub(crate) alias: String,
     pub(crate) nodes: SortedVec<NodeIR>,
     pub(crate) edges: BTreeMap<NodeID, Vec<Edge>>,
+    /// The declared outputs, as the node and the name its output is
+    /// returned under, in order
+    pub(crate) outputs: Vec<(NodeID, String)>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub(crate) struct Edge {
     pub(crate) source: NodeID,
     pub(crate) target: NodeID,
+    /// The named input port of the target that receives the source's output
+    pub(crate) port: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -59,6 +74,10 @@ pub struct DAGConfig {
     #[serde(default)]
     pub metadata: Option<Value>,
     pub nodes: Vec<NodeConfig>,
+    /// The nodes whose outputs an execution returns, in order. Executions
+    /// return every node's output when there are none.
+    #[serde(default)]
+    pub outputs: Vec<Output>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -66,12 +85,219 @@ pub struct NodeConfig {
     pub id: String,
     pub component_type: String,
     pub config: Value,
+    /// Scopes the node's ID as `namespace::id`; its `depends_on` are then
+    /// resolved relative to the namespace
     #[serde(default)]
     pub namespace: Option<String>,
     #[serde(default)]
     pub inputs: Option<Value>,
     #[serde(default)]
-    pub depends_on: Vec<String>,
+    pub depends_on: Vec<Dependency>,
+    /// Overrides the DAG-wide retry policy for this node
+    #[serde(default)]
+    pub retry: Option<RetryPolicy>,
+    /// Overrides the DAG-wide `per_node_timeout_ms` for this node
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// A jq predicate evaluated against the node's input; the node is
+    /// bypassed rather than executed when it yields `false` or `null`
+    #[serde(default)]
+    pub when: Option<String>,
+    /// How the node treats dependencies that were bypassed (default: `all`)
+    #[serde(default)]
+    pub join: Option<JoinPolicy>,
+    /// Nodes with a higher priority execute first when concurrency is
+    /// limited (default: 0)
+    #[serde(default)]
+    pub priority: Option<i32>,
+    /// Memoize the node's output by its component type, config and actual
+    /// input, so that executions with the same input reuse it (default:
+    /// `false`). Nodes of `Map` and `SubDag` sub-DAGs aren't memoized on
+    /// their own; the `Map` or `SubDag` node can be.
+    #[serde(default)]
+    pub cacheable: Option<bool>,
+    /// How long a memoized output is reused; until evicted if unset
+    #[serde(default)]
+    pub cache_ttl_ms: Option<u64>,
+}
+
+/// An entry of a node's `depends_on`: either a plain node ID, whose output is
+/// passed positionally, or `{"from": ..., "as": ...}`, binding the output to
+/// a named input port.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Dependency {
+    Node(NodeID),
+    Port {
+        from: NodeID,
+        #[serde(rename = "as")]
+        port: String,
+    },
+}
+
+impl Dependency {
+    /// Binds the output of `from` to the input port `port`
+    pub fn named(from: impl Into<String>, port: impl Into<String>) -> Self {
+        Dependency::Port {
+            from: from.into(),
+            port: port.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn source(&self) -> &str {
+        match self {
+            Dependency::Node(source) | Dependency::Port { from: source, .. } => source,
+        }
+    }
+
+    #[must_use]
+    pub fn port(&self) -> Option<&str> {
+        match self {
+            Dependency::Node(_) => None,
+            Dependency::Port { port, .. } => Some(port),
+        }
+    }
+}
+
+impl From<&str> for Dependency {
+    fn from(source: &str) -> Self {
+        Dependency::Node(source.to_string())
+    }
+}
+
+impl From<String> for Dependency {
+    fn from(source: String) -> Self {
+        Dependency::Node(source)
+    }
+}
+
+/// An entry of a DAG's `outputs`: either a node ID, whose output is returned
+/// under that ID, or `{"node": ..., "as": ...}`, returning it under another
+/// name.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Output {
+    Node(NodeID),
+    Renamed {
+        node: NodeID,
+        #[serde(rename = "as")]
+        name: String,
+    },
+}
+
+impl Output {
+    /// Returns the output of `node` under `name`
+    pub fn renamed(node: impl Into<String>, name: impl Into<String>) -> Self {
+        Output::Renamed {
+            node: node.into(),
+            name: name.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn node(&self) -> &str {
+        match self {
+            Output::Node(node) | Output::Renamed { node, .. } => node,
+        }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
+            Output::Node(name) | Output::Renamed { name, .. } => name,
+        }
+    }
+}
+
+impl From<&str> for Output {
+    fn from(node: &str) -> Self {
+        Output::Node(node.to_string())
+    }
+}
+
+impl From<String> for Output {
+    fn from(node: String) -> Self {
+        Output::Node(node)
+    }
+}
+
+/// Separates a node's namespaces from its ID in fully-qualified node IDs, as
+/// in `ns::node`. A leading separator, as in `::node`, refers to a node
+/// outside of any namespace.
+pub const NAMESPACE_SEPARATOR: &str = "::";
+
+/// Whether `node_id` is in `namespace`, or in a namespace nested in it
+#[must_use]
+pub fn in_namespace(node_id: &str, namespace: &str) -> bool {
+    node_id
+        .strip_prefix(namespace)
+        .is_some_and(|rest| rest.starts_with(NAMESPACE_SEPARATOR))
+}
+
+/// The namespace of a fully-qualified node ID, if it has one
+fn namespace_of(node_id: &str) -> Option<&str> {
+    node_id
+        .rsplit_once(NAMESPACE_SEPARATOR)
+        .map(|(namespace, _)| namespace)
+}
+
+/// Resolves a dependency of the node `node_id` to a fully-qualified ID. The
+/// dependency is relative to the node's namespace: it refers to the node of
+/// the innermost enclosing namespace that has it (other than the dependent
+/// node itself), or else to the node with exactly that ID.
+fn resolve_dependency(source: &str, node_id: &str, node_ids: &HashSet<NodeID>) -> NodeID {
+    if let Some(absolute) = source.strip_prefix(NAMESPACE_SEPARATOR) {
+        return absolute.to_string();
+    }
+
+    let mut scope = namespace_of(node_id);
+    while let Some(current) = scope {
+        let candidate = format!("{current}{NAMESPACE_SEPARATOR}{source}");
+        if candidate != node_id && node_ids.contains(&candidate) {
+            return candidate;
+        }
+        scope = namespace_of(current);
+    }
+    source.to_string()
+}
+
+/// The DAG that a `Map` or `SubDag` node's `config["dag"]` refers to
+#[derive(Debug, Clone)]
+pub(crate) enum SubDagRef {
+    /// A DAG registered with `Registry::register_dag`
+    Alias(String),
+    Inline(Box<DAGConfig>),
+}
+
+impl SubDagRef {
+    pub(crate) fn from_node_config(config: &Value) -> Result<Self, String> {
+        match &config["dag"] {
+            Value::String(alias) => Ok(SubDagRef::Alias(alias.clone())),
+            inline @ Value::Object(_) => serde_json::from_value(inline.clone())
+                .map(|config| SubDagRef::Inline(Box::new(config)))
+                .map_err(|e| format!("invalid inline DAG: {e}")),
+            _ => Err("`dag` must be a DAG alias or an inline DAG configuration".to_string()),
+        }
+    }
+
+    pub(crate) fn alias(&self) -> &str {
+        match self {
+            SubDagRef::Alias(alias) => alias,
+            SubDagRef::Inline(config) => &config.alias,
+        }
+    }
+
+    /// The referenced configuration, looking aliases up in the registry
+    pub(crate) fn resolve(self, registry: &Registry) -> Result<DAGConfig, String> {
+        match self {
+            SubDagRef::Alias(alias) => registry
+                .get_dag(&alias)
+                .cloned()
+                .ok_or_else(|| format!("no DAG registered with alias {alias}")),
+            SubDagRef::Inline(config) => Ok(*config),
+        }
+    }
 }
 
 impl DAGIR {
@@ -113,12 +339,17 @@ impl DAGIR {
             return Err("Alias cannot be empty".to_string());
         }
 
+        let node_ids = Self::qualified_node_ids(&config.nodes)?;
+
         let mut nodes = SortedVec::new();
         let mut edges = BTreeMap::new();
 
         for node in config.nodes {
-            if node.id.is_empty() {
-                return Err("Node ID cannot be empty".to_string());
+            let id = node.qualified_id();
+            let namespace = namespace_of(&id);
+
+            if node.cache_ttl_ms.is_some() && node.cacheable != Some(true) {
+                return Err(format!("Node {id} sets cache_ttl_ms, but isn't cacheable"));
             }
 
             let inputs = match node.inputs {
@@ -127,56 +358,170 @@ impl DAGIR {
             };
 
             nodes.push(NodeIR {
-                id: node.id.clone(),
-                namespace: node.namespace,
+                id: id.clone(),
+                namespace: namespace.map(str::to_string),
                 component_type: node.component_type,
                 config: node.config,
                 inputs,
+                retry: node.retry,
+                timeout_ms: node.timeout_ms,
+                when: node.when,
+                join: node.join.unwrap_or_default(),
+                priority: node.priority.unwrap_or(0),
+                cacheable: node.cacheable.unwrap_or(false),
+                cache_ttl_ms: node.cache_ttl_ms,
             });
 
             if !node.depends_on.is_empty() {
+                let named_ports = node.depends_on.iter().filter(|dep| dep.port().is_some()).count();
+                if named_ports != 0 && named_ports != node.depends_on.len() {
+                    return Err(format!(
+                        "Node {id} mixes named and unnamed dependencies; either all or none must use `as`"
+                    ));
+                }
+
+                if named_ports != 0 && node.join == Some(JoinPolicy::FirstAvailable) {
+                    return Err(format!(
+                        "Node {id} joins on its first available dependency, so its dependencies can't be bound to named ports"
+                    ));
+                }
+
+                let mut ports = HashSet::new();
+                for port in node.depends_on.iter().filter_map(Dependency::port) {
+                    if !ports.insert(port) {
+                        return Err(format!(
+                            "Node {id} binds more than one dependency to port {port}"
+                        ));
+                    }
+                }
+
                 let node_edges = node
                     .depends_on
                     .into_iter()
-                    .map(|source| Edge {
-                        source,
-                        target: node.id.clone(),
+                    .map(|dep| Edge {
+                        source: resolve_dependency(dep.source(), &id, &node_ids),
+                        target: id.clone(),
+                        port: dep.port().map(str::to_string),
                     })
                     .collect();
-                edges.insert(node.id, node_edges);
+                edges.insert(id, node_edges);
             }
         }
 
+        let outputs = Self::resolve_outputs(&config.outputs, &node_ids)?;
+
         Ok(DAGIR {
             alias: config.alias,
             nodes,
             edges,
+            outputs,
         })
     }
 
+    /// The fully-qualified nodes of `outputs` and the names they're
+    /// returned under. Outputs are relative to the DAG rather than to any
+    /// namespace, so a leading `::` is optional.
+    fn resolve_outputs(
+        outputs: &[Output],
+        node_ids: &HashSet<NodeID>,
+    ) -> Result<Vec<(NodeID, String)>, String> {
+        let mut names = HashSet::new();
+        let mut resolved = Vec::with_capacity(outputs.len());
+        for output in outputs {
+            let node = output.node();
+            let node = node.strip_prefix(NAMESPACE_SEPARATOR).unwrap_or(node);
+            if !node_ids.contains(node) {
+                return Err(format!("Output node {node} is not in the DAG"));
+            }
+            if output.name().is_empty() {
+                return Err(format!("Output of node {node}: name cannot be empty"));
+            }
+            if !names.insert(output.name()) {
+                return Err(format!("More than one output is named {}", output.name()));
+            }
+            resolved.push((node.to_string(), output.name().to_string()));
+        }
+        Ok(resolved)
+    }
+
+    /// The fully-qualified IDs of `nodes`
+    fn qualified_node_ids(nodes: &[NodeConfig]) -> Result<HashSet<NodeID>, String> {
+        let mut node_ids = HashSet::new();
+        for node in nodes {
+            if node.id.is_empty() {
+                return Err("Node ID cannot be empty".to_string());
+            }
+            if node.namespace.as_ref().is_some_and(String::is_empty) {
+                return Err(format!("Node {}: namespace cannot be empty", node.id));
+            }
+            node_ids.insert(node.qualified_id());
+        }
+        Ok(node_ids)
+    }
+
+    /// A view of the DAG restricted to the nodes in `namespace` (including
+    /// its nested namespaces) and the edges between them
+    #[must_use]
+    pub fn filter_namespace(&self, namespace: &str) -> DAGIR {
+        let nodes = self
+            .nodes
+            .iter()
+            .filter(|node| in_namespace(&node.id, namespace))
+            .cloned()
+            .collect::<Vec<_>>();
+        let edges = self
+            .edges
+            .iter()
+            .filter(|(target, _)| in_namespace(target, namespace))
+            .filter_map(|(target, edges)| {
+                let edges: Vec<Edge> = edges
+                    .iter()
+                    .filter(|edge| in_namespace(&edge.source, namespace))
+                    .cloned()
+                    .collect();
+                (!edges.is_empty()).then(|| (target.clone(), edges))
+            })
+            .collect();
+
+        let outputs = self
+            .outputs
+            .iter()
+            .filter(|(node, _)| in_namespace(node, namespace))
+            .cloned()
+            .collect();
+
+        DAGIR {
+            alias: format!("{}{NAMESPACE_SEPARATOR}{namespace}", self.alias),
+            nodes: SortedVec::from_unsorted(nodes),
+            edges,
+            outputs,
+        }
+    }
+
+    /// Inputs as `Data`: integers are `Integer` if they fit in 32 bits, else
+    /// `Int64`, and arrays are lists of their parsed items
     fn parse_input_value(value: &Value) -> Result<Data, String> {
         match value {
+            Value::Null => Ok(Data::Null),
+            Value::Bool(b) => Ok(Data::Bool(*b)),
             Value::String(s) => Ok(Data::Text(s.clone())),
-            Value::Number(n) => n
-                .as_i64()
-                .map(|i| Data::Integer(i32::try_from(i).unwrap()))
-                .ok_or_else(|| "Unsupported number type in inputs".to_string()),
-            Value::Array(arr) => {
-                let data_list = arr
-                    .iter()
-                    .map(|item| match item {
-                        Value::String(s) => Ok(Data::Text(s.clone())),
-                        Value::Number(n) => n
-                            .as_i64()
-                            .map(|i| Data::Integer(i32::try_from(i).unwrap()))
-                            .ok_or_else(|| "Unsupported number type in array input".to_string()),
-                        _ => Err("Unsupported type in array input".to_string()),
-                    })
-                    .collect::<Result<Vec<_>, String>>()?;
-                Ok(Data::List(data_list))
+            Value::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    Ok(i32::try_from(i).map_or(Data::Int64(i), Data::Integer))
+                } else if n.is_f64() {
+                    n.as_f64()
+                        .map(Data::Float)
+                        .ok_or_else(|| format!("Unsupported number {n} in inputs"))
+                } else {
+                    Err(format!("Number {n} in inputs doesn't fit in 64 bits"))
+                }
             }
+            Value::Array(arr) => arr
+                .iter()
+                .map(Self::parse_input_value)
+                .collect::<Result<Vec<_>, String>>()
+                .map(Data::List),
             Value::Object(_) => Ok(Data::Json(value.clone())),
-            _ => Err("Unsupported input type".to_string()),
         }
     }
 
@@ -190,6 +535,8 @@ impl DAGIR {
             node.component_type.hash(&mut hasher);
             node.config.to_string().hash(&mut hasher);
             node.inputs.hash(&mut hasher);
+            node.when.hash(&mut hasher);
+            node.join.hash(&mut hasher);
         }
 
         for (target, edges) in &self.edges {
@@ -201,6 +548,8 @@ impl DAGIR {
             }
         }
 
+        self.outputs.hash(&mut hasher);
+
         hasher.finish()
     }
 }
@@ -212,6 +561,7 @@ impl DAGConfig {
             alias: alias.into(),
             metadata: None,
             nodes: Vec::new(),
+            outputs: Vec::new(),
         }
     }
 
@@ -239,13 +589,21 @@ impl DAGConfig {
         self
     }
 
+    /// Declares an output of the DAG, either a node ID or `Output::renamed`
+    #[must_use]
+    pub fn with_output(mut self, output: impl Into<Output>) -> Self {
+        self.outputs.push(output.into());
+        self
+    }
+
     /// Merges this DAG configuration with another, creating a new configuration that combines both.
     ///
     /// The merge process:
     /// 1. Preserves the base configuration while applying overrides
     /// 2. Tracks whether changes are structural (new nodes/edges) or config-only
     /// 3. Updates metadata including version bumps and merge history
-    /// 4. Validates the resulting graph structure
+    /// 4. Adds the other's outputs, replacing any of the same name
+    /// 5. Validates the resulting graph structure
     ///
     /// # Arguments
     ///
@@ -298,16 +656,16 @@ impl DAGConfig {
         let mut node_map: BTreeMap<NodeID, NodeConfig> = self
             .nodes
             .iter()
-            .map(|n| (n.id.clone(), n.clone()))
+            .map(|n| (n.qualified_id(), n.clone()))
             .collect();
 
         for override_node in &other.nodes {
-            match node_map.get_mut(&override_node.id) {
+            match node_map.get_mut(&override_node.qualified_id()) {
                 Some(existing_node) => {
                     if override_node.component_type != existing_node.component_type {
                         return Err(format!(
                             "Cannot change component type for node {}: {} -> {}",
-                            override_node.id,
+                            override_node.qualified_id(),
                             existing_node.component_type,
                             override_node.component_type
                         ));
@@ -325,7 +683,35 @@ impl DAGConfig {
                         existing_node.inputs.clone_from(&override_node.inputs);
                     }
 
-                    let new_deps: Vec<String> = override_node
+                    if override_node.retry.is_some() {
+                        existing_node.retry.clone_from(&override_node.retry);
+                    }
+
+                    if override_node.timeout_ms.is_some() {
+                        existing_node.timeout_ms = override_node.timeout_ms;
+                    }
+
+                    if override_node.when.is_some() {
+                        existing_node.when.clone_from(&override_node.when);
+                    }
+
+                    if override_node.join.is_some() {
+                        existing_node.join = override_node.join;
+                    }
+
+                    if override_node.priority.is_some() {
+                        existing_node.priority = override_node.priority;
+                    }
+
+                    if override_node.cacheable.is_some() {
+                        existing_node.cacheable = override_node.cacheable;
+                    }
+
+                    if override_node.cache_ttl_ms.is_some() {
+                        existing_node.cache_ttl_ms = override_node.cache_ttl_ms;
+                    }
+
+                    let new_deps: Vec<Dependency> = override_node
                         .depends_on
                         .iter()
                         .filter(|d| !existing_node.depends_on.contains(d))
@@ -335,27 +721,95 @@ impl DAGConfig {
                 }
 
                 _ => {
-                    node_map.insert(override_node.id.clone(), override_node.clone());
+                    node_map.insert(override_node.qualified_id(), override_node.clone());
                 }
             }
         }
 
         merged.nodes = node_map.into_values().collect();
+
+        for output in &other.outputs {
+            match merged.outputs.iter_mut().find(|o| o.name() == output.name()) {
+                Some(existing) => existing.clone_from(output),
+                None => merged.outputs.push(output.clone()),
+            }
+        }
         Self::validate_graph_structure(&merged)?;
 
         Ok(merged)
     }
 
+    /// Merges `other` into this configuration after moving all of its nodes
+    /// under `namespace` (see `in_namespace`), so that its node IDs can't
+    /// collide with this DAG's. Nodes of this DAG, or of overrides merged
+    /// later, can depend on the imported ones as `namespace::node`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `namespace` is empty, or for the reasons `merge`
+    /// does.
+    pub fn merge_under_namespace(
+        &self,
+        other: &DAGConfig,
+        namespace: &str,
+    ) -> Result<DAGConfig, String> {
+        self.merge(&other.in_namespace(namespace)?)
+    }
+
+    /// Moves every node of this configuration under `namespace`, nesting
+    /// namespaces its nodes already have. Dependencies keep referring to the
+    /// same nodes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `namespace` is empty.
+    pub fn in_namespace(&self, namespace: &str) -> Result<DAGConfig, String> {
+        if namespace.is_empty() {
+            return Err("Namespace cannot be empty".to_string());
+        }
+
+        let mut moved = self.clone();
+        for node in &mut moved.nodes {
+            node.namespace = Some(match &node.namespace {
+                Some(inner) => format!("{namespace}{NAMESPACE_SEPARATOR}{inner}"),
+                None => namespace.to_string(),
+            });
+            // Relative dependencies now resolve within the namespace first,
+            // but those outside of any namespace must be moved explicitly
+            for dep in &mut node.depends_on {
+                let source = match dep {
+                    Dependency::Node(source) | Dependency::Port { from: source, .. } => source,
+                };
+                if let Some(absolute) = source.strip_prefix(NAMESPACE_SEPARATOR) {
+                    *source = format!("{NAMESPACE_SEPARATOR}{namespace}{NAMESPACE_SEPARATOR}{absolute}");
+                }
+            }
+        }
+        // Outputs are relative to the DAG, so they all move
+        for output in &mut moved.outputs {
+            let node = match output {
+                Output::Node(node) | Output::Renamed { node, .. } => node,
+            };
+            let relative = node.strip_prefix(NAMESPACE_SEPARATOR).unwrap_or(node);
+            *node = format!("{namespace}{NAMESPACE_SEPARATOR}{relative}");
+        }
+        Ok(moved)
+    }
+
     fn is_structural_change(base: &DAGConfig, other: &DAGConfig) -> bool {
-        let base_node_ids: HashSet<_> = base.nodes.iter().map(|n| &n.id).collect();
-        let other_node_ids: HashSet<_> = other.nodes.iter().map(|n| &n.id).collect();
+        let base_node_ids: HashSet<_> = base.nodes.iter().map(NodeConfig::qualified_id).collect();
+        let other_node_ids: HashSet<_> = other.nodes.iter().map(NodeConfig::qualified_id).collect();
 
         if !other_node_ids.is_subset(&base_node_ids) {
             return true;
         }
 
         for node in &other.nodes {
-            if let Some(base_node) = base.nodes.iter().find(|n| n.id == node.id) {
+            if let Some(base_node) = base
+                .nodes
+                .iter()
+                .find(|n| n.qualified_id() == node.qualified_id())
+            {
                 if base_node.depends_on != node.depends_on {
                     return true;
                 }
@@ -428,22 +882,36 @@ impl DAGConfig {
     }
 
     fn validate_graph_structure(&self) -> Result<(), String> {
+        let node_ids = DAGIR::qualified_node_ids(&self.nodes)?;
+        let resolved: Vec<(NodeID, Vec<NodeID>)> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let id = node.qualified_id();
+                let sources = node
+                    .depends_on
+                    .iter()
+                    .map(|dep| resolve_dependency(dep.source(), &id, &node_ids))
+                    .collect();
+                (id, sources)
+            })
+            .collect();
+
         let mut adj_list: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
-        for node in &self.nodes {
-            adj_list.entry(&node.id).or_default();
-            for dep in &node.depends_on {
-                adj_list.entry(dep).or_default().push(&node.id);
+        for (id, sources) in &resolved {
+            adj_list.entry(id).or_default();
+            for source in sources {
+                adj_list.entry(source).or_default().push(id);
             }
         }
 
         let mut visited = HashSet::new();
         let mut stack = HashSet::new();
 
-        for node in &self.nodes {
-            if Self::has_cycle(&adj_list, &node.id, &mut visited, &mut stack) {
+        for (id, _) in &resolved {
+            if Self::has_cycle(&adj_list, id, &mut visited, &mut stack) {
                 return Err(format!(
-                    "Cycle detected in merged DAG starting from node {}",
-                    node.id
+                    "Cycle detected in merged DAG starting from node {id}"
                 ));
             }
         }
@@ -524,6 +992,16 @@ impl DAGConfig {
 }
 
 impl NodeConfig {
+    /// The node's ID prefixed with its namespace, which is how other
+    /// namespaces and the DAG's results refer to it
+    #[must_use]
+    pub fn qualified_id(&self) -> NodeID {
+        match &self.namespace {
+            Some(namespace) => format!("{namespace}{NAMESPACE_SEPARATOR}{}", self.id),
+            None => self.id.clone(),
+        }
+    }
+
     /// Creates a new node configuration
     pub fn new(id: impl Into<String>, component_type: impl Into<String>) -> Self {
         NodeConfig {
@@ -533,6 +1011,13 @@ impl NodeConfig {
             namespace: None,
             inputs: None,
             depends_on: Vec::new(),
+            retry: None,
+            timeout_ms: None,
+            when: None,
+            join: None,
+            priority: None,
+            cacheable: None,
+            cache_ttl_ms: None,
         }
     }
 
@@ -559,8 +1044,52 @@ impl NodeConfig {
 
     /// Adds dependencies for this node
     #[must_use]
-    pub fn with_dependencies(mut self, deps: Vec<String>) -> Self {
-        self.depends_on = deps;
+    pub fn with_dependencies<D: Into<Dependency>>(mut self, deps: Vec<D>) -> Self {
+        self.depends_on = deps.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the node's timeout, overriding the DAG-wide one
+    #[must_use]
+    pub fn with_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.timeout_ms = Some(timeout_ms);
+        self
+    }
+
+    /// Sets the node's retry policy, overriding the DAG-wide one
+    #[must_use]
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Only executes the node when the jq predicate holds for its input
+    #[must_use]
+    pub fn with_when(mut self, predicate: impl Into<String>) -> Self {
+        self.when = Some(predicate.into());
+        self
+    }
+
+    /// Sets how the node treats dependencies that were bypassed
+    #[must_use]
+    pub fn with_join(mut self, join: JoinPolicy) -> Self {
+        self.join = Some(join);
+        self
+    }
+
+    /// Sets the node's priority for when concurrency is limited
+    #[must_use]
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Memoizes the node's output, reusing it for executions with the same
+    /// input for `ttl_ms` if given, or until evicted
+    #[must_use]
+    pub fn with_cache(mut self, ttl_ms: Option<u64>) -> Self {
+        self.cacheable = Some(true);
+        self.cache_ttl_ms = ttl_ms;
         self
     }
 }
@@ -588,7 +1117,15 @@ mod tests {
                 namespace: None,
                 inputs: None,
                 depends_on: vec![],
+                retry: None,
+                timeout_ms: None,
+                when: None,
+                join: None,
+                priority: None,
+                cacheable: None,
+                cache_ttl_ms: None,
             }],
+            outputs: vec![],
         };
 
         let override_config = DAGConfig {
@@ -607,7 +1144,15 @@ mod tests {
                 namespace: None,
                 inputs: None,
                 depends_on: vec![],
+                retry: None,
+                timeout_ms: None,
+                when: None,
+                join: None,
+                priority: None,
+                cacheable: None,
+                cache_ttl_ms: None,
             }],
+            outputs: vec![],
         };
 
         let merged = base.merge(&override_config).unwrap();
@@ -638,7 +1183,15 @@ mod tests {
                 namespace: None,
                 inputs: None,
                 depends_on: vec![],
+                retry: None,
+                timeout_ms: None,
+                when: None,
+                join: None,
+                priority: None,
+                cacheable: None,
+                cache_ttl_ms: None,
             }],
+            outputs: vec![],
         };
 
         let override_config = DAGConfig {
@@ -650,15 +1203,23 @@ mod tests {
                 config: json!({}),
                 namespace: None,
                 inputs: None,
-                depends_on: vec!["node1".to_string()],
+                depends_on: vec!["node1".into()],
+                retry: None,
+                timeout_ms: None,
+                when: None,
+                join: None,
+                priority: None,
+                cacheable: None,
+                cache_ttl_ms: None,
             }],
+            outputs: vec![],
         };
 
         let merged = base.merge(&override_config).unwrap();
         assert_eq!(merged.nodes.len(), 2);
 
         let node2 = merged.nodes.iter().find(|n| n.id == "node2").unwrap();
-        assert_eq!(node2.depends_on, vec!["node1"]);
+        assert_eq!(node2.depends_on, vec![Dependency::from("node1")]);
     }
 
     #[test]
@@ -673,7 +1234,15 @@ mod tests {
                 namespace: None,
                 inputs: None,
                 depends_on: vec![],
+                retry: None,
+                timeout_ms: None,
+                when: None,
+                join: None,
+                priority: None,
+                cacheable: None,
+                cache_ttl_ms: None,
             }],
+            outputs: vec![],
         };
 
         let override_config = DAGConfig {
@@ -686,7 +1255,15 @@ mod tests {
                 namespace: None,
                 inputs: None,
                 depends_on: vec![],
+                retry: None,
+                timeout_ms: None,
+                when: None,
+                join: None,
+                priority: None,
+                cacheable: None,
+                cache_ttl_ms: None,
             }],
+            outputs: vec![],
         };
 
         assert!(base.merge(&override_config).is_err());
@@ -704,7 +1281,14 @@ mod tests {
                     config: json!({}),
                     namespace: None,
                     inputs: None,
-                    depends_on: vec!["node2".to_string()],
+                    depends_on: vec!["node2".into()],
+                    retry: None,
+                    timeout_ms: None,
+                    when: None,
+                    join: None,
+                    priority: None,
+                    cacheable: None,
+                    cache_ttl_ms: None,
                 },
                 NodeConfig {
                     id: "node2".to_string(),
@@ -713,8 +1297,16 @@ mod tests {
                     namespace: None,
                     inputs: None,
                     depends_on: vec![],
+                    retry: None,
+                    timeout_ms: None,
+                    when: None,
+                    join: None,
+                    priority: None,
+                    cacheable: None,
+                    cache_ttl_ms: None,
                 },
             ],
+            outputs: vec![],
         };
 
         let override_config = DAGConfig {
@@ -726,8 +1318,16 @@ mod tests {
                 config: json!({}),
                 namespace: None,
                 inputs: None,
-                depends_on: vec!["node1".to_string()],
+                depends_on: vec!["node1".into()],
+                retry: None,
+                timeout_ms: None,
+                when: None,
+                join: None,
+                priority: None,
+                cacheable: None,
+                cache_ttl_ms: None,
             }],
+            outputs: vec![],
         };
 
         assert!(base.merge(&override_config).is_err());
@@ -748,7 +1348,15 @@ mod tests {
                 namespace: None,
                 inputs: None,
                 depends_on: vec![],
+                retry: None,
+                timeout_ms: None,
+                when: None,
+                join: None,
+                priority: None,
+                cacheable: None,
+                cache_ttl_ms: None,
             }],
+            outputs: vec![],
         };
 
         let config_change = DAGConfig {
@@ -763,7 +1371,15 @@ mod tests {
                 namespace: None,
                 inputs: None,
                 depends_on: vec![],
+                retry: None,
+                timeout_ms: None,
+                when: None,
+                join: None,
+                priority: None,
+                cacheable: None,
+                cache_ttl_ms: None,
             }],
+            outputs: vec![],
         };
 
         let merged = base.merge(&config_change).unwrap();
@@ -789,6 +1405,13 @@ mod tests {
                     namespace: None,
                     inputs: None,
                     depends_on: vec![],
+                    retry: None,
+                    timeout_ms: None,
+                    when: None,
+                    join: None,
+                    priority: None,
+                    cacheable: None,
+                    cache_ttl_ms: None,
                 },
                 NodeConfig {
                     id: "node2".to_string(),
@@ -796,9 +1419,17 @@ mod tests {
                     config: json!({}),
                     namespace: None,
                     inputs: None,
-                    depends_on: vec!["node1".to_string()],
+                    depends_on: vec!["node1".into()],
+                    retry: None,
+                    timeout_ms: None,
+                    when: None,
+                    join: None,
+                    priority: None,
+                    cacheable: None,
+                    cache_ttl_ms: None,
                 },
             ],
+            outputs: vec![],
         };
 
         let merged = merged.merge(&structural_change).unwrap();
@@ -812,4 +1443,23 @@ mod tests {
         assert_eq!(last_merge["previous_version"], "1.0.1");
         assert_eq!(last_merge["new_version"], "1.0.2");
     }
+
+    #[test]
+    fn test_parse_input_value() {
+        let parse = |value: Value| DAGIR::parse_input_value(&value);
+
+        assert_eq!(parse(json!(1)), Ok(Data::Integer(1)));
+        assert_eq!(parse(json!(5_000_000_000_i64)), Ok(Data::Int64(5_000_000_000)));
+        assert_eq!(parse(json!(1.5)), Ok(Data::Float(1.5)));
+        assert_eq!(parse(json!(true)), Ok(Data::Bool(true)));
+        assert_eq!(parse(json!(null)), Ok(Data::Null));
+        assert_eq!(
+            parse(json!([[1, "a"], [false]])),
+            Ok(Data::List(vec![
+                Data::List(vec![Data::Integer(1), Data::Text("a".to_string())]),
+                Data::List(vec![Data::Bool(false)]),
+            ]))
+        );
+        assert!(parse(json!(u64::MAX)).is_err());
+    }
 }
@@ -0,0 +1,159 @@
+use rusqlite::OptionalExtension;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, RwLock};
+
+use crate::cache::DAGResult;
+
+mod index;
+mod jsonl;
+
+pub use jsonl::{CompactionReport, JsonlHistory, RetentionPolicy, RotationPolicy};
+
+/// Where a `Cache` keeps the results of past requests, for replay by
+/// request ID. The `Cache` calls it from blocking threads, so stores may do
+/// blocking I/O.
+pub trait HistoryStore: Send + Sync {
+    /// Records the result of a request
+    ///
+    /// # Errors
+    ///
+    /// Returns a `HistoryError` if the result couldn't be written.
+    fn append(&self, result: &DAGResult) -> Result<(), HistoryError>;
+
+    /// The recorded result of the request, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns a `HistoryError` if the history couldn't be read.
+    fn get(&self, request_id: &str) -> Result<Option<DAGResult>, HistoryError>;
+}
+
+#[derive(Debug)]
+pub enum HistoryError {
+    Io(std::io::Error),
+    Serialization(serde_json::Error),
+    Database(rusqlite::Error),
+}
+
+impl std::fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoryError::Io(e) => write!(f, "History I/O error: {e}"),
+            HistoryError::Serialization(e) => write!(f, "History serialization error: {e}"),
+            HistoryError::Database(e) => write!(f, "History database error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {}
+
+impl From<std::io::Error> for HistoryError {
+    fn from(e: std::io::Error) -> Self {
+        HistoryError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for HistoryError {
+    fn from(e: serde_json::Error) -> Self {
+        HistoryError::Serialization(e)
+    }
+}
+
+impl From<rusqlite::Error> for HistoryError {
+    fn from(e: rusqlite::Error) -> Self {
+        HistoryError::Database(e)
+    }
+}
+
+/// A database on local disk (`SQLite`), indexed by request ID
+pub struct SqliteHistory {
+    connection: Mutex<rusqlite::Connection>,
+}
+
+impl SqliteHistory {
+    /// Opens the database at `path`, creating it if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns a `HistoryError` if the database can't be opened or set up.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, HistoryError> {
+        Self::setup(rusqlite::Connection::open(path)?)
+    }
+
+    /// A database that lives as long as the store, mostly for tests
+    ///
+    /// # Errors
+    ///
+    /// Returns a `HistoryError` if the database can't be set up.
+    pub fn open_in_memory() -> Result<Self, HistoryError> {
+        Self::setup(rusqlite::Connection::open_in_memory()?)
+    }
+
+    fn setup(connection: rusqlite::Connection) -> Result<Self, HistoryError> {
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS history (
+                request_id TEXT PRIMARY KEY,
+                timestamp TEXT NOT NULL,
+                result TEXT NOT NULL
+            )",
+            (),
+        )?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+impl HistoryStore for SqliteHistory {
+    fn append(&self, result: &DAGResult) -> Result<(), HistoryError> {
+        let json = serde_json::to_string(result)?;
+        self.connection.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO history (request_id, timestamp, result) VALUES (?1, ?2, ?3)",
+            (&result.request_id, result.timestamp.to_rfc3339(), json),
+        )?;
+        Ok(())
+    }
+
+    fn get(&self, request_id: &str) -> Result<Option<DAGResult>, HistoryError> {
+        let json: Option<String> = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT result FROM history WHERE request_id = ?1",
+                [request_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        json.map(|json| serde_json::from_str(&json).map_err(HistoryError::from))
+            .transpose()
+    }
+}
+
+/// Keeps the history in memory only, for as long as the store lives
+#[derive(Default)]
+pub struct MemoryHistory {
+    results: RwLock<HashMap<String, DAGResult>>,
+}
+
+impl MemoryHistory {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl HistoryStore for MemoryHistory {
+    fn append(&self, result: &DAGResult) -> Result<(), HistoryError> {
+        self.results
+            .write()
+            .unwrap()
+            .insert(result.request_id.clone(), result.clone());
+        Ok(())
+    }
+
+    fn get(&self, request_id: &str) -> Result<Option<DAGResult>, HistoryError> {
+        Ok(self.results.read().unwrap().get(request_id).cloned())
+    }
+}
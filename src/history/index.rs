@@ -0,0 +1,238 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use super::HistoryError;
+
+/// A segment of a JSONL history: the file being appended to if `None`, else
+/// a rotated segment by file name
+pub(super) type SegmentName = Option<Arc<str>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Position {
+    pub(super) segment: SegmentName,
+    pub(super) offset: u64,
+}
+
+/// A change to the index, as logged in its sidecar file
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub(super) enum IndexRecord {
+    /// The result of a request was written at `offset` of `segment`
+    Append {
+        request_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        segment: Option<String>,
+        offset: u64,
+    },
+    /// The file being appended to was rotated into `segment`
+    Rotate { segment: String },
+    /// Retention dropped `segment`
+    Drop { segment: String },
+}
+
+/// Where the latest result of each request is in a JSONL history. It's kept
+/// in memory, and every change to it is appended to a sidecar file next to
+/// the history, from which it's loaded back.
+pub(super) struct RequestIndex {
+    positions: HashMap<String, Position>,
+    /// The rotated segments whose results are indexed
+    segments: HashSet<Arc<str>>,
+    /// The sidecar, for appending; loading opens it
+    sidecar: Option<File>,
+    sidecar_path: PathBuf,
+}
+
+impl RequestIndex {
+    /// The index logged in `sidecar_path`, and whether the log was
+    /// readable to the end
+    pub(super) fn load(sidecar_path: PathBuf) -> Result<(Self, bool), HistoryError> {
+        let mut index = Self {
+            positions: HashMap::new(),
+            segments: HashSet::new(),
+            sidecar: None,
+            sidecar_path,
+        };
+        let file = match File::open(&index.sidecar_path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((index, false)),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut complete = true;
+        for line in BufReader::new(file).lines() {
+            // A torn last line, if appending it was interrupted
+            match serde_json::from_str(&line?) {
+                Ok(record) => index.apply(record),
+                Err(_) => complete = false,
+            }
+        }
+        Ok((index, complete))
+    }
+
+    pub(super) fn get(&self, request_id: &str) -> Option<&Position> {
+        self.positions.get(request_id)
+    }
+
+    pub(super) fn positions(&self) -> impl Iterator<Item = &Position> {
+        self.positions.values()
+    }
+
+    pub(super) fn indexes_segment(&self, segment: &str) -> bool {
+        self.segments.contains(segment)
+    }
+
+    pub(super) fn indexed_segments(&self) -> impl Iterator<Item = &Arc<str>> {
+        self.segments.iter()
+    }
+
+    /// Applies a change in memory only
+    pub(super) fn apply(&mut self, record: IndexRecord) {
+        match record {
+            IndexRecord::Append {
+                request_id,
+                segment,
+                offset,
+            } => {
+                let segment = segment.map(|segment| self.intern(&segment));
+                if let Some(segment) = &segment {
+                    self.segments.insert(Arc::clone(segment));
+                }
+                self.positions.insert(request_id, Position { segment, offset });
+            }
+            IndexRecord::Rotate { segment } => {
+                let segment = self.intern(&segment);
+                for position in self.positions.values_mut() {
+                    if position.segment.is_none() {
+                        position.segment = Some(Arc::clone(&segment));
+                    }
+                }
+                self.segments.insert(segment);
+            }
+            IndexRecord::Drop { segment } => {
+                self.positions
+                    .retain(|_, position| position.segment.as_deref() != Some(segment.as_str()));
+                self.segments.remove(segment.as_str());
+            }
+        }
+    }
+
+    /// Applies a change, then logs it to the sidecar
+    pub(super) fn record(&mut self, record: IndexRecord) -> Result<(), HistoryError> {
+        let line = format!("{}\n", serde_json::to_string(&record)?);
+        self.apply(record);
+        let sidecar = match self.sidecar.as_mut() {
+            Some(sidecar) => sidecar,
+            None => self.sidecar.insert(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.sidecar_path)?,
+            ),
+        };
+        sidecar.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// Indexes a result found in a segment the index missed, unless the
+    /// request has a result in a newer segment already. Returns whether the
+    /// index changed.
+    pub(super) fn insert_found(&mut self, request_id: &str, position: Position) -> bool {
+        let newer = self.positions.get(request_id).is_some_and(|indexed| {
+            indexed == &position || segment_rank(&indexed.segment) > segment_rank(&position.segment)
+        });
+        if newer {
+            return false;
+        }
+        if let Some(segment) = &position.segment {
+            self.segments.insert(Arc::clone(segment));
+        }
+        self.positions.insert(request_id.to_string(), position);
+        true
+    }
+
+    /// Forgets the request, whose result is nowhere to be found
+    pub(super) fn remove(&mut self, request_id: &str) {
+        self.positions.remove(request_id);
+    }
+
+    /// Forgets a segment without logging it, e.g. when it's gone from disk
+    pub(super) fn forget_segment(&mut self, segment: &str) {
+        self.apply(IndexRecord::Drop {
+            segment: segment.to_string(),
+        });
+    }
+
+    /// Marks a rotated segment as indexed, even if no result in it is the
+    /// latest of its request
+    pub(super) fn mark_indexed(&mut self, segment: &str) {
+        let segment = self.intern(segment);
+        self.segments.insert(segment);
+    }
+
+    /// Forgets everything, e.g. before indexing a compacted history
+    pub(super) fn clear(&mut self) {
+        self.positions.clear();
+        self.segments.clear();
+    }
+
+    /// Replaces the sidecar with one logging the index as it is, which also
+    /// drops the changes that no longer matter
+    pub(super) fn rewrite(&mut self) -> Result<(), HistoryError> {
+        let staging = with_suffix(&self.sidecar_path, "tmp");
+        let mut writer = BufWriter::new(File::create(&staging)?);
+        let mut segments: Vec<&Arc<str>> = self.segments.iter().collect();
+        segments.sort_by_key(|segment| segment_rank(&Some(Arc::clone(segment))));
+        for segment in segments {
+            let record = IndexRecord::Rotate {
+                segment: segment.to_string(),
+            };
+            writeln!(writer, "{}", serde_json::to_string(&record)?)?;
+        }
+        for (request_id, position) in &self.positions {
+            let record = IndexRecord::Append {
+                request_id: request_id.clone(),
+                segment: position.segment.as_deref().map(str::to_string),
+                offset: position.offset,
+            };
+            writeln!(writer, "{}", serde_json::to_string(&record)?)?;
+        }
+        writer.flush()?;
+        drop(writer);
+
+        self.sidecar = None;
+        fs::rename(&staging, &self.sidecar_path)?;
+        Ok(())
+    }
+
+    fn intern(&self, segment: &str) -> Arc<str> {
+        self.segments
+            .get(segment)
+            .cloned()
+            .unwrap_or_else(|| Arc::from(segment))
+    }
+}
+
+/// Orders segments from oldest to newest, by the time they were rotated at
+/// that suffixes their names
+pub(super) fn segment_rank(segment: &SegmentName) -> i64 {
+    segment.as_deref().map_or(i64::MAX, |segment| {
+        segment
+            .rsplit('.')
+            .next()
+            .and_then(|rotated_at| rotated_at.parse().ok())
+            .unwrap_or(i64::MIN)
+    })
+}
+
+/// `path` with `.suffix` appended to its file name
+pub(super) fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(suffix);
+    path.into()
+}
@@ -0,0 +1,582 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+use chrono::{DateTime, Utc};
+
+use super::index::{with_suffix, IndexRecord, Position, RequestIndex, SegmentName};
+use super::{HistoryError, HistoryStore};
+use crate::cache::DAGResult;
+
+/// When a `JsonlHistory` moves its file aside and starts a new one. Moved
+/// files become read-only segments, named after the file and suffixed with
+/// when they were rotated, in milliseconds (`history.jsonl.1718000000000`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RotationPolicy {
+    /// Rotate once the file is this large
+    pub max_segment_bytes: Option<u64>,
+    /// Rotate once the first result in the file is this old
+    pub max_segment_age_ms: Option<u64>,
+}
+
+/// Which results a `JsonlHistory` keeps. It's applied as segments rotate,
+/// dropping whole segments, so up to a segment more may be kept until the
+/// history is compacted.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RetentionPolicy {
+    /// Drop results older than this
+    pub max_age_ms: Option<u64>,
+    /// Keep at most this many of the latest results
+    pub max_entries: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactionReport {
+    /// How many files were rewritten, including the one being appended to
+    pub segments_compacted: usize,
+    pub entries_read: usize,
+    pub entries_kept: usize,
+}
+
+/// What's known of a history, shared by every `JsonlHistory` of its file in
+/// the process
+struct State {
+    /// Size of the file being appended to
+    active_bytes: u64,
+    /// When the first result in the file being appended to was recorded
+    active_started: Option<DateTime<Utc>>,
+    index: RequestIndex,
+}
+
+/// Loaded on first use
+type StateSlot = Mutex<Option<State>>;
+type SharedState = Arc<StateSlot>;
+
+/// The state of the history in `path`, shared with the other histories of
+/// that file in the process, so that a `Replay` finds what a `Cache` appends
+fn shared_state(path: &Path) -> SharedState {
+    static OPEN: OnceLock<Mutex<HashMap<PathBuf, Weak<StateSlot>>>> = OnceLock::new();
+
+    let key = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => {
+            let parent = if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            };
+            parent
+                .canonicalize()
+                .map_or_else(|_| path.to_path_buf(), |parent| parent.join(name))
+        }
+        _ => path.to_path_buf(),
+    };
+
+    let mut open = OPEN.get_or_init(Mutex::default).lock().unwrap();
+    if let Some(state) = open.get(&key).and_then(Weak::upgrade) {
+        return state;
+    }
+    open.retain(|_, state| state.strong_count() > 0);
+    let state = Arc::new(Mutex::new(None));
+    open.insert(key, Arc::downgrade(&state));
+    state
+}
+
+/// An append-only file with a JSON `DAGResult` per line, optionally rotated
+/// into segments.
+///
+/// Results are looked up through an index of request IDs, which every
+/// append updates and logs to a sidecar file (`history.jsonl.index`). It's
+/// loaded on first use, reconciled with the segments on disk, and shared
+/// by the histories of the same file in the process.
+pub struct JsonlHistory {
+    path: PathBuf,
+    rotation: RotationPolicy,
+    retention: RetentionPolicy,
+    state: SharedState,
+}
+
+impl JsonlHistory {
+    /// A history in `path` that is never rotated
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let state = shared_state(&path);
+        Self {
+            path,
+            rotation: RotationPolicy::default(),
+            retention: RetentionPolicy::default(),
+            state,
+        }
+    }
+
+    #[must_use]
+    pub fn with_rotation(mut self, rotation: RotationPolicy) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    #[must_use]
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Loads the index now rather than on first use, e.g. at startup
+    ///
+    /// # Errors
+    ///
+    /// Returns a `HistoryError` if the index or the segments it's
+    /// reconciled with can't be read.
+    pub fn load_index(&self) -> Result<(), HistoryError> {
+        self.with_state(|_| Ok(()))
+    }
+
+    /// The files holding results, newest first: the file being appended to,
+    /// then the rotated segments
+    ///
+    /// # Errors
+    ///
+    /// Returns a `HistoryError` if the directory of the history can't be read.
+    pub fn segments(&self) -> Result<Vec<PathBuf>, HistoryError> {
+        let mut segments: Vec<PathBuf> = self
+            .rotated_segments()?
+            .into_iter()
+            .rev()
+            .map(|(_, segment)| segment)
+            .collect();
+        if self.path.exists() {
+            segments.insert(0, self.path.clone());
+        }
+        Ok(segments)
+    }
+
+    fn dir(&self) -> &Path {
+        match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        }
+    }
+
+    fn segment_path(&self, segment: &SegmentName) -> PathBuf {
+        match segment {
+            Some(name) => self.dir().join(&**name),
+            None => self.path.clone(),
+        }
+    }
+
+    /// Rotated segments and when they were rotated, oldest first
+    fn rotated_segments(&self) -> Result<Vec<(i64, PathBuf)>, HistoryError> {
+        let Some(name) = self.path.file_name().and_then(|name| name.to_str()) else {
+            return Ok(Vec::new());
+        };
+        let prefix = format!("{name}.");
+        let entries = match fs::read_dir(self.dir()) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut segments = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let rotated_at = entry
+                .file_name()
+                .to_str()
+                .and_then(|file_name| file_name.strip_prefix(&prefix))
+                .and_then(|suffix| suffix.parse::<i64>().ok());
+            if let Some(rotated_at) = rotated_at {
+                segments.push((rotated_at, entry.path()));
+            }
+        }
+        segments.sort();
+        Ok(segments)
+    }
+
+    /// A path for a segment rotated at `now`, sorting after the existing ones
+    fn next_segment_path(&self, now: DateTime<Utc>) -> Result<PathBuf, HistoryError> {
+        let newest = self.rotated_segments()?.last().map(|(rotated_at, _)| *rotated_at);
+        let rotated_at = newest.map_or(now.timestamp_millis(), |newest| {
+            now.timestamp_millis().max(newest + 1)
+        });
+        Ok(with_suffix(&self.path, &rotated_at.to_string()))
+    }
+
+    fn with_state<T>(
+        &self,
+        f: impl FnOnce(&mut State) -> Result<T, HistoryError>,
+    ) -> Result<T, HistoryError> {
+        let mut guard = self.state.lock().unwrap();
+        let state = match guard.as_mut() {
+            Some(state) => state,
+            None => guard.insert(self.load()?),
+        };
+        f(state)
+    }
+
+    /// Loads the index from its sidecar, then indexes what it missed: the
+    /// segments it doesn't know of, if appending to it failed or it's new,
+    /// and the tail of the file being appended to
+    fn load(&self) -> Result<State, HistoryError> {
+        let (mut index, complete) = RequestIndex::load(with_suffix(&self.path, "index"))?;
+        let mut stale = !complete;
+
+        let rotated = self.rotated_segments()?;
+        let on_disk: HashSet<String> = rotated
+            .iter()
+            .filter_map(|(_, segment)| file_name(segment))
+            .collect();
+        let gone: Vec<String> = index
+            .indexed_segments()
+            .filter(|segment| !on_disk.contains(&***segment))
+            .map(ToString::to_string)
+            .collect();
+        for segment in gone {
+            index.forget_segment(&segment);
+            stale = true;
+        }
+
+        for (_, segment) in &rotated {
+            let Some(name) = file_name(segment) else {
+                continue;
+            };
+            if index.indexes_segment(&name) {
+                continue;
+            }
+            let name: Arc<str> = name.into();
+            for_each_result(segment, 0, |offset, result| {
+                let position = Position {
+                    segment: Some(Arc::clone(&name)),
+                    offset,
+                };
+                index.insert_found(&result.request_id, position);
+            })?;
+            index.mark_indexed(&name);
+            stale = true;
+        }
+
+        let (active_bytes, active_started) = match File::open(&self.path) {
+            Ok(file) => {
+                let bytes = file.metadata()?.len();
+                let mut first_line = String::new();
+                BufReader::new(file).read_line(&mut first_line)?;
+                let started = serde_json::from_str::<DAGResult>(&first_line)
+                    .ok()
+                    .map(|result| result.timestamp);
+                (bytes, started)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (0, None),
+            Err(e) => return Err(e.into()),
+        };
+        let indexed_until = index
+            .positions()
+            .filter(|position| position.segment.is_none())
+            .map(|position| position.offset)
+            .max()
+            .unwrap_or(0);
+        let tail_from = if indexed_until < active_bytes {
+            indexed_until
+        } else {
+            0
+        };
+        for_each_result(&self.path, tail_from, |offset, result| {
+            let position = Position {
+                segment: None,
+                offset,
+            };
+            stale |= index.insert_found(&result.request_id, position);
+        })?;
+
+        if stale {
+            index.rewrite()?;
+        }
+        Ok(State {
+            active_bytes,
+            active_started,
+            index,
+        })
+    }
+
+    fn should_rotate(&self, state: &State, now: DateTime<Utc>) -> bool {
+        let too_large = self
+            .rotation
+            .max_segment_bytes
+            .is_some_and(|max| state.active_bytes > 0 && state.active_bytes >= max);
+        let too_old = self
+            .rotation
+            .max_segment_age_ms
+            .zip(state.active_started)
+            .is_some_and(|(max, started)| older_than(started, now, max));
+        too_large || too_old
+    }
+
+    /// Moves the file being appended to aside, then drops the segments
+    /// retention no longer keeps
+    fn rotate(&self, state: &mut State, now: DateTime<Utc>) -> Result<(), HistoryError> {
+        let rotated = self.next_segment_path(now)?;
+        fs::rename(&self.path, &rotated)?;
+        state.active_bytes = 0;
+        state.active_started = None;
+        if let Some(segment) = file_name(&rotated) {
+            state.index.record(IndexRecord::Rotate { segment })?;
+        }
+
+        let segments = self.rotated_segments()?;
+        // A segment holds nothing newer than when it was rotated
+        let mut dropped = self.retention.max_age_ms.map_or(0, |max| {
+            segments
+                .iter()
+                .take_while(|(rotated_at, _)| {
+                    DateTime::from_timestamp_millis(*rotated_at)
+                        .is_some_and(|rotated_at| older_than(rotated_at, now, max))
+                })
+                .count()
+        });
+        if let Some(max_entries) = self.retention.max_entries {
+            // Results superseded by later ones of their request don't count
+            let mut latest_per_segment: HashMap<&str, usize> = HashMap::new();
+            for position in state.index.positions() {
+                if let Some(segment) = &position.segment {
+                    *latest_per_segment.entry(segment).or_default() += 1;
+                }
+            }
+            let mut kept = 0;
+            for (i, (_, segment)) in segments.iter().enumerate().skip(dropped).rev() {
+                if kept >= max_entries {
+                    dropped = i + 1;
+                    break;
+                }
+                kept += file_name(segment)
+                    .and_then(|name| latest_per_segment.get(name.as_str()).copied())
+                    .unwrap_or(0);
+            }
+        }
+
+        for (_, segment) in &segments[..dropped] {
+            remove_if_exists(segment)?;
+            if let Some(segment) = file_name(segment) {
+                state.index.record(IndexRecord::Drop { segment })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Looks the request up without the index, which lost track of it, and
+    /// fixes the index
+    fn repair(&self, state: &mut State, request_id: &str) -> Result<Option<DAGResult>, HistoryError> {
+        for segment in self.segments()? {
+            let mut found = None;
+            for_each_result(&segment, 0, |offset, result| {
+                if result.request_id == request_id {
+                    found = Some((offset, result));
+                }
+            })?;
+            if let Some((offset, result)) = found {
+                let segment = if segment == self.path {
+                    None
+                } else {
+                    file_name(&segment)
+                };
+                state.index.record(IndexRecord::Append {
+                    request_id: request_id.to_string(),
+                    segment,
+                    offset,
+                })?;
+                return Ok(Some(result));
+            }
+        }
+        state.index.remove(request_id);
+        Ok(None)
+    }
+
+    /// Rewrites every segment, including the file being appended to, into a
+    /// single segment holding the latest result of each request that the
+    /// retention policy keeps, and reindexes it. Run it while nothing else
+    /// appends to the history.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `HistoryError` if a segment can't be read, or the compacted
+    /// segment or its index can't be written.
+    pub fn compact(&self) -> Result<CompactionReport, HistoryError> {
+        self.with_state(|state| {
+            let segments = self.segments()?;
+
+            let mut entries_read = 0;
+            let mut latest = HashMap::new();
+            for segment in segments.iter().rev() {
+                for_each_result(segment, 0, |_, result| {
+                    entries_read += 1;
+                    latest.insert(result.request_id.clone(), result);
+                })?;
+            }
+
+            let now = Utc::now();
+            let mut kept: Vec<DAGResult> = latest
+                .into_values()
+                .filter(|result| {
+                    !self
+                        .retention
+                        .max_age_ms
+                        .is_some_and(|max| older_than(result.timestamp, now, max))
+                })
+                .collect();
+            kept.sort_by_key(|result| result.timestamp);
+            if let Some(max_entries) = self.retention.max_entries {
+                kept.drain(..kept.len().saturating_sub(max_entries));
+            }
+
+            let staging = with_suffix(&self.path, "compacting");
+            let mut positions = Vec::with_capacity(kept.len());
+            let mut writer = BufWriter::new(File::create(&staging)?);
+            let mut offset = 0;
+            for result in &kept {
+                let line = format!("{}\n", serde_json::to_string(result)?);
+                writer.write_all(line.as_bytes())?;
+                positions.push((result.request_id.clone(), offset));
+                offset += line.len() as u64;
+            }
+            writer.flush()?;
+            drop(writer);
+
+            let compacted = self.next_segment_path(now)?;
+            fs::rename(&staging, &compacted)?;
+            for segment in &segments {
+                remove_if_exists(segment)?;
+            }
+
+            state.active_bytes = 0;
+            state.active_started = None;
+            state.index.clear();
+            if let Some(segment) = file_name(&compacted) {
+                state.index.mark_indexed(&segment);
+                for (request_id, offset) in positions {
+                    state.index.apply(IndexRecord::Append {
+                        request_id,
+                        segment: Some(segment.clone()),
+                        offset,
+                    });
+                }
+            }
+            state.index.rewrite()?;
+
+            Ok(CompactionReport {
+                segments_compacted: segments.len(),
+                entries_read,
+                entries_kept: kept.len(),
+            })
+        })
+    }
+}
+
+impl HistoryStore for JsonlHistory {
+    fn append(&self, result: &DAGResult) -> Result<(), HistoryError> {
+        let line = format!("{}\n", serde_json::to_string(result)?);
+        let now = Utc::now();
+
+        self.with_state(|state| {
+            if self.should_rotate(state, now) {
+                self.rotate(state, now)?;
+            }
+
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            file.write_all(line.as_bytes())?;
+            let offset = state.active_bytes;
+            state.active_bytes += line.len() as u64;
+            state.active_started.get_or_insert(result.timestamp);
+            state.index.record(IndexRecord::Append {
+                request_id: result.request_id.clone(),
+                segment: None,
+                offset,
+            })
+        })
+    }
+
+    fn get(&self, request_id: &str) -> Result<Option<DAGResult>, HistoryError> {
+        self.with_state(|state| {
+            let Some(position) = state.index.get(request_id) else {
+                return Ok(None);
+            };
+            let segment = self.segment_path(&position.segment);
+            match read_at(&segment, position.offset, request_id)? {
+                Some(result) => Ok(Some(result)),
+                // The history changed behind the index's back
+                None => self.repair(state, request_id),
+            }
+        })
+    }
+}
+
+fn file_name(path: &Path) -> Option<String> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(str::to_string)
+}
+
+fn older_than(timestamp: DateTime<Utc>, now: DateTime<Utc>, max_age_ms: u64) -> bool {
+    u64::try_from((now - timestamp).num_milliseconds()).is_ok_and(|age| age >= max_age_ms)
+}
+
+fn remove_if_exists(path: &Path) -> Result<(), HistoryError> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Opens a segment, which may have been dropped or compacted away since it
+/// was listed
+fn open_segment(segment: &Path) -> Result<Option<BufReader<File>>, HistoryError> {
+    match File::open(segment) {
+        Ok(file) => Ok(Some(BufReader::new(file))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Calls `f` with every result in the segment from `from` on and its
+/// offset, skipping lines that aren't one
+fn for_each_result(
+    segment: &Path,
+    from: u64,
+    mut f: impl FnMut(u64, DAGResult),
+) -> Result<(), HistoryError> {
+    let Some(mut reader) = open_segment(segment)? else {
+        return Ok(());
+    };
+    reader.seek(SeekFrom::Start(from))?;
+    let mut offset = from;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        if read == 0 {
+            return Ok(());
+        }
+        if let Ok(result) = serde_json::from_str::<DAGResult>(&line) {
+            f(offset, result);
+        }
+        offset += read as u64;
+    }
+}
+
+/// The result at `offset` of the segment, if it's the request's
+fn read_at(segment: &Path, offset: u64, request_id: &str) -> Result<Option<DAGResult>, HistoryError> {
+    let Some(mut reader) = open_segment(segment)? else {
+        return Ok(None);
+    };
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    Ok(serde_json::from_str::<DAGResult>(&line)
+        .ok()
+        .filter(|result| result.request_id == request_id))
+}
@@ -1,8 +1,11 @@
 pub mod cache;
+pub mod coercion;
 pub mod component;
+pub mod condition;
 pub mod dag;
 pub mod dag_visualizer;
 pub mod dagir;
+pub mod history;
 
 pub mod components {
     pub mod adder;
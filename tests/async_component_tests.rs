@@ -0,0 +1,250 @@
+use axum::{routing::post, Json, Router};
+use baselard::component::{AsyncComponent, Data, DataType, Error, Registry};
+use baselard::components::adder::Adder;
+use baselard::components::crash_test_dummy::CrashTestDummy;
+use baselard::components::ml_model::MLModel;
+use baselard::dag::{DAGError, DAGSettings, NodeExecutionContext, DAG};
+use baselard::dagir::DAGIR;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Waits without holding a thread, then doubles its input
+struct SlowDoubler {
+    delay_ms: u64,
+}
+
+impl AsyncComponent for SlowDoubler {
+    fn configure(config: Value) -> Result<Self, Error> {
+        Ok(Self {
+            delay_ms: config["delay_ms"].as_u64().unwrap_or(0),
+        })
+    }
+
+    async fn execute(&self, _context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
+        tokio::time::sleep(Duration::from_millis(self.delay_ms)).await;
+        Ok(Data::Integer(input.as_integer().unwrap_or_default() * 2))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Integer
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Integer
+    }
+}
+
+/// How many `SlowCounter` executions ran to the end
+static FINISHED_COUNTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Waits 300ms without observing cancellation, like an in-flight request,
+/// then counts itself as finished
+struct SlowCounter;
+
+impl AsyncComponent for SlowCounter {
+    fn configure(_: Value) -> Result<Self, Error> {
+        Ok(Self)
+    }
+
+    async fn execute(&self, _context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        FINISHED_COUNTS.fetch_add(1, Ordering::SeqCst);
+        Ok(input)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Integer
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Integer
+    }
+}
+
+fn setup_test_registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<Adder>("Adder");
+    registry.register::<CrashTestDummy>("CrashTestDummy");
+    registry.register_async::<SlowDoubler>("SlowDoubler");
+    registry.register_async::<SlowCounter>("SlowCounter");
+    registry.register_async::<MLModel>("MLModel");
+    registry
+}
+
+fn build_dag(json_config: &Value, settings: DAGSettings) -> DAG {
+    let registry = setup_test_registry();
+    let ir = DAGIR::from_json(json_config).expect("Valid IR");
+    DAG::from_ir(&ir, &registry, settings, None).expect("Valid DAG")
+}
+
+fn fan_out_config(node_type: &str, config: &Value, width: usize) -> Value {
+    let mut nodes = vec![json!({ "id": "source", "component_type": "Adder", "config": { "value": 0 }, "inputs": 1 })];
+    nodes.extend((0..width).map(|i| {
+        json!({
+            "id": format!("fan_{i}"),
+            "component_type": node_type,
+            "config": config,
+            "depends_on": ["source"]
+        })
+    }));
+    json!({ "alias": "fan_out", "nodes": nodes })
+}
+
+#[test]
+fn test_async_components_do_not_hold_blocking_threads() {
+    // With a single blocking thread, 32 blocking 50ms nodes would take 1.6s
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .max_blocking_threads(1)
+        .enable_all()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        let dag = build_dag(
+            &fan_out_config("SlowDoubler", &json!({ "delay_ms": 50 }), 32),
+            DAGSettings::cache_off(),
+        );
+
+        let start = Instant::now();
+        let results = dag.execute(None).await.expect("Execution success");
+        assert!(
+            start.elapsed() < Duration::from_millis(800),
+            "Async nodes should wait concurrently, took {:?}",
+            start.elapsed()
+        );
+        assert_eq!(results.len(), 33);
+        assert!((0..32).all(|i| results.get(&format!("fan_{i}")) == Some(&Data::Integer(2))));
+    });
+}
+
+#[tokio::test]
+async fn test_async_and_blocking_components_chain() {
+    let dag = build_dag(
+        &json!({
+            "alias": "mixed",
+            "nodes": [
+                { "id": "double", "component_type": "SlowDoubler", "config": {}, "inputs": 5 },
+                { "id": "add", "component_type": "Adder", "config": { "value": 1 }, "depends_on": ["double"] },
+                { "id": "double_again", "component_type": "SlowDoubler", "config": {}, "depends_on": ["add"] }
+            ]
+        }),
+        DAGSettings::cache_off(),
+    );
+
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.get("double"), Some(&Data::Integer(10)));
+    assert_eq!(results.get("add"), Some(&Data::Integer(11)));
+    assert_eq!(results.get("double_again"), Some(&Data::Integer(22)));
+}
+
+#[tokio::test]
+async fn test_async_component_times_out() {
+    let dag = build_dag(
+        &json!({
+            "alias": "async_timeout",
+            "nodes": [
+                { "id": "slow", "component_type": "SlowDoubler", "config": { "delay_ms": 500 }, "inputs": 1, "timeout_ms": 50 }
+            ]
+        }),
+        DAGSettings::cache_off(),
+    );
+
+    let result = dag.execute(None).await;
+    assert!(
+        matches!(&result, Err(DAGError::NodeTimeout { node_id, timeout_ms: 50 }) if node_id == "slow"),
+        "Unexpected result: {result:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_async_components_are_aborted_with_their_node() {
+    let timed_out = build_dag(
+        &json!({
+            "alias": "async_abort_timeout",
+            "nodes": [
+                { "id": "slow", "component_type": "SlowCounter", "config": {}, "inputs": 1, "timeout_ms": 50 }
+            ]
+        }),
+        DAGSettings::cache_off(),
+    );
+    let past_deadline = build_dag(
+        &json!({
+            "alias": "async_abort_deadline",
+            "nodes": [
+                { "id": "slow", "component_type": "SlowCounter", "config": {}, "inputs": 1, "timeout_ms": 1000 }
+            ]
+        }),
+        DAGSettings {
+            dag_deadline_ms: Some(50),
+            ..DAGSettings::cache_off()
+        },
+    );
+    let aborted = build_dag(
+        &json!({
+            "alias": "async_abort_failure",
+            "nodes": [
+                { "id": "slow", "component_type": "SlowCounter", "config": {}, "inputs": 1, "timeout_ms": 1000 },
+                { "id": "crash", "component_type": "CrashTestDummy", "config": { "fail": true, "sleep_duration_ms": 50 } }
+            ]
+        }),
+        DAGSettings::cache_off(),
+    );
+
+    let result = timed_out.execute(None).await;
+    assert!(matches!(&result, Err(DAGError::NodeTimeout { .. })), "Unexpected result: {result:?}");
+    let result = past_deadline.execute(None).await;
+    assert!(
+        matches!(&result, Err(DAGError::DeadlineExceeded { .. })),
+        "Unexpected result: {result:?}"
+    );
+    let result = aborted.execute(None).await;
+    assert!(
+        matches!(&result, Err(DAGError::ExecutionError { node_id, .. }) if node_id == "crash"),
+        "Unexpected result: {result:?}"
+    );
+
+    // Long enough for every execution to have finished, had it kept going
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert_eq!(FINISHED_COUNTS.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ml_model_remote_predictions_are_async() {
+    async fn predict(Json(body): Json<Value>) -> Json<Value> {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let features: Vec<f64> = body["features"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(Value::as_f64)
+            .map(|feature| feature * 10.0)
+            .collect();
+        Json(json!({ "processed_features": features }))
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}/predict", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, Router::new().route("/predict", post(predict)))
+            .await
+            .unwrap();
+    });
+
+    let dag = build_dag(
+        &json!({
+            "alias": "remote_model",
+            "nodes": [
+                { "id": "model", "component_type": "MLModel", "config": { "remote_endpoint": endpoint }, "inputs": [1, 2] }
+            ]
+        }),
+        DAGSettings::cache_off(),
+    );
+
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(
+        results.get("model"),
+        Some(&Data::List(vec![Data::Float(10.0), Data::Float(20.0)]))
+    );
+}
@@ -0,0 +1,153 @@
+use baselard::cache::{Cache, CacheSettings};
+use baselard::component::{Data, Registry};
+use baselard::components::adder::Adder;
+use baselard::dag::{DAGSettings, DAG};
+use baselard::dagir::{DAGConfig, NodeConfig, DAGIR};
+use baselard::history::MemoryHistory;
+use indexmap::IndexMap;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn store(cache: &Cache, ir_hash: u64, output: Data) {
+    let results = IndexMap::from([("node".to_string(), output)]);
+    cache.store_result(ir_hash, &HashMap::new(), &results, &format!("request_{ir_hash}"));
+}
+
+fn is_cached(cache: &Cache, ir_hash: u64) -> bool {
+    cache.get_cached_result(ir_hash, &HashMap::new()).is_some()
+}
+
+#[tokio::test]
+async fn test_results_expire_after_time_to_live() {
+    let cache = Cache::new(None::<String>, 100).with_settings(CacheSettings {
+        time_to_live_ms: Some(50),
+        ..CacheSettings::with_capacity(100)
+    });
+
+    store(&cache, 1, Data::Integer(1));
+    assert!(is_cached(&cache, 1));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!is_cached(&cache, 1));
+}
+
+#[tokio::test]
+async fn test_results_expire_after_time_to_idle() {
+    let cache = Cache::new(None::<String>, 100).with_settings(CacheSettings {
+        time_to_idle_ms: Some(100),
+        ..CacheSettings::with_capacity(100)
+    });
+
+    store(&cache, 1, Data::Integer(1));
+    for _ in 0..3 {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(is_cached(&cache, 1), "Reads should keep the result");
+    }
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(!is_cached(&cache, 1));
+}
+
+#[test]
+fn test_results_are_weighed_by_size() {
+    let large = || Data::Text("x".repeat(800));
+
+    let by_count = Cache::new(None::<String>, 1000);
+    let by_size = Cache::new(None::<String>, 1000).with_settings(CacheSettings {
+        weigh_by_size: true,
+        ..CacheSettings::with_capacity(1000)
+    });
+    for cache in [&by_count, &by_size] {
+        for ir_hash in 0..3 {
+            store(cache, ir_hash, large());
+        }
+        cache.run_pending_tasks();
+    }
+
+    assert_eq!((0..3).filter(|ir_hash| is_cached(&by_count, *ir_hash)).count(), 3);
+    assert!((0..3).filter(|ir_hash| is_cached(&by_size, *ir_hash)).count() <= 1);
+}
+
+fn build_dag(alias: &str, value: i32, cache: &Arc<Cache>) -> DAG {
+    let mut registry = Registry::new();
+    registry.register::<Adder>("Adder");
+    let config = DAGConfig::new(alias).with_nodes(vec![
+        NodeConfig::new("first", "Adder")
+            .with_config(json!({ "value": value }))
+            .with_inputs(json!(1)),
+        NodeConfig::new("second", "Adder")
+            .with_config(json!({ "value": 1 }))
+            .with_dependencies(vec!["first"]),
+    ]);
+    let settings = DAGSettings {
+        enable_memory_cache: true,
+        enable_history: true,
+        ..DAGSettings::cache_off()
+    };
+    let ir = DAGIR::from_config(config).expect("Valid IR");
+    DAG::from_ir(&ir, &registry, settings, Some(Arc::clone(cache))).expect("Valid DAG")
+}
+
+/// Executes the DAG, and its `first` node alone, for the cache to hold both
+async fn execute_and_cache(dag: &DAG) {
+    dag.execute(None).await.expect("Execution success");
+    dag.execute_targets(None, &["first".to_string()])
+        .await
+        .expect("Execution success");
+    tokio::time::sleep(Duration::from_millis(50)).await;
+}
+
+async fn hits_cache(dag: &DAG) -> bool {
+    let report = dag.execute_with_report(None).await.expect("Execution success");
+    report.attempts.is_empty()
+}
+
+#[tokio::test]
+async fn test_dag_invalidates_its_results() {
+    let cache = Arc::new(Cache::new(None::<String>, 100));
+    let dag = build_dag("invalidated", 1, &cache);
+    let other = build_dag("other", 3, &cache);
+    execute_and_cache(&dag).await;
+    execute_and_cache(&other).await;
+    assert!(dag.get_cached_result().is_some());
+
+    dag.invalidate_cache();
+    assert!(dag.get_cached_result().is_none());
+    assert!(other.get_cached_result().is_some(), "Other DAGs keep their results");
+    assert!(!hits_cache(&dag).await);
+    let targeted = dag.execute_targets(None, &["first".to_string()]).await.unwrap();
+    assert_eq!(targeted.get("first"), Some(&Data::Integer(2)));
+}
+
+#[tokio::test]
+async fn test_invalidate_alias_covers_every_configuration() {
+    let cache = Arc::new(Cache::new(None::<String>, 100));
+    let old = build_dag("versioned", 1, &cache);
+    let new = build_dag("versioned", 2, &cache);
+    // The IR hash leaves the alias out, so DAGs sharing an IR share results
+    let other = build_dag("other", 3, &cache);
+    for dag in [&old, &new, &other] {
+        execute_and_cache(dag).await;
+    }
+
+    new.invalidate_alias();
+    assert!(old.get_cached_result().is_none());
+    assert!(new.get_cached_result().is_none());
+    assert!(other.get_cached_result().is_some());
+}
+
+#[tokio::test]
+async fn test_invalidate_request_id_keeps_history() {
+    let cache = Arc::new(Cache::with_history_store(MemoryHistory::new(), 100));
+    let dag = build_dag("requests", 1, &cache);
+    let request_id = "request".to_string();
+    dag.execute(Some(request_id.clone())).await.expect("Execution success");
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(dag.get_result_by_request_id(&request_id).is_some());
+
+    dag.invalidate_request_id(&request_id);
+    assert!(dag.get_cached_result().is_none());
+    assert!(dag.get_result_by_request_id(&request_id).is_none());
+    let replayed = dag.replay(&request_id).await.expect("Replay success");
+    assert_eq!(replayed.get("second"), Some(&Data::Integer(3)));
+}
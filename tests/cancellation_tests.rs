@@ -0,0 +1,163 @@
+use baselard::component::Registry;
+use baselard::component::{Component, Data, DataType, Error};
+use baselard::components::crash_test_dummy::CrashTestDummy;
+use baselard::dag::{DAGError, DAGSettings, NodeExecutionContext, DAG};
+use baselard::dagir::DAGIR;
+use serde_json::json;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Node IDs of every `StartRecorder` that began executing
+static STARTED_NODES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+/// Node IDs of every `Waiter` whose `on_abort` hook ran
+static ABORTED_NODES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Records that it started and passes its input through
+struct StartRecorder;
+
+impl Component for StartRecorder {
+    fn configure(_: serde_json::Value) -> Result<Self, Error> {
+        Ok(Self)
+    }
+
+    fn execute(&self, context: NodeExecutionContext, _input: Data) -> Result<Data, DAGError> {
+        STARTED_NODES.lock().unwrap().push(context.node_id);
+        Ok(Data::Null)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Union(vec![DataType::Null, DataType::Text])
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Null
+    }
+}
+
+/// Waits until the DAG is cancelled (or gives up after a second)
+struct Waiter;
+
+impl Component for Waiter {
+    fn configure(_: serde_json::Value) -> Result<Self, Error> {
+        Ok(Self)
+    }
+
+    fn execute(&self, context: NodeExecutionContext, _input: Data) -> Result<Data, DAGError> {
+        let start = Instant::now();
+        while !context.is_cancelled() && start.elapsed() < Duration::from_secs(1) {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        Ok(Data::Null)
+    }
+
+    fn on_abort(&self, context: NodeExecutionContext) {
+        assert!(context.is_cancelled(), "on_abort should see a cancelled token");
+        ABORTED_NODES.lock().unwrap().push(context.node_id);
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Null
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Null
+    }
+}
+
+fn setup_test_registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<CrashTestDummy>("CrashTestDummy");
+    registry.register::<StartRecorder>("StartRecorder");
+    registry.register::<Waiter>("Waiter");
+    registry
+}
+
+#[tokio::test]
+async fn test_downstream_nodes_never_start_after_failure() {
+    let registry = setup_test_registry();
+    let json_config = json!({
+        "alias": "downstream_never_starts_test",
+        "nodes": [
+            {
+                "id": "failing",
+                "component_type": "CrashTestDummy",
+                "config": { "fail": true, "sleep_duration_ms": 10 }
+            },
+            {
+                "id": "after_failing",
+                "component_type": "StartRecorder",
+                "config": {},
+                "depends_on": ["failing"]
+            },
+            {
+                "id": "slow_sibling",
+                "component_type": "CrashTestDummy",
+                "config": {
+                    "sleep_duration_ms": 150,
+                    "spin_threshold_us": 1000
+                }
+            },
+            {
+                "id": "after_slow_sibling",
+                "component_type": "StartRecorder",
+                "config": {},
+                "depends_on": ["slow_sibling"]
+            }
+        ]
+    });
+
+    let dag = DAGIR::from_json(&json_config)
+        .and_then(|ir| DAG::from_ir(&ir, &registry, DAGSettings::cache_off(), None))
+        .expect("Valid DAG");
+
+    let start = Instant::now();
+    let result = dag.execute(None).await;
+    assert!(
+        matches!(&result, Err(DAGError::ExecutionError { node_id, .. }) if node_id == "failing"),
+        "Execution should fail on the failing node, got {result:?}"
+    );
+    assert!(
+        start.elapsed() < Duration::from_millis(100),
+        "Execution should abort without waiting for the slow sibling, took {:?}",
+        start.elapsed()
+    );
+
+    // Give any leaked task the time it would need to start
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let started = STARTED_NODES.lock().unwrap();
+    assert!(
+        !started.iter().any(|id| id == "after_failing" || id == "after_slow_sibling"),
+        "No downstream node should start after a failure, got {started:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_on_abort_called_for_in_flight_nodes() {
+    let registry = setup_test_registry();
+    let json_config = json!({
+        "alias": "on_abort_test",
+        "nodes": [
+            {
+                "id": "waiting_node",
+                "component_type": "Waiter",
+                "config": {}
+            },
+            {
+                "id": "failing",
+                "component_type": "CrashTestDummy",
+                "config": { "fail": true, "sleep_duration_ms": 20 }
+            }
+        ]
+    });
+
+    let dag = DAGIR::from_json(&json_config)
+        .and_then(|ir| DAG::from_ir(&ir, &registry, DAGSettings::cache_off(), None))
+        .expect("Valid DAG");
+
+    let result = dag.execute(None).await;
+    assert!(result.is_err(), "Execution should fail");
+
+    let aborted = ABORTED_NODES.lock().unwrap();
+    assert_eq!(*aborted, vec!["waiting_node".to_string()]);
+}
@@ -0,0 +1,236 @@
+use baselard::coercion::CoercionRegistry;
+use baselard::component::{Component, Data, DataType, Error, Registry};
+use baselard::components::adder::Adder;
+use baselard::components::payload_transformer::PayloadTransformer;
+use baselard::dag::{DAGError, DAGSettings, ImplicitConversion, NodeExecutionContext, DAG};
+use baselard::dagir::DAGIR;
+use serde_json::{json, Value};
+
+/// Sums a list of floats
+struct FloatSum;
+
+impl Component for FloatSum {
+    fn configure(_: Value) -> Result<Self, Error> {
+        Ok(FloatSum)
+    }
+
+    fn execute(&self, _: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
+        let Data::List(items) = input else {
+            return Ok(Data::Float(0.0));
+        };
+        Ok(Data::Float(
+            items
+                .iter()
+                .filter_map(|item| match item {
+                    Data::Float(f) => Some(*f),
+                    _ => None,
+                })
+                .sum(),
+        ))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::List(Box::new(DataType::Float))
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Float
+    }
+}
+
+/// Halves a float
+struct Halver;
+
+impl Component for Halver {
+    fn configure(_: Value) -> Result<Self, Error> {
+        Ok(Halver)
+    }
+
+    fn execute(&self, _: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
+        let Data::Float(f) = input else {
+            return Ok(Data::Null);
+        };
+        Ok(Data::Float(f / 2.0))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Float
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Float
+    }
+}
+
+fn setup_test_registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<Adder>("Adder");
+    registry.register::<PayloadTransformer>("PayloadTransformer");
+    registry.register::<FloatSum>("FloatSum");
+    registry.register::<Halver>("Halver");
+    registry
+}
+
+fn coercing() -> DAGSettings {
+    DAGSettings {
+        implicit_coercions: true,
+        ..DAGSettings::cache_off()
+    }
+}
+
+fn build_dag(registry: &Registry, settings: DAGSettings, config: &Value) -> Result<DAG, String> {
+    DAGIR::from_json(config).and_then(|ir| DAG::from_ir(&ir, registry, settings, None))
+}
+
+fn adder_into_halver() -> Value {
+    json!({
+        "alias": "adder_into_halver",
+        "nodes": [
+            { "id": "number", "component_type": "Adder", "config": { "value": 2 }, "inputs": 1 },
+            { "id": "half", "component_type": "Halver", "config": {}, "depends_on": ["number"] }
+        ]
+    })
+}
+
+fn features_into_sum(expression: &str, expected_output: &Value) -> Value {
+    json!({
+        "alias": "features_into_sum",
+        "nodes": [
+            {
+                "id": "features",
+                "component_type": "PayloadTransformer",
+                "config": {
+                    "transformation_expression": expression,
+                    "validation_data": {
+                        "input": { "features": [1, 2.5] },
+                        "expected_output": expected_output
+                    }
+                },
+                "inputs": { "features": [1, 2.5] }
+            },
+            { "id": "sum", "component_type": "FloatSum", "config": {}, "depends_on": ["features"] }
+        ]
+    })
+}
+
+#[test]
+fn test_default_coercions() {
+    let coercions = CoercionRegistry::with_defaults();
+
+    let integer_to_float = coercions
+        .find(&DataType::Integer, &DataType::Float)
+        .expect("Integers convert into floats");
+    assert_eq!(integer_to_float.name(), "integer_to_float");
+    assert_eq!(integer_to_float.apply(Data::Integer(3)), Ok(Data::Float(3.0)));
+
+    let to_json = coercions
+        .find(&DataType::List(Box::new(DataType::Float)), &DataType::Json)
+        .expect("Number lists convert into JSON");
+    assert_eq!(
+        to_json.apply(Data::List(vec![Data::Float(1.5), Data::Float(2.0)])),
+        Ok(Data::Json(json!([1.5, 2.0])))
+    );
+
+    let json_to_float = coercions.find(&DataType::Json, &DataType::Float).unwrap();
+    assert_eq!(json_to_float.apply(Data::Json(json!(1.5))), Ok(Data::Float(1.5)));
+    assert!(json_to_float.apply(Data::Json(json!("1.5"))).is_err());
+
+    assert!(coercions.find(&DataType::Text, &DataType::Float).is_none());
+    assert!(CoercionRegistry::new()
+        .find(&DataType::Integer, &DataType::Float)
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_coercions_are_opt_in_and_reported() {
+    let registry = setup_test_registry();
+    let err = build_dag(&registry, DAGSettings::cache_off(), &adder_into_halver()).unwrap_err();
+    assert!(
+        err.contains("number -> half: output Integer is incompatible with input Float"),
+        "Unexpected error: {err}"
+    );
+
+    let dag = build_dag(&registry, coercing(), &adder_into_halver()).expect("Valid DAG");
+    assert_eq!(
+        dag.implicit_conversions(),
+        [ImplicitConversion {
+            source: "number".to_string(),
+            target: "half".to_string(),
+            port: None,
+            coercion: "integer_to_float".to_string(),
+            from: DataType::Integer,
+            to: DataType::Float,
+        }]
+    );
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.get("half"), Some(&Data::Float(1.5)));
+}
+
+#[tokio::test]
+async fn test_fan_in_elements_are_coerced() {
+    let registry = setup_test_registry();
+    let dag = build_dag(
+        &registry,
+        coercing(),
+        &json!({
+            "alias": "fan_in_coercion",
+            "nodes": [
+                { "id": "a", "component_type": "Adder", "config": { "value": 1 }, "inputs": 1 },
+                { "id": "b", "component_type": "Adder", "config": { "value": 2 }, "inputs": 1 },
+                { "id": "sum", "component_type": "FloatSum", "config": {}, "depends_on": ["a", "b"] }
+            ]
+        }),
+    )
+    .expect("Valid DAG");
+
+    assert_eq!(dag.implicit_conversions().len(), 2);
+    assert!(dag
+        .implicit_conversions()
+        .iter()
+        .all(|conversion| conversion.to == DataType::Float));
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.get("sum"), Some(&Data::Float(5.0)));
+}
+
+#[tokio::test]
+async fn test_json_arrays_convert_into_lists() {
+    let registry = setup_test_registry();
+    let dag = build_dag(
+        &registry,
+        coercing(),
+        &features_into_sum(".features", &json!([1, 2.5])),
+    )
+    .expect("Valid DAG");
+    assert_eq!(dag.implicit_conversions()[0].coercion, "json_array_to_float_list");
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.get("sum"), Some(&Data::Float(3.5)));
+
+    // Whether the JSON converts is only known at runtime
+    let dag = build_dag(
+        &registry,
+        coercing(),
+        &features_into_sum(".features | length", &json!(2)),
+    )
+    .expect("Valid DAG");
+    let err = dag.execute(None).await.unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Implicit conversion json_array_to_float_list of the output of features failed"),
+        "Unexpected error: {err}"
+    );
+}
+
+#[tokio::test]
+async fn test_registered_coercions_take_precedence() {
+    let mut registry = setup_test_registry();
+    registry.register_coercion("doubled_float", DataType::Integer, DataType::Float, |data| {
+        data.as_integer()
+            .map(|i| Data::Float(f64::from(i) * 2.0))
+            .ok_or_else(|| "Expected an integer".to_string())
+    });
+
+    let dag = build_dag(&registry, coercing(), &adder_into_halver()).expect("Valid DAG");
+    assert_eq!(dag.implicit_conversions()[0].coercion, "doubled_float");
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.get("half"), Some(&Data::Float(3.0)));
+}
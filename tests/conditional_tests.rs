@@ -0,0 +1,163 @@
+use baselard::component::{Data, Registry};
+use baselard::components::adder::Adder;
+use baselard::components::crash_test_dummy::CrashTestDummy;
+use baselard::components::json_combiner::JsonCombiner;
+use baselard::dag::{DAGSettings, NodeStatus, DAG};
+use baselard::dagir::DAGIR;
+use serde_json::json;
+
+fn setup_test_registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<Adder>("Adder");
+    registry.register::<CrashTestDummy>("CrashTestDummy");
+    registry.register::<JsonCombiner>("JsonCombiner");
+    registry
+}
+
+fn build_dag(json_config: &serde_json::Value) -> Result<DAG, String> {
+    let registry = setup_test_registry();
+    DAGIR::from_json(json_config)
+        .and_then(|ir| DAG::from_ir(&ir, &registry, DAGSettings::cache_off(), None))
+}
+
+/// `small` only runs for inputs below 10 and `large` only for the others,
+/// both feeding `total` with the given join policy
+fn routing_config(input: i32, join: &str) -> serde_json::Value {
+    json!({
+        "alias": "routing_test",
+        "nodes": [
+            { "id": "source", "component_type": "Adder", "config": { "value": 0 }, "inputs": input },
+            {
+                "id": "small",
+                "component_type": "Adder",
+                "config": { "value": 1 },
+                "depends_on": ["source"],
+                "when": ". < 10"
+            },
+            {
+                "id": "large",
+                "component_type": "Adder",
+                "config": { "value": 100 },
+                "depends_on": ["source"],
+                "when": ". >= 10"
+            },
+            {
+                "id": "total",
+                "component_type": "Adder",
+                "config": { "value": 0 },
+                "depends_on": ["small", "large"],
+                "join": join
+            }
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_false_condition_bypasses_node_and_all_joins() {
+    let dag = build_dag(&routing_config(3, "all")).expect("Valid DAG");
+
+    let report = dag.execute_with_report(None).await.expect("Bypassing isn't an error");
+    assert!(report.is_complete());
+    assert_eq!(report.results.get("small"), Some(&Data::Integer(4)));
+    assert!(!report.results.contains_key("large"));
+    assert!(!report.results.contains_key("total"));
+
+    assert!(matches!(report.statuses["large"], NodeStatus::ConditionFalse));
+    assert!(matches!(
+        &report.statuses["total"],
+        NodeStatus::Bypassed { skipped_dependency } if skipped_dependency == "large"
+    ));
+}
+
+#[tokio::test]
+async fn test_any_join_executes_with_produced_outputs() {
+    for (input, expected) in [(3, 4), (30, 130)] {
+        let dag = build_dag(&routing_config(input, "any")).expect("Valid DAG");
+
+        let report = dag.execute_with_report(None).await.expect("Execution success");
+        assert_eq!(report.results.get("total"), Some(&Data::Integer(expected)));
+        assert!(report.statuses["total"].is_succeeded());
+    }
+}
+
+#[tokio::test]
+async fn test_first_available_join_does_not_wait_for_slow_dependencies() {
+    let dag = build_dag(&json!({
+        "alias": "first_available_test",
+        "nodes": [
+            { "id": "fast", "component_type": "Adder", "config": { "value": 1 }, "inputs": 1 },
+            { "id": "slow", "component_type": "CrashTestDummy", "config": { "sleep_duration_ms": 100 } },
+            {
+                "id": "first",
+                "component_type": "CrashTestDummy",
+                "config": {},
+                "depends_on": ["slow", "fast"],
+                "join": "first_available"
+            }
+        ]
+    }))
+    .expect("Valid DAG");
+
+    let results = dag.execute(None).await.expect("Execution success");
+    let position = |node_id: &str| results.get_index_of(node_id).unwrap();
+    assert!(
+        position("first") < position("slow"),
+        "first should complete before slow: {results:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_condition_evaluated_against_named_ports() {
+    let dag = build_dag(&json!({
+        "alias": "port_condition_test",
+        "nodes": [
+            { "id": "user", "component_type": "JsonCombiner", "config": {}, "inputs": [] },
+            { "id": "order", "component_type": "JsonCombiner", "config": {}, "inputs": [] },
+            {
+                "id": "combined",
+                "component_type": "JsonCombiner",
+                "config": {},
+                "depends_on": [
+                    { "from": "user", "as": "customer" },
+                    { "from": "order", "as": "purchase" }
+                ],
+                "when": "has(\"customer\") and (.purchase | length == 0)"
+            }
+        ]
+    }))
+    .expect("Valid DAG");
+
+    let results = dag.execute(None).await.expect("Execution success");
+    assert!(results.contains_key("combined"));
+}
+
+#[test]
+fn test_invalid_condition_rejected_at_build_time() {
+    let err = build_dag(&json!({
+        "alias": "invalid_condition_test",
+        "nodes": [
+            { "id": "source", "component_type": "Adder", "config": { "value": 0 }, "inputs": 1, "when": ". >" }
+        ]
+    }))
+    .unwrap_err();
+    assert!(err.contains("Node source: Invalid condition"), "Unexpected error: {err}");
+}
+
+#[test]
+fn test_first_available_join_rejects_named_ports() {
+    let err = DAGIR::from_json(&json!({
+        "alias": "first_available_ports_test",
+        "nodes": [
+            { "id": "a", "component_type": "Adder", "config": { "value": 0 }, "inputs": 1 },
+            {
+                "id": "b",
+                "component_type": "JsonCombiner",
+                "config": {},
+                "depends_on": [{ "from": "a", "as": "value" }],
+                "join": "first_available"
+            }
+        ]
+    }))
+    .unwrap_err();
+    assert!(err.contains("can't be bound to named ports"), "Unexpected error: {err}");
+}
@@ -0,0 +1,98 @@
+use baselard::component::{Data, Registry};
+use baselard::components::adder::Adder;
+use baselard::components::crash_test_dummy::CrashTestDummy;
+use baselard::components::data_to_json_processor::DataToJsonProcessor;
+use baselard::components::payload_transformer::PayloadTransformer;
+use baselard::components::wildcard_processor::WildcardProcessor;
+use baselard::dag::{DAGSettings, DAG};
+use baselard::dagir::DAGIR;
+use serde_json::{json, Value};
+
+fn setup_test_registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<Adder>("Adder");
+    registry.register::<CrashTestDummy>("CrashTestDummy");
+    registry.register::<PayloadTransformer>("PayloadTransformer");
+    registry.register::<WildcardProcessor>("WildcardProcessor");
+    registry.register::<DataToJsonProcessor>("DataToJsonProcessor");
+    registry
+}
+
+fn build_single_node_dag(component_type: &str, config: &Value, inputs: &Value) -> Result<DAG, String> {
+    let registry = setup_test_registry();
+    DAGIR::from_json(&json!({
+        "alias": "config_test",
+        "nodes": [
+            { "id": "node", "component_type": component_type, "config": config, "inputs": inputs }
+        ]
+    }))
+    .and_then(|ir| DAG::from_ir(&ir, &registry, DAGSettings::cache_off(), None))
+}
+
+#[test]
+fn test_registry_exposes_config_schemas() {
+    let registry = setup_test_registry();
+
+    let adder = registry.config_schema("Adder").expect("Adder has a config schema");
+    assert_eq!(adder["properties"]["value"]["type"], json!("integer"));
+    assert_eq!(adder["required"], json!(["value"]));
+
+    let dummy = registry
+        .config_schema("CrashTestDummy")
+        .expect("CrashTestDummy has a config schema");
+    assert!(dummy["properties"]["sleep_duration_ms"].is_object());
+    assert!(
+        dummy.get("required").is_none(),
+        "Every CrashTestDummy field has a default: {dummy}"
+    );
+
+    let transformer = registry.config_schema("PayloadTransformer").unwrap();
+    assert_eq!(transformer["required"], json!(["validation_data"]));
+
+    assert!(registry.config_schema("DataToJsonProcessor").is_none());
+    assert!(registry.config_schema("Unknown").is_none());
+}
+
+#[test]
+fn test_invalid_configs_are_rejected_with_paths() {
+    let Err(err) = build_single_node_dag("Adder", &json!({ "value": "one" }), &json!(1)) else {
+        panic!("A string value should be rejected");
+    };
+    assert!(err.contains("node"), "Unexpected error: {err}");
+    assert!(err.contains("Invalid config at value: invalid type: string"), "Unexpected error: {err}");
+
+    let Err(err) = build_single_node_dag("Adder", &json!({}), &json!(1)) else {
+        panic!("A missing value should be rejected");
+    };
+    assert!(err.contains("Invalid config: missing field `value`"), "Unexpected error: {err}");
+
+    let Err(err) = build_single_node_dag("Adder", &json!({ "value": 1_i64 << 40 }), &json!(1)) else {
+        panic!("A value beyond 32 bits should be rejected");
+    };
+    assert!(err.contains("Invalid config at value"), "Unexpected error: {err}");
+
+    let Err(err) = build_single_node_dag(
+        "WildcardProcessor",
+        &json!({ "expected_input_keys": ["name", 1] }),
+        &json!({ "name": "Ada" }),
+    ) else {
+        panic!("A non-string key should be rejected");
+    };
+    assert!(err.contains("Invalid config at expected_input_keys[1]"), "Unexpected error: {err}");
+}
+
+#[tokio::test]
+async fn test_config_defaults_apply() {
+    let dag = build_single_node_dag("CrashTestDummy", &json!({}), &json!(null)).expect("Valid DAG");
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.get("node"), Some(&Data::Text("Success!".to_string())));
+
+    let dag = build_single_node_dag(
+        "PayloadTransformer",
+        &json!({ "validation_data": { "input": { "a": 1 }, "expected_output": { "a": 1 } } }),
+        &json!({ "a": 2 }),
+    )
+    .expect("The expression defaults to the identity");
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.get("node"), Some(&Data::Json(json!({ "a": 2 }))));
+}
@@ -0,0 +1,194 @@
+use baselard::component::{Component, Data, DataType, Registry, TensorDType};
+use baselard::components::data_to_json_processor::DataToJsonProcessor;
+use baselard::dag::{DAGSettings, NodeExecutionContext, DAG};
+use baselard::dagir::DAGIR;
+use indexmap::IndexMap;
+use serde_json::json;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+fn hash(data: &Data) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn float32_tensor(shape: Vec<usize>, values: &[f32]) -> Result<Data, String> {
+    let data = values.iter().flat_map(|value| value.to_le_bytes()).collect();
+    Data::tensor(shape, TensorDType::Float32, data)
+}
+
+#[test]
+fn test_integers_widen_into_int64() {
+    assert!(DataType::Integer.is_compatible_with(&DataType::Int64));
+    assert!(!DataType::Int64.is_compatible_with(&DataType::Integer));
+    assert!(Data::Integer(1).validate_type(&DataType::Int64));
+    assert!(!Data::Int64(1).validate_type(&DataType::Integer));
+    assert_eq!(Data::Integer(-1).as_int64(), Some(-1));
+    assert_eq!(Data::Int64(1 << 40).as_int64(), Some(1 << 40));
+}
+
+#[test]
+fn test_tensor_data_must_match_its_shape() {
+    let tensor = float32_tensor(vec![2, 2], &[1.0, 2.0, 3.0, 4.0]).expect("Valid tensor");
+    assert_eq!(tensor.get_type(), DataType::Tensor(TensorDType::Float32));
+    assert!(tensor.validate_type(&DataType::Tensor(TensorDType::Float32)));
+    assert!(!tensor.validate_type(&DataType::Tensor(TensorDType::Float64)));
+    assert_eq!(
+        tensor.to_json(),
+        json!({ "shape": [2, 2], "dtype": "float32", "data": [1.0, 2.0, 3.0, 4.0] })
+    );
+
+    let err = float32_tensor(vec![2, 3], &[1.0, 2.0]).unwrap_err();
+    assert!(err.contains("needs 24 bytes, got 8"), "Unexpected error: {err}");
+    assert!(Data::tensor(vec![usize::MAX, 2], TensorDType::UInt8, vec![]).is_err());
+}
+
+#[test]
+fn test_maps_are_typed_by_their_values() {
+    let entries = IndexMap::from([
+        ("a".to_string(), Data::Integer(1)),
+        ("b".to_string(), Data::Integer(2)),
+    ]);
+    let map = Data::Map(entries.clone());
+    let record = Data::Record(entries);
+
+    assert_eq!(map.get_type(), DataType::Map(Box::new(DataType::Integer)));
+    assert!(map.validate_type(&DataType::Map(Box::new(DataType::Int64))));
+    assert!(!map.validate_type(&DataType::Map(Box::new(DataType::Text))));
+    assert_eq!(map.to_json(), json!({ "a": 1, "b": 2 }));
+    assert_ne!(map, record);
+    assert_ne!(hash(&map), hash(&record));
+}
+
+#[test]
+fn test_entries_hash_regardless_of_order() {
+    let entries = [
+        ("a".to_string(), Data::Integer(1)),
+        ("b".to_string(), Data::Text("b".to_string())),
+    ];
+    let mut reversed = entries.clone();
+    reversed.reverse();
+
+    let map = Data::Map(IndexMap::from(entries.clone()));
+    let reversed_map = Data::Map(IndexMap::from(reversed.clone()));
+    assert_eq!(map, reversed_map);
+    assert_eq!(hash(&map), hash(&reversed_map));
+
+    let record = Data::Record(IndexMap::from(entries));
+    let reversed_record = Data::Record(IndexMap::from(reversed));
+    assert_eq!(record, reversed_record);
+    assert_eq!(hash(&record), hash(&reversed_record));
+}
+
+#[test]
+fn test_new_variants_round_trip_through_serde() {
+    let values = vec![
+        Data::Bool(true),
+        Data::Int64(i64::MIN),
+        Data::Bytes(vec![0, 1, 255]),
+        Data::Map(IndexMap::from([("key".to_string(), Data::Bool(false))])),
+        float32_tensor(vec![3], &[0.5, 1.5, 2.5]).expect("Valid tensor"),
+    ];
+
+    for value in values {
+        let serialized = serde_json::to_string(&value).expect("Serializable");
+        let deserialized: Data = serde_json::from_str(&serialized).expect("Deserializable");
+        assert_eq!(deserialized, value);
+        assert_eq!(hash(&deserialized), hash(&value));
+    }
+    assert_ne!(hash(&Data::Int64(1)), hash(&Data::Integer(1)));
+}
+
+#[tokio::test]
+async fn test_inputs_beyond_32_bits_are_int64() {
+    let mut registry = Registry::new();
+    registry.register::<DataToJsonProcessor>("DataToJsonProcessor");
+    let ir = DAGIR::from_json(&json!({
+        "alias": "int64_inputs",
+        "nodes": [
+            {
+                "id": "to_json",
+                "component_type": "DataToJsonProcessor",
+                "config": {},
+                "inputs": 5_000_000_000_i64
+            }
+        ]
+    }))
+    .expect("Valid IR");
+    let dag = DAG::from_ir(&ir, &registry, DAGSettings::cache_off(), None).expect("Valid DAG");
+
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(
+        results.get("to_json"),
+        Some(&Data::Json(json!({ "type": "integer", "value": 5_000_000_000_i64 })))
+    );
+
+    let err = DAGIR::from_json(&json!({
+        "alias": "too_large",
+        "nodes": [
+            { "id": "to_json", "component_type": "DataToJsonProcessor", "config": {}, "inputs": u64::MAX }
+        ]
+    }))
+    .unwrap_err();
+    assert!(err.contains("doesn't fit in 64 bits"), "Unexpected error: {err}");
+}
+
+#[test]
+fn test_every_variant_converts_to_json() {
+    let to_json = |data: Data| {
+        let context = NodeExecutionContext::new("to_json".to_string(), "request".to_string());
+        DataToJsonProcessor.execute(context, data).expect("Execution success")
+    };
+
+    let list = Data::List(vec![Data::Int64(1 << 40), Data::Bool(true), Data::Float(0.5)]);
+    assert_eq!(
+        to_json(list),
+        Data::Json(json!({ "type": "list", "values": [
+            { "type": "integer", "value": 1_i64 << 40 },
+            { "type": "bool", "value": true },
+            { "type": "float", "value": 0.5 },
+        ] }))
+    );
+
+    let map = Data::Map(IndexMap::from([("key".to_string(), Data::Bool(false))]));
+    assert_eq!(
+        to_json(map),
+        Data::Json(json!({
+            "type": "map",
+            "entries": { "key": { "type": "bool", "value": false } }
+        }))
+    );
+
+    assert_eq!(
+        to_json(Data::Bytes(vec![0, 255])),
+        Data::Json(json!({ "type": "bytes", "value": [0, 255] }))
+    );
+
+    let tensor = float32_tensor(vec![2], &[1.0, 2.0]).expect("Valid tensor");
+    assert_eq!(
+        to_json(tensor),
+        Data::Json(json!({
+            "type": "tensor",
+            "value": { "shape": [2], "dtype": "float32", "data": [1.0, 2.0] }
+        }))
+    );
+}
+
+#[test]
+fn test_records_and_nested_lists_convert_to_json() {
+    let context = NodeExecutionContext::new("to_json".to_string(), "request".to_string());
+    let record = Data::Record(IndexMap::from([
+        ("name".to_string(), Data::Text("a".to_string())),
+        ("scores".to_string(), Data::List(vec![Data::List(vec![Data::Null])])),
+    ]));
+    assert_eq!(DataToJsonProcessor.input_type(), DataType::Any);
+    assert_eq!(
+        DataToJsonProcessor.execute(context, record).expect("Execution success"),
+        Data::Json(json!({ "type": "record", "fields": {
+            "name": { "type": "text", "value": "a" },
+            "scores": { "type": "list", "values": [
+                { "type": "list", "values": [{ "type": "null" }] },
+            ] },
+        } }))
+    );
+}
@@ -0,0 +1,70 @@
+//! The process-wide node limit applies to every DAG execution in the test
+//! binary, so it gets a binary of its own.
+
+use baselard::component::Registry;
+use baselard::components::crash_test_dummy::CrashTestDummy;
+use baselard::dag::{set_global_node_limit, DAGSettings, DAG};
+use baselard::dagir::DAGIR;
+use serde_json::json;
+use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
+
+fn build_dag(alias: &str) -> DAG {
+    let mut registry = Registry::new();
+    registry.register::<CrashTestDummy>("CrashTestDummy");
+    let sleeper = |id: &str| {
+        json!({ "id": id, "component_type": "CrashTestDummy", "config": { "sleep_duration_ms": 30 }, "inputs": "a" })
+    };
+    let config = json!({
+        "alias": alias,
+        "nodes": [
+            sleeper("first"),
+            sleeper("second"),
+            {
+                "id": "each",
+                "component_type": "Map",
+                "config": {
+                    "dag": { "alias": "nap", "nodes": [sleeper("nap")] }
+                },
+                "inputs": ["a", "b"]
+            }
+        ]
+    });
+
+    let ir = DAGIR::from_json(&config).expect("Valid IR");
+    let settings = DAGSettings {
+        per_node_timeout_ms: None,
+        ..DAGSettings::cache_off()
+    };
+    DAG::from_ir(&ir, &registry, settings, None).expect("Valid DAG")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_global_node_limit_spans_executions() {
+    let left = build_dag("left");
+    let right = build_dag("right");
+
+    set_global_node_limit(NonZeroUsize::new(1));
+    let start = Instant::now();
+    let (left_results, right_results) = tokio::join!(left.execute(None), right.execute(None));
+    let elapsed = start.elapsed();
+    set_global_node_limit(None);
+
+    assert_eq!(left_results.expect("Execution success").len(), 3);
+    assert_eq!(right_results.expect("Execution success").len(), 3);
+    // Four sleepers and four Map elements, one at a time; the Map nodes
+    // themselves don't hold on to the only permit
+    assert!(
+        elapsed >= Duration::from_millis(240),
+        "Nodes should execute one at a time, took {elapsed:?}"
+    );
+
+    let start = Instant::now();
+    let (left_results, right_results) = tokio::join!(left.execute(None), right.execute(None));
+    assert!(left_results.is_ok() && right_results.is_ok());
+    assert!(
+        start.elapsed() < Duration::from_millis(200),
+        "Without the limit, nodes should execute concurrently, took {:?}",
+        start.elapsed()
+    );
+}
@@ -0,0 +1,111 @@
+use baselard::cache::{Cache, DAGResult};
+use baselard::component::{Data, Registry};
+use baselard::components::replay::Replay;
+use baselard::dag::{DAGSettings, DAG};
+use baselard::dagir::{DAGConfig, NodeConfig, DAGIR};
+use baselard::history::{HistoryStore, JsonlHistory, RotationPolicy};
+use indexmap::IndexMap;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::time::Duration;
+
+fn result(request_id: &str, value: i32) -> DAGResult {
+    DAGResult {
+        request_id: request_id.to_string(),
+        timestamp: chrono::Utc::now(),
+        node_results: IndexMap::from([("node".to_string(), Data::Integer(value))]),
+    }
+}
+
+fn value(history: &JsonlHistory, request_id: &str) -> Option<Data> {
+    history
+        .get(request_id)
+        .expect("Readable history")
+        .and_then(|mut result| result.node_results.shift_remove("node"))
+}
+
+#[test]
+fn test_index_is_logged_on_every_append_and_reloaded() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let path = temp_dir.path().join("history.jsonl");
+    let history = JsonlHistory::new(&path).with_rotation(RotationPolicy {
+        max_segment_bytes: Some(1),
+        ..RotationPolicy::default()
+    });
+    for (request_id, value) in [("a", 1), ("b", 2), ("a", 3)] {
+        history.append(&result(request_id, value)).unwrap();
+    }
+
+    let sidecar = fs::read_to_string(temp_dir.path().join("history.jsonl.index")).unwrap();
+    assert_eq!(
+        sidecar.lines().filter(|line| line.contains(r#""op":"append""#)).count(),
+        3
+    );
+    drop(history);
+
+    let reloaded = JsonlHistory::new(&path);
+    assert_eq!(value(&reloaded, "a"), Some(Data::Integer(3)));
+    assert_eq!(value(&reloaded, "b"), Some(Data::Integer(2)));
+    assert_eq!(value(&reloaded, "c"), None);
+}
+
+#[test]
+fn test_index_catches_up_with_unindexed_results() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let path = temp_dir.path().join("history.jsonl");
+
+    // A history written before it was indexed
+    let lines: Vec<String> = [result("a", 1), result("b", 2)]
+        .iter()
+        .map(|result| serde_json::to_string(result).unwrap() + "\n")
+        .collect();
+    fs::write(&path, lines.concat()).unwrap();
+
+    let history = JsonlHistory::new(&path);
+    assert_eq!(value(&history, "a"), Some(Data::Integer(1)));
+    assert!(temp_dir.path().join("history.jsonl.index").exists());
+    history.append(&result("c", 3)).unwrap();
+    drop(history);
+
+    // A result appended without the index, e.g. if the process died between
+    // the two writes
+    let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+    writeln!(file, "{}", serde_json::to_string(&result("d", 4)).unwrap()).unwrap();
+    drop(file);
+
+    let history = JsonlHistory::new(&path);
+    assert_eq!(value(&history, "b"), Some(Data::Integer(2)));
+    assert_eq!(value(&history, "c"), Some(Data::Integer(3)));
+    assert_eq!(value(&history, "d"), Some(Data::Integer(4)));
+}
+
+#[tokio::test]
+async fn test_replay_shares_the_cache_index() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let path = temp_dir.path().join("history.jsonl");
+    fs::write(&path, "").unwrap();
+
+    let mut registry = Registry::new();
+    registry.register::<Replay>("Replay");
+    let config = DAGConfig::new("replay").with_node(
+        NodeConfig::new("replay", "Replay")
+            .with_config(json!({ "history_path": path }))
+            .with_inputs(json!({ "request_id": "request" })),
+    );
+    let ir = DAGIR::from_config(config).expect("Valid IR");
+    // Replay loads the index before anything is recorded
+    let dag = DAG::from_ir(&ir, &registry, DAGSettings::cache_off(), None).expect("Valid DAG");
+
+    let cache = Cache::new(Some(&path), 100);
+    let results = IndexMap::from([("node".to_string(), Data::Integer(1))]);
+    cache.store_result(0, &HashMap::new(), &results, &"request".to_string());
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let replayed = dag.execute(None).await.expect("Execution success");
+    assert_eq!(
+        replayed.get("replay"),
+        Some(&Data::Json(json!({ "node": { "integer": 1 } })))
+    );
+}
@@ -0,0 +1,173 @@
+use baselard::cache::DAGResult;
+use baselard::component::{Data, Registry};
+use baselard::components::replay::Replay;
+use baselard::dag::{DAGSettings, DAG};
+use baselard::dagir::{DAGConfig, NodeConfig, DAGIR};
+use baselard::history::{
+    CompactionReport, HistoryStore, JsonlHistory, RetentionPolicy, RotationPolicy,
+};
+use indexmap::IndexMap;
+use serde_json::json;
+use std::path::Path;
+use std::time::Duration;
+
+fn result(request_id: &str, value: i32) -> DAGResult {
+    DAGResult {
+        request_id: request_id.to_string(),
+        timestamp: chrono::Utc::now(),
+        node_results: IndexMap::from([("node".to_string(), Data::Integer(value))]),
+    }
+}
+
+fn value(history: &JsonlHistory, request_id: &str) -> Option<Data> {
+    history
+        .get(request_id)
+        .expect("Readable history")
+        .and_then(|mut result| result.node_results.shift_remove("node"))
+}
+
+/// Rotates before every append but the first into an empty file
+fn rotate_every_append() -> RotationPolicy {
+    RotationPolicy {
+        max_segment_bytes: Some(1),
+        ..RotationPolicy::default()
+    }
+}
+
+fn history(dir: &Path) -> JsonlHistory {
+    JsonlHistory::new(dir.join("history.jsonl"))
+}
+
+#[test]
+fn test_lookups_span_rotated_segments() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let history = history(temp_dir.path()).with_rotation(rotate_every_append());
+
+    for (request_id, value) in [("a", 1), ("b", 2), ("a", 3)] {
+        history.append(&result(request_id, value)).unwrap();
+    }
+
+    assert_eq!(history.segments().unwrap().len(), 3);
+    assert_eq!(value(&history, "a"), Some(Data::Integer(3)), "The latest result wins");
+    assert_eq!(value(&history, "b"), Some(Data::Integer(2)));
+    assert_eq!(value(&history, "c"), None);
+}
+
+#[test]
+fn test_segments_rotate_by_age() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let history = history(temp_dir.path()).with_rotation(RotationPolicy {
+        max_segment_age_ms: Some(20),
+        ..RotationPolicy::default()
+    });
+
+    history.append(&result("a", 1)).unwrap();
+    history.append(&result("b", 2)).unwrap();
+    assert_eq!(history.segments().unwrap().len(), 1);
+
+    std::thread::sleep(Duration::from_millis(30));
+    history.append(&result("c", 3)).unwrap();
+    assert_eq!(history.segments().unwrap().len(), 2);
+}
+
+#[test]
+fn test_retention_drops_old_segments() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let history = history(temp_dir.path())
+        .with_rotation(rotate_every_append())
+        .with_retention(RetentionPolicy {
+            max_entries: Some(2),
+            ..RetentionPolicy::default()
+        });
+
+    for (i, request_id) in ["a", "b", "c", "d", "e"].into_iter().enumerate() {
+        history.append(&result(request_id, i32::try_from(i).unwrap())).unwrap();
+    }
+
+    // The two latest rotated segments, and the file being appended to
+    assert_eq!(history.segments().unwrap().len(), 3);
+    assert_eq!(value(&history, "b"), None);
+    assert_eq!(value(&history, "c"), Some(Data::Integer(2)));
+    assert_eq!(value(&history, "e"), Some(Data::Integer(4)));
+}
+
+#[test]
+fn test_retention_drops_expired_segments() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let history = history(temp_dir.path())
+        .with_rotation(rotate_every_append())
+        .with_retention(RetentionPolicy {
+            max_age_ms: Some(20),
+            ..RetentionPolicy::default()
+        });
+
+    history.append(&result("a", 1)).unwrap();
+    history.append(&result("b", 2)).unwrap();
+    std::thread::sleep(Duration::from_millis(30));
+    history.append(&result("c", 3)).unwrap();
+
+    assert_eq!(value(&history, "a"), None);
+    assert_eq!(value(&history, "b"), Some(Data::Integer(2)));
+    assert_eq!(value(&history, "c"), Some(Data::Integer(3)));
+}
+
+#[test]
+fn test_compaction_keeps_latest_results_and_indexes_them() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let history = history(temp_dir.path())
+        .with_rotation(rotate_every_append())
+        .with_retention(RetentionPolicy {
+            max_entries: Some(2),
+            ..RetentionPolicy::default()
+        });
+
+    for (request_id, value) in [("a", 1), ("b", 2), ("a", 3), ("c", 4)] {
+        history.append(&result(request_id, value)).unwrap();
+    }
+
+    let report = history.compact().unwrap();
+    assert_eq!(
+        report,
+        CompactionReport {
+            segments_compacted: 3,
+            entries_read: 3,
+            entries_kept: 2,
+        }
+    );
+    assert_eq!(history.segments().unwrap().len(), 1);
+    assert!(temp_dir.path().join("history.jsonl.index").exists());
+
+    // Appends after compaction are found alongside the compacted segment,
+    // by a history reloaded from disk too
+    history.append(&result("d", 5)).unwrap();
+    let assert_values = |history: &JsonlHistory| {
+        assert_eq!(value(history, "a"), Some(Data::Integer(3)));
+        assert_eq!(value(history, "b"), None);
+        assert_eq!(value(history, "c"), Some(Data::Integer(4)));
+        assert_eq!(value(history, "d"), Some(Data::Integer(5)));
+    };
+    assert_values(&history);
+    drop(history);
+    assert_values(&JsonlHistory::new(temp_dir.path().join("history.jsonl")));
+}
+
+#[tokio::test]
+async fn test_replay_finds_rotated_results() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let history = history(temp_dir.path()).with_rotation(rotate_every_append());
+    history.append(&result("old", 1)).unwrap();
+    history.append(&result("new", 2)).unwrap();
+
+    let mut registry = Registry::new();
+    registry.register::<Replay>("Replay");
+    let config = DAGConfig::new("replay").with_node(
+        NodeConfig::new("replay", "Replay")
+            .with_config(json!({ "history_path": history.path() }))
+            .with_inputs(json!({ "request_id": "old" })),
+    );
+    let ir = DAGIR::from_config(config).expect("Valid IR");
+    let dag = DAG::from_ir(&ir, &registry, DAGSettings::cache_off(), None).expect("Valid DAG");
+
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.get("replay"), Some(&Data::Json(json!({ "node": { "integer": 1 } }))));
+}
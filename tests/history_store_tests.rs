@@ -0,0 +1,87 @@
+use baselard::cache::Cache;
+use baselard::component::Data;
+use baselard::history::{HistoryStore, MemoryHistory, SqliteHistory};
+use indexmap::IndexMap;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn results(value: i32) -> IndexMap<String, Data> {
+    IndexMap::from([("node".to_string(), Data::Integer(value))])
+}
+
+async fn store(cache: &Cache, request_id: &str, value: i32) {
+    cache.store_result(0, &HashMap::new(), &results(value), &request_id.to_string());
+    // The history is written in the background
+    tokio::time::sleep(Duration::from_millis(50)).await;
+}
+
+#[tokio::test]
+async fn test_jsonl_history_survives_the_cache() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let path = temp_dir.path().join("history.jsonl");
+
+    store(&Cache::new(Some(&path), 100), "request", 1).await;
+
+    let cache = Cache::new(Some(&path), 100);
+    let result = cache.get_historical_result("request").await.expect("Recorded result");
+    assert_eq!(result.node_results, results(1));
+    assert!(cache.get_historical_result("missing").await.is_none());
+}
+
+#[tokio::test]
+async fn test_sqlite_history_survives_the_cache() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let path = temp_dir.path().join("history.db");
+
+    let cache = Cache::with_history_store(SqliteHistory::open(&path).unwrap(), 100);
+    store(&cache, "first", 1).await;
+    store(&cache, "second", 2).await;
+    drop(cache);
+
+    let cache = Cache::with_history_store(SqliteHistory::open(&path).unwrap(), 100);
+    let result = cache.get_historical_result("second").await.expect("Recorded result");
+    assert_eq!(result.node_results, results(2));
+    assert!(cache.get_historical_result("missing").await.is_none());
+}
+
+#[test]
+fn test_stores_replace_results_by_request_id() {
+    let stores: Vec<Box<dyn HistoryStore>> = vec![
+        Box::new(MemoryHistory::new()),
+        Box::new(SqliteHistory::open_in_memory().unwrap()),
+    ];
+    for store in stores {
+        for value in [1, 2] {
+            let result = baselard::cache::DAGResult {
+                request_id: "request".to_string(),
+                timestamp: chrono::Utc::now(),
+                node_results: results(value),
+            };
+            store.append(&result).unwrap();
+        }
+        let result = store.get("request").unwrap().expect("Recorded result");
+        assert_eq!(result.node_results, results(2));
+        assert!(store.get("missing").unwrap().is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_history_write_failures_are_reported() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    // A directory can't be appended to
+    let reported = Arc::new(AtomicUsize::new(0));
+    let cache = Cache::new(Some(temp_dir.path()), 100).with_history_error_handler({
+        let reported = Arc::clone(&reported);
+        move |_| {
+            reported.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+
+    store(&cache, "request", 1).await;
+    assert_eq!(cache.history_write_failures(), 1);
+    assert_eq!(reported.load(Ordering::Relaxed), 1);
+    // Still replayable from memory
+    assert!(cache.get_result_by_request_id("request").is_some());
+}
@@ -0,0 +1,199 @@
+use baselard::component::Registry;
+use baselard::component::{Component, Data, DataType, Error};
+use baselard::components::adder::Adder;
+use baselard::components::json_combiner::JsonCombiner;
+use baselard::components::string_length_counter::StringLengthCounter;
+use baselard::dag::{DAGError, DAGSettings, NodeExecutionContext, DAG};
+use baselard::dagir::DAGIR;
+use serde_json::json;
+
+/// Subtracts its `subtrahend` port from its `minuend` port
+struct Subtractor;
+
+impl Component for Subtractor {
+    fn configure(_: serde_json::Value) -> Result<Self, Error> {
+        Ok(Self)
+    }
+
+    fn execute(&self, context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
+        let ports = input.as_record().ok_or_else(|| DAGError::ExecutionError {
+            node_id: context.node_id.clone(),
+            reason: "Subtractor requires named input ports".to_string(),
+        })?;
+        let minuend = ports["minuend"].as_integer().unwrap();
+        let subtrahend = ports["subtrahend"].as_integer().unwrap();
+        Ok(Data::Integer(minuend - subtrahend))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Record(
+            [
+                ("minuend".to_string(), DataType::Integer),
+                ("subtrahend".to_string(), DataType::Integer),
+            ]
+            .into(),
+        )
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Integer
+    }
+}
+
+fn setup_test_registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<Adder>("Adder");
+    registry.register::<JsonCombiner>("JsonCombiner");
+    registry.register::<StringLengthCounter>("StringLengthCounter");
+    registry.register::<Subtractor>("Subtractor");
+    registry
+}
+
+fn build_dag(json_config: &serde_json::Value) -> Result<DAG, String> {
+    let registry = setup_test_registry();
+    DAGIR::from_json(json_config)
+        .and_then(|ir| DAG::from_ir(&ir, &registry, DAGSettings::cache_off(), None))
+}
+
+fn subtraction_config(minuend_port: &str, subtrahend_source: &str) -> serde_json::Value {
+    json!({
+        "alias": "subtraction_test",
+        "nodes": [
+            { "id": "ten", "component_type": "Adder", "config": { "value": 9 }, "inputs": 1 },
+            { "id": "three", "component_type": "Adder", "config": { "value": 2 }, "inputs": 1 },
+            { "id": "text_length", "component_type": "StringLengthCounter", "config": {}, "inputs": "abc" },
+            {
+                "id": "difference",
+                "component_type": "Subtractor",
+                "config": {},
+                "depends_on": [
+                    { "from": "three", "as": "subtrahend" },
+                    { "from": subtrahend_source, "as": "unused" },
+                    { "from": "ten", "as": minuend_port }
+                ]
+            }
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_named_ports_deliver_record_input() {
+    let dag = build_dag(&json!({
+        "alias": "named_ports_test",
+        "nodes": [
+            { "id": "ten", "component_type": "Adder", "config": { "value": 9 }, "inputs": 1 },
+            { "id": "three", "component_type": "Adder", "config": { "value": 2 }, "inputs": 1 },
+            {
+                "id": "difference",
+                "component_type": "Subtractor",
+                "config": {},
+                "depends_on": [
+                    { "from": "three", "as": "subtrahend" },
+                    { "from": "ten", "as": "minuend" }
+                ]
+            }
+        ]
+    }))
+    .expect("Valid DAG");
+
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.get("difference"), Some(&Data::Integer(7)));
+}
+
+#[test]
+fn test_unknown_port_rejected_at_build_time() {
+    let err = build_dag(&subtraction_config("minuend", "text_length")).unwrap_err();
+    assert!(err.contains("text_length -> difference: unknown input port unused"), "Unexpected error: {err}");
+}
+
+#[test]
+fn test_port_type_mismatch_rejected_at_build_time() {
+    let err = build_dag(&json!({
+        "alias": "port_type_mismatch_test",
+        "nodes": [
+            { "id": "ten", "component_type": "Adder", "config": { "value": 9 }, "inputs": 1 },
+            { "id": "combined", "component_type": "JsonCombiner", "config": {}, "depends_on": [] },
+            {
+                "id": "difference",
+                "component_type": "Subtractor",
+                "config": {},
+                "depends_on": [
+                    { "from": "combined", "as": "subtrahend" },
+                    { "from": "ten", "as": "minuend" }
+                ]
+            }
+        ]
+    }))
+    .unwrap_err();
+    assert!(
+        err.contains("combined -> difference: input port subtrahend expects Integer, but the output is Json"),
+        "Unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_unbound_port_rejected_at_build_time() {
+    let err = build_dag(&json!({
+        "alias": "unbound_port_test",
+        "nodes": [
+            { "id": "ten", "component_type": "Adder", "config": { "value": 9 }, "inputs": 1 },
+            {
+                "id": "difference",
+                "component_type": "Subtractor",
+                "config": {},
+                "depends_on": [{ "from": "ten", "as": "minuend" }]
+            }
+        ]
+    }))
+    .unwrap_err();
+    assert!(
+        err.contains("difference: unbound input ports: [\"subtrahend\"]"),
+        "Unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_mixed_named_and_unnamed_dependencies_rejected() {
+    let err = DAGIR::from_json(&json!({
+        "alias": "mixed_dependencies_test",
+        "nodes": [
+            { "id": "ten", "component_type": "Adder", "config": { "value": 9 }, "inputs": 1 },
+            { "id": "three", "component_type": "Adder", "config": { "value": 2 }, "inputs": 1 },
+            {
+                "id": "difference",
+                "component_type": "Subtractor",
+                "config": {},
+                "depends_on": ["three", { "from": "ten", "as": "minuend" }]
+            }
+        ]
+    }))
+    .unwrap_err();
+    assert!(err.contains("mixes named and unnamed dependencies"), "Unexpected error: {err}");
+}
+
+#[tokio::test]
+async fn test_json_combiner_keys_named_ports() {
+    let dag = build_dag(&json!({
+        "alias": "json_combiner_ports_test",
+        "nodes": [
+            { "id": "user", "component_type": "JsonCombiner", "config": {}, "inputs": [] },
+            { "id": "order", "component_type": "JsonCombiner", "config": {}, "inputs": [] },
+            {
+                "id": "combined",
+                "component_type": "JsonCombiner",
+                "config": {},
+                "depends_on": [
+                    { "from": "user", "as": "customer" },
+                    { "from": "order", "as": "purchase" }
+                ]
+            }
+        ]
+    }))
+    .expect("Valid DAG");
+
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(
+        results.get("combined"),
+        Some(&Data::Json(json!({ "customer": {}, "purchase": {} })))
+    );
+}
@@ -0,0 +1,181 @@
+use baselard::component::{Data, DataType, JsonSchema, ObjectSchema, Registry};
+use baselard::components::payload_transformer::PayloadTransformer;
+use baselard::components::wildcard_processor::WildcardProcessor;
+use baselard::dag::{DAGSettings, DAG};
+use baselard::dagir::DAGIR;
+use serde_json::{json, Value};
+
+fn user_schema() -> JsonSchema {
+    JsonSchema::Object(
+        ObjectSchema::new()
+            .with_required_property("name", JsonSchema::String)
+            .with_property("tags", JsonSchema::array(JsonSchema::String)),
+    )
+}
+
+fn build_dag(
+    transformer_config: &Value,
+    wildcard_keys: &[&str],
+    settings: DAGSettings,
+) -> Result<DAG, String> {
+    let mut registry = Registry::new();
+    registry.register::<PayloadTransformer>("PayloadTransformer");
+    registry.register::<WildcardProcessor>("WildcardProcessor");
+    DAGIR::from_json(&json!({
+        "alias": "schema_test",
+        "nodes": [
+            {
+                "id": "transform",
+                "component_type": "PayloadTransformer",
+                "config": transformer_config,
+                "inputs": { "user": { "name": "Ada" } }
+            },
+            {
+                "id": "wildcard",
+                "component_type": "WildcardProcessor",
+                "config": { "expected_input_keys": wildcard_keys, "expected_output_keys": ["name"] },
+                "depends_on": ["transform"]
+            }
+        ]
+    }))
+    .and_then(|ir| DAG::from_ir(&ir, &registry, settings, None))
+}
+
+fn optimistic() -> DAGSettings {
+    DAGSettings {
+        optimistic_type_checks: true,
+        ..DAGSettings::cache_off()
+    }
+}
+
+#[test]
+fn test_schemas_parse_from_json_schema_documents() {
+    let document = json!({
+        "title": "User",
+        "type": "object",
+        "properties": {
+            "name": { "type": "string" },
+            "tags": { "type": "array", "items": { "type": "string" } }
+        },
+        "required": ["name"]
+    });
+    let schema = JsonSchema::from_json(&document).expect("Valid schema");
+    assert_eq!(schema, user_schema());
+    assert_eq!(JsonSchema::from_json(&schema.to_json()), Ok(schema));
+    assert_eq!(JsonSchema::from_json(&json!({})), Ok(JsonSchema::Any));
+
+    let err = JsonSchema::from_json(&json!({
+        "type": "object",
+        "properties": { "id": { "oneOf": [] } }
+    }))
+    .unwrap_err();
+    assert_eq!(err, "#/properties/id: unsupported keyword `oneOf`");
+}
+
+#[test]
+fn test_payloads_are_validated_with_paths() {
+    let schema = user_schema();
+    assert!(schema.validate(&json!({ "name": "Ada", "extra": 1 })).is_ok());
+    assert_eq!(
+        schema.validate(&json!({ "tags": [] })),
+        Err("$: missing required property `name`".to_string())
+    );
+    assert_eq!(
+        schema.validate(&json!({ "name": "Ada", "tags": ["a", 1] })),
+        Err("$.tags[1]: expected string, got number".to_string())
+    );
+
+    let data_type = DataType::JsonSchema(Box::new(schema));
+    assert!(Data::Json(json!({ "name": "Ada" })).validate_type(&data_type));
+    assert!(!Data::Json(json!({ "name": 1 })).validate_type(&data_type));
+    assert!(!Data::Text("Ada".to_string()).validate_type(&data_type));
+}
+
+#[test]
+fn test_schemas_are_structural_subtypes() {
+    let user = DataType::JsonSchema(Box::new(user_schema()));
+    let admin = DataType::JsonSchema(Box::new(JsonSchema::Object(
+        ObjectSchema::new()
+            .with_required_property("name", JsonSchema::String)
+            .with_required_property("tags", JsonSchema::array(JsonSchema::String))
+            .with_required_property("level", JsonSchema::Integer),
+    )));
+    let named = DataType::JsonSchema(Box::new(JsonSchema::Object(
+        ObjectSchema::new().with_required_property("name", JsonSchema::Any),
+    )));
+
+    assert!(admin.is_compatible_with(&user), "More required properties is a subtype");
+    assert!(!user.is_compatible_with(&admin));
+    assert!(user.is_compatible_with(&named));
+    assert!(user.is_compatible_with(&DataType::Json));
+    assert!(!DataType::Json.is_compatible_with(&user));
+    assert!(DataType::Json.can_flow_into(&user), "Opaque JSON is checked at runtime");
+    assert!(DataType::JsonSchema(Box::new(JsonSchema::Integer))
+        .is_compatible_with(&DataType::JsonSchema(Box::new(JsonSchema::Number))));
+
+    // Undeclared properties may hold anything
+    let untyped_tags = DataType::JsonSchema(Box::new(JsonSchema::Object(
+        ObjectSchema::new().with_required_property("name", JsonSchema::String),
+    )));
+    assert!(!untyped_tags.is_compatible_with(&user));
+}
+
+fn transformer_config(expression: &str, output: &Value, output_schema: Option<Value>) -> Value {
+    let mut config = json!({
+        "transformation_expression": expression,
+        "validation_data": {
+            "input": { "user": { "name": "Ada" } },
+            "expected_output": output
+        }
+    });
+    if let Some(output_schema) = output_schema {
+        config["output_schema"] = output_schema;
+    }
+    config
+}
+
+#[test]
+fn test_declared_schemas_are_checked_when_building() {
+    let output = json!({ "name": "Ada" });
+    let schema = json!({ "type": "object", "required": ["name"] });
+
+    let Err(err) = build_dag(
+        &transformer_config(".user", &output, Some(schema.clone())),
+        &["name", "age"],
+        DAGSettings::cache_off(),
+    ) else {
+        panic!("Missing property should be rejected");
+    };
+    assert!(err.contains("transform -> wildcard"), "Unexpected error: {err}");
+
+    assert!(build_dag(
+        &transformer_config(".user", &output, Some(schema)),
+        &["name"],
+        DAGSettings::cache_off()
+    )
+    .is_ok());
+}
+
+#[tokio::test]
+async fn test_opaque_json_is_checked_against_schemas_at_runtime() {
+    let output = json!({ "name": "Ada" });
+    let Err(err) = build_dag(
+        &transformer_config(".user", &output, None),
+        &["name"],
+        DAGSettings::cache_off(),
+    ) else {
+        panic!("Opaque JSON only flows into a schema with optimistic type checks");
+    };
+    assert!(err.contains("transform -> wildcard"), "Unexpected error: {err}");
+
+    let dag = build_dag(&transformer_config(".user", &output, None), &["name", "age"], optimistic())
+        .expect("Opaque JSON may flow into a schema");
+
+    let err = dag.execute(None).await.unwrap_err();
+    assert!(err.to_string().contains("Type mismatch"), "Unexpected error: {err}");
+
+    let dag = build_dag(&transformer_config(".user", &output, None), &["name"], optimistic())
+        .expect("Valid DAG");
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.get("wildcard"), Some(&Data::Json(output)));
+}
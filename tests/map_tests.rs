@@ -0,0 +1,362 @@
+use baselard::component::{Component, Data, DataType, Error, Registry};
+use baselard::components::adder::Adder;
+use baselard::components::crash_test_dummy::CrashTestDummy;
+use baselard::components::string_length_counter::StringLengthCounter;
+use baselard::dag::{DAGError, DAGSettings, NodeExecutionContext, DAG};
+use baselard::dagir::{DAGConfig, DAGIR};
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Splits its text input into words
+struct Splitter;
+
+impl Component for Splitter {
+    fn configure(_: serde_json::Value) -> Result<Self, Error> {
+        Ok(Self)
+    }
+
+    fn execute(&self, _context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
+        let words = input
+            .as_text()
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(|word| Data::Text(word.to_string()))
+            .collect();
+        Ok(Data::List(words))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Text
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::List(Box::new(DataType::Text))
+    }
+}
+
+/// How many `Napper` naps ran to the end without being cancelled
+static FINISHED_NAPS: AtomicUsize = AtomicUsize::new(0);
+
+/// Naps for 300ms, unless cancelled first
+struct Napper;
+
+impl Component for Napper {
+    fn configure(_: serde_json::Value) -> Result<Self, Error> {
+        Ok(Self)
+    }
+
+    fn execute(&self, context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_millis(300) {
+            if context.is_cancelled() {
+                return Err(DAGError::Cancelled {
+                    node_id: context.node_id,
+                });
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        FINISHED_NAPS.fetch_add(1, Ordering::SeqCst);
+        Ok(input)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+}
+
+/// How many `Tracker`s are executing, and the most that ever were at once
+static TRACKERS_RUNNING: AtomicUsize = AtomicUsize::new(0);
+static TRACKERS_MAX_RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+/// Executes for 50ms, tracking how many of its kind execute at once
+struct Tracker;
+
+impl Component for Tracker {
+    fn configure(_: serde_json::Value) -> Result<Self, Error> {
+        Ok(Self)
+    }
+
+    fn execute(&self, _context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
+        let running = TRACKERS_RUNNING.fetch_add(1, Ordering::SeqCst) + 1;
+        TRACKERS_MAX_RUNNING.fetch_max(running, Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(50));
+        TRACKERS_RUNNING.fetch_sub(1, Ordering::SeqCst);
+        Ok(input)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+}
+
+fn setup_test_registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<Adder>("Adder");
+    registry.register::<CrashTestDummy>("CrashTestDummy");
+    registry.register::<Napper>("Napper");
+    registry.register::<Splitter>("Splitter");
+    registry.register::<Tracker>("Tracker");
+    registry.register::<StringLengthCounter>("StringLengthCounter");
+
+    let add_eleven: DAGConfig = serde_json::from_value(json!({
+        "alias": "add_eleven",
+        "nodes": [
+            { "id": "plus_one", "component_type": "Adder", "config": { "value": 1 } },
+            { "id": "plus_ten", "component_type": "Adder", "config": { "value": 10 }, "depends_on": ["plus_one"] }
+        ]
+    }))
+    .unwrap();
+    registry.register_dag(add_eleven);
+    registry
+}
+
+fn build_dag(json_config: &serde_json::Value) -> Result<DAG, String> {
+    let registry = setup_test_registry();
+    DAGIR::from_json(json_config)
+        .and_then(|ir| DAG::from_ir(&ir, &registry, DAGSettings::cache_off(), None))
+}
+
+#[tokio::test]
+async fn test_map_runs_aliased_sub_dag_per_element() {
+    let dag = build_dag(&json!({
+        "alias": "map_alias_test",
+        "nodes": [
+            {
+                "id": "each",
+                "component_type": "Map",
+                "config": { "dag": "add_eleven" },
+                "inputs": [1, 2, 3]
+            },
+            { "id": "total", "component_type": "Adder", "config": { "value": 0 }, "depends_on": ["each"] }
+        ]
+    }))
+    .expect("Valid DAG");
+
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(
+        results.get("each"),
+        Some(&Data::List(vec![
+            Data::Integer(12),
+            Data::Integer(13),
+            Data::Integer(14)
+        ]))
+    );
+    assert_eq!(results.get("total"), Some(&Data::Integer(39)));
+}
+
+#[tokio::test]
+async fn test_map_runs_inline_sub_dag_per_element() {
+    let dag = build_dag(&json!({
+        "alias": "map_inline_test",
+        "nodes": [
+            { "id": "words", "component_type": "Splitter", "config": {}, "inputs": "map every word" },
+            {
+                "id": "lengths",
+                "component_type": "Map",
+                "config": {
+                    "dag": {
+                        "alias": "word_length",
+                        "nodes": [
+                            { "id": "length", "component_type": "StringLengthCounter", "config": {} }
+                        ]
+                    }
+                },
+                "depends_on": ["words"]
+            }
+        ]
+    }))
+    .expect("Valid DAG");
+
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(
+        results.get("lengths"),
+        Some(&Data::List(vec![
+            Data::Integer(3),
+            Data::Integer(5),
+            Data::Integer(4)
+        ]))
+    );
+}
+
+#[test]
+fn test_map_element_type_checked_at_build_time() {
+    let err = build_dag(&json!({
+        "alias": "map_type_test",
+        "nodes": [
+            { "id": "words", "component_type": "Splitter", "config": {}, "inputs": "not numbers" },
+            { "id": "each", "component_type": "Map", "config": { "dag": "add_eleven" }, "depends_on": ["words"] }
+        ]
+    }))
+    .unwrap_err();
+
+    assert!(
+        err.contains("words -> each: output List(Text) is incompatible with input List("),
+        "Unexpected error: {err}"
+    );
+}
+
+#[tokio::test]
+async fn test_map_concurrency_is_bounded() {
+    let dag = build_dag(&json!({
+        "alias": "map_concurrency_test",
+        "nodes": [
+            {
+                "id": "each",
+                "component_type": "Map",
+                "config": {
+                    "dag": {
+                        "alias": "nap",
+                        "nodes": [
+                            { "id": "sleep", "component_type": "CrashTestDummy", "config": { "sleep_duration_ms": 30 } }
+                        ]
+                    },
+                    "max_concurrency": 1
+                },
+                "inputs": ["a", "b", "c"]
+            }
+        ]
+    }))
+    .expect("Valid DAG");
+
+    let start = Instant::now();
+    let results = dag.execute(None).await.expect("Execution success");
+    assert!(
+        start.elapsed() >= Duration::from_millis(90),
+        "Elements should run one at a time, took {:?}",
+        start.elapsed()
+    );
+    assert_eq!(results.get("each").and_then(Data::as_list).map(<[Data]>::len), Some(3));
+}
+
+#[tokio::test]
+async fn test_map_elements_count_towards_max_concurrent_nodes() {
+    let registry = setup_test_registry();
+    let dag = DAGIR::from_json(&json!({
+        "alias": "map_node_limit_test",
+        "nodes": [
+            {
+                "id": "each",
+                "component_type": "Map",
+                "config": {
+                    "dag": {
+                        "alias": "track",
+                        "nodes": [{ "id": "track", "component_type": "Tracker", "config": {} }]
+                    },
+                    "max_concurrency": 4
+                },
+                "inputs": ["a", "b", "c", "d", "e", "f"]
+            },
+            { "id": "sibling", "component_type": "Tracker", "config": {}, "inputs": "g" }
+        ]
+    }))
+    .and_then(|ir| {
+        let settings = DAGSettings {
+            per_node_timeout_ms: Some(2000),
+            max_concurrent_nodes: Some(2),
+            ..DAGSettings::cache_off()
+        };
+        DAG::from_ir(&ir, &registry, settings, None)
+    })
+    .expect("Valid DAG");
+
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.get("each").and_then(Data::as_list).map(<[Data]>::len), Some(6));
+    assert_eq!(TRACKERS_MAX_RUNNING.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_map_timeout_cancels_element_executions() {
+    let dag = build_dag(&json!({
+        "alias": "map_timeout_test",
+        "nodes": [
+            {
+                "id": "each",
+                "component_type": "Map",
+                "config": {
+                    "dag": {
+                        "alias": "naps",
+                        "nodes": [
+                            { "id": "nap", "component_type": "Napper", "config": {}, "timeout_ms": 1000 }
+                        ]
+                    },
+                    "max_concurrency": 2
+                },
+                "inputs": ["a", "b", "c", "d"],
+                "timeout_ms": 100
+            }
+        ]
+    }))
+    .expect("Valid DAG");
+
+    let result = dag.execute(None).await;
+    assert!(
+        matches!(&result, Err(DAGError::NodeTimeout { node_id, .. }) if node_id == "each"),
+        "Unexpected result: {result:?}"
+    );
+
+    // Long enough for every element to have napped, had they kept going
+    tokio::time::sleep(Duration::from_millis(800)).await;
+    assert_eq!(FINISHED_NAPS.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn test_map_element_failure_fails_node() {
+    let dag = build_dag(&json!({
+        "alias": "map_failure_test",
+        "nodes": [
+            {
+                "id": "each",
+                "component_type": "Map",
+                "config": {
+                    "dag": {
+                        "alias": "crash",
+                        "nodes": [
+                            { "id": "crash", "component_type": "CrashTestDummy", "config": { "fail": true } }
+                        ]
+                    }
+                },
+                "inputs": ["a", "b"]
+            }
+        ]
+    }))
+    .expect("Valid DAG");
+
+    let result = dag.execute(None).await;
+    assert!(
+        matches!(
+            &result,
+            Err(DAGError::ExecutionError { node_id, reason }) if node_id == "each" && reason.starts_with("Element 0")
+        ),
+        "Unexpected result: {result:?}"
+    );
+}
+
+#[test]
+fn test_map_rejects_unknown_alias_and_self_reference() {
+    let err = build_dag(&json!({
+        "alias": "map_unknown_test",
+        "nodes": [
+            { "id": "each", "component_type": "Map", "config": { "dag": "missing" }, "inputs": [1] }
+        ]
+    }))
+    .unwrap_err();
+    assert!(err.contains("no DAG registered with alias missing"), "Unexpected error: {err}");
+
+    let err = build_dag(&json!({
+        "alias": "add_eleven",
+        "nodes": [
+            { "id": "each", "component_type": "Map", "config": { "dag": "add_eleven" }, "inputs": [1] }
+        ]
+    }))
+    .unwrap_err();
+    assert!(err.contains("DAG add_eleven would contain itself"), "Unexpected error: {err}");
+}
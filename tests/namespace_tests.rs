@@ -0,0 +1,142 @@
+use ascii_tree::write_tree;
+use baselard::component::{Data, Registry};
+use baselard::components::adder::Adder;
+use baselard::dag::{DAGSettings, DAG};
+use baselard::dag_visualizer::TreeView;
+use baselard::dagir::{DAGConfig, NodeConfig, DAGIR};
+use serde_json::json;
+
+fn setup_test_registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<Adder>("Adder");
+    registry
+}
+
+fn build_dag(config: DAGConfig) -> Result<DAG, String> {
+    let registry = setup_test_registry();
+    DAGIR::from_config(config)
+        .and_then(|ir| DAG::from_ir(&ir, &registry, DAGSettings::cache_off(), None))
+}
+
+fn adder(id: &str, value: i32) -> NodeConfig {
+    NodeConfig::new(id, "Adder").with_config(json!({ "value": value }))
+}
+
+/// Adds one, then ten, to its input
+fn pipeline(alias: &str) -> DAGConfig {
+    DAGConfig::new(alias).with_nodes(vec![
+        adder("first", 1).with_inputs(json!(1)),
+        adder("second", 10).with_dependencies(vec!["first"]),
+    ])
+}
+
+#[tokio::test]
+async fn test_namespaced_nodes_have_qualified_ids() {
+    let config = DAGConfig::new("namespaces").with_nodes(vec![
+        adder("first", 1).with_inputs(json!(1)),
+        adder("first", 2).with_namespace("left").with_dependencies(vec!["first"]),
+        // Resolves to its sibling left::first
+        adder("second", 0).with_namespace("left").with_dependencies(vec!["first"]),
+        // Refers explicitly to the node outside of any namespace
+        adder("first", 3).with_namespace("right").with_dependencies(vec!["::first"]),
+        adder("second", 0)
+            .with_namespace("right::inner")
+            .with_dependencies(vec!["first"]),
+    ]);
+
+    let report = build_dag(config)
+        .expect("Valid DAG")
+        .execute_with_report(None)
+        .await
+        .expect("Execution success");
+
+    assert_eq!(report.results.get("first"), Some(&Data::Integer(2)));
+    assert_eq!(report.results.get("left::first"), Some(&Data::Integer(4)));
+    assert_eq!(report.results.get("left::second"), Some(&Data::Integer(4)));
+    assert_eq!(report.results.get("right::first"), Some(&Data::Integer(5)));
+    assert_eq!(report.results.get("right::inner::second"), Some(&Data::Integer(5)));
+
+    let right = report.results_in_namespace("right");
+    assert_eq!(
+        right.keys().collect::<Vec<_>>(),
+        vec!["right::first", "right::inner::second"]
+    );
+    assert!(report.results_in_namespace("rig").is_empty());
+}
+
+#[test]
+fn test_duplicate_qualified_ids_are_rejected() {
+    let config = DAGConfig::new("duplicates").with_nodes(vec![
+        adder("node", 1).with_namespace("ns").with_inputs(json!(1)),
+        adder("ns::node", 1).with_inputs(json!(1)),
+    ]);
+
+    let err = build_dag(config).unwrap_err();
+    assert_eq!(err, "Duplicate node ID found: ns::node");
+}
+
+#[tokio::test]
+async fn test_merge_imports_dag_under_namespace() {
+    let base = pipeline("base");
+    let merged = base
+        .merge_under_namespace(&pipeline("imported"), "imported")
+        .expect("IDs don't collide");
+    let ids: Vec<_> = merged.nodes.iter().map(NodeConfig::qualified_id).collect();
+    assert_eq!(
+        ids,
+        vec!["first", "imported::first", "imported::second", "second"]
+    );
+
+    // Later overrides can connect the base DAG to the imported nodes
+    let connected = merged
+        .merge(
+            &DAGConfig::new("base")
+                .with_node(adder("total", 0).with_dependencies(vec!["second", "imported::second"])),
+        )
+        .expect("Valid merge");
+
+    let results = build_dag(connected)
+        .expect("Valid DAG")
+        .execute(None)
+        .await
+        .expect("Execution success");
+    assert_eq!(results.get("second"), Some(&Data::Integer(12)));
+    assert_eq!(results.get("imported::second"), Some(&Data::Integer(12)));
+    assert_eq!(results.get("total"), Some(&Data::Integer(24)));
+}
+
+#[test]
+fn test_merge_overrides_match_qualified_ids() {
+    let merged = pipeline("base")
+        .merge_under_namespace(&pipeline("imported"), "imported")
+        .unwrap()
+        .merge(
+            &DAGConfig::new("base")
+                .with_node(adder("first", 100).with_namespace("imported")),
+        )
+        .unwrap();
+
+    let config_of = |id: &str| {
+        merged
+            .nodes
+            .iter()
+            .find(|node| node.qualified_id() == id)
+            .map(|node| node.config.clone())
+    };
+    assert_eq!(config_of("first"), Some(json!({ "value": 1 })));
+    assert_eq!(config_of("imported::first"), Some(json!({ "value": 100 })));
+}
+
+#[test]
+fn test_namespace_tree_only_shows_namespace() {
+    let merged = pipeline("base")
+        .merge_under_namespace(&pipeline("imported"), "imported")
+        .unwrap();
+    let ir = DAGIR::from_config(merged).unwrap();
+
+    let mut tree = String::new();
+    write_tree(&mut tree, &ir.build_namespace_tree(TreeView::Execution, "imported")).unwrap();
+    assert!(tree.contains("imported::first"), "Unexpected tree:\n{tree}");
+    assert!(tree.contains("imported::second"), "Unexpected tree:\n{tree}");
+    assert_eq!(tree.matches("first").count(), 1, "Unexpected tree:\n{tree}");
+}
@@ -0,0 +1,186 @@
+use baselard::cache::Cache;
+use baselard::component::{Component, Data, DataType, Error, Registry};
+use baselard::components::adder::Adder;
+use baselard::dag::{DAGError, DAGSettings, NodeExecutionContext, DAG};
+use baselard::dagir::{DAGConfig, NodeConfig, DAGIR};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+fn executions() -> &'static Mutex<HashMap<String, usize>> {
+    static EXECUTIONS: OnceLock<Mutex<HashMap<String, usize>>> = OnceLock::new();
+    EXECUTIONS.get_or_init(Mutex::default)
+}
+
+fn execution_count(test: &str) -> usize {
+    executions().lock().unwrap().get(test).copied().unwrap_or(0)
+}
+
+/// Counts its executions per test, doubling its input
+struct Counter {
+    test: String,
+}
+
+impl Component for Counter {
+    fn configure(config: Value) -> Result<Self, Error> {
+        Ok(Self {
+            test: config["test"].as_str().unwrap_or_default().to_string(),
+        })
+    }
+
+    fn execute(&self, _context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
+        *executions().lock().unwrap().entry(self.test.clone()).or_default() += 1;
+        Ok(Data::Integer(input.as_integer().unwrap_or_default() * 2))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Integer
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Integer
+    }
+}
+
+fn settings() -> DAGSettings {
+    DAGSettings {
+        enable_memory_cache: true,
+        ..DAGSettings::cache_off()
+    }
+}
+
+/// A cacheable `Counter` feeding a leaf `Adder` adding `leaf_value`
+fn pipeline(test: &str, input: i32, leaf_value: i32, ttl_ms: Option<u64>) -> DAGConfig {
+    DAGConfig::new(test).with_nodes(vec![
+        NodeConfig::new("counter", "Counter")
+            .with_config(json!({ "test": test }))
+            .with_inputs(json!(input))
+            .with_cache(ttl_ms),
+        NodeConfig::new("leaf", "Adder")
+            .with_config(json!({ "value": leaf_value }))
+            .with_dependencies(vec!["counter"]),
+    ])
+}
+
+async fn execute(config: DAGConfig, settings: DAGSettings, cache: &Arc<Cache>) -> Option<Data> {
+    let mut registry = Registry::new();
+    registry.register::<Adder>("Adder");
+    registry.register::<Counter>("Counter");
+    let ir = DAGIR::from_config(config).expect("Valid IR");
+    let dag = DAG::from_ir(&ir, &registry, settings, Some(Arc::clone(cache))).expect("Valid DAG");
+    let mut results = dag.execute(None).await.expect("Execution success");
+    results.shift_remove("leaf")
+}
+
+#[tokio::test]
+async fn test_cacheable_node_survives_downstream_config_change() {
+    let test = "downstream_change";
+    let cache = Arc::new(Cache::new(None::<String>, 100));
+
+    let leaf = execute(pipeline(test, 5, 1, None), settings(), &cache).await;
+    assert_eq!(leaf, Some(Data::Integer(11)));
+    assert_eq!(execution_count(test), 1);
+
+    // Another DAG, so no whole-run cache hit, but the same counter node
+    let leaf = execute(pipeline(test, 5, 2, None), settings(), &cache).await;
+    assert_eq!(leaf, Some(Data::Integer(12)));
+    assert_eq!(execution_count(test), 1, "The counter's output should be reused");
+
+    // Other input, other output
+    let leaf = execute(pipeline(test, 6, 2, None), settings(), &cache).await;
+    assert_eq!(leaf, Some(Data::Integer(14)));
+    assert_eq!(execution_count(test), 2);
+}
+
+#[tokio::test]
+async fn test_memoized_outputs_expire_after_ttl() {
+    let test = "ttl";
+    let cache = Arc::new(Cache::new(None::<String>, 100));
+
+    execute(pipeline(test, 5, 1, Some(50)), settings(), &cache).await;
+    execute(pipeline(test, 5, 2, Some(50)), settings(), &cache).await;
+    assert_eq!(execution_count(test), 1);
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    execute(pipeline(test, 5, 3, Some(50)), settings(), &cache).await;
+    assert_eq!(execution_count(test), 2, "The memoized output should have expired");
+}
+
+#[tokio::test]
+async fn test_nodes_are_not_memoized_without_memory_cache() {
+    let test = "memory_cache_off";
+    let cache = Arc::new(Cache::new(None::<String>, 100));
+
+    execute(pipeline(test, 5, 1, None), DAGSettings::cache_off(), &cache).await;
+    execute(pipeline(test, 5, 2, None), DAGSettings::cache_off(), &cache).await;
+    assert_eq!(execution_count(test), 2);
+}
+
+#[test]
+fn test_cache_ttl_requires_cacheable() {
+    let err = DAGIR::from_json(&json!({
+        "alias": "ttl_only",
+        "nodes": [
+            { "id": "node", "component_type": "Adder", "config": { "value": 1 }, "inputs": 1, "cache_ttl_ms": 100 }
+        ]
+    }))
+    .unwrap_err();
+    assert_eq!(err, "Node node sets cache_ttl_ms, but isn't cacheable");
+}
+
+/// Passes its input on, counting its executions per test
+struct Echo {
+    test: String,
+}
+
+impl Component for Echo {
+    fn configure(config: Value) -> Result<Self, Error> {
+        Ok(Self {
+            test: config["test"].as_str().unwrap_or_default().to_string(),
+        })
+    }
+
+    fn execute(&self, _context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
+        *executions().lock().unwrap().entry(self.test.clone()).or_default() += 1;
+        Ok(input)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+}
+
+#[tokio::test]
+async fn test_nested_lists_are_memoized_apart() {
+    let test = "nested_lists";
+    let cache = Arc::new(Cache::new(None::<String>, 100));
+    let mut registry = Registry::new();
+    registry.register::<Echo>("Echo");
+
+    let echo = |input: Value| async {
+        let config = DAGConfig::new(test).with_nodes(vec![NodeConfig::new("echo", "Echo")
+            .with_config(json!({ "test": test }))
+            .with_inputs(input)
+            .with_cache(None)]);
+        let ir = DAGIR::from_config(config).expect("Valid IR");
+        let dag = DAG::from_ir(&ir, &registry, settings(), Some(Arc::clone(&cache)))
+            .expect("Valid DAG");
+        dag.execute(None).await.expect("Execution success").shift_remove("echo")
+    };
+
+    let list = |values: Vec<Data>| Data::List(values);
+    assert_eq!(
+        echo(json!([[1], 2])).await,
+        Some(list(vec![list(vec![Data::Integer(1)]), Data::Integer(2)]))
+    );
+    assert_eq!(
+        echo(json!([[1, 2]])).await,
+        Some(list(vec![list(vec![Data::Integer(1), Data::Integer(2)])]))
+    );
+    assert_eq!(execution_count(test), 2);
+}
@@ -0,0 +1,162 @@
+use baselard::cache::Cache;
+use baselard::component::{Data, Registry};
+use baselard::components::adder::Adder;
+use baselard::dag::{DAGSettings, NodeStatus, DAG};
+use baselard::dagir::{DAGConfig, NodeConfig, Output, DAGIR};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+fn setup_test_registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<Adder>("Adder");
+    registry
+}
+
+fn adder(id: &str, value: i32) -> NodeConfig {
+    NodeConfig::new(id, "Adder").with_config(json!({ "value": value }))
+}
+
+/// `source` fans out to a short and a long branch, the latter consuming it
+/// again further down, joined by `total`
+fn pipeline() -> DAGConfig {
+    DAGConfig::new("outputs").with_nodes(vec![
+        adder("source", 1).with_inputs(json!(1)),
+        adder("short", 10).with_dependencies(vec!["source"]),
+        adder("long", 5).with_dependencies(vec!["source"]),
+        adder("long_2", 100).with_dependencies(vec!["source", "long"]),
+        adder("total", 0).with_dependencies(vec!["short", "long_2"]),
+    ])
+}
+
+fn build_dag(config: DAGConfig, settings: DAGSettings, cache: Option<Arc<Cache>>) -> DAG {
+    let ir = DAGIR::from_config(config).expect("Valid IR");
+    DAG::from_ir(&ir, &setup_test_registry(), settings, cache).expect("Valid DAG")
+}
+
+fn settings() -> DAGSettings {
+    DAGSettings {
+        per_node_timeout_ms: None,
+        ..DAGSettings::cache_off()
+    }
+}
+
+#[tokio::test]
+async fn test_execute_returns_declared_outputs() {
+    let config = pipeline()
+        .with_output(Output::renamed("total", "answer"))
+        .with_output("short");
+    let dag = build_dag(config, settings(), None);
+
+    let report = dag.execute_with_report(None).await.expect("Execution success");
+    // Intermediates were freed along the way, but every node's output was
+    // there for its dependents
+    assert_eq!(
+        report.results.into_iter().collect::<Vec<_>>(),
+        vec![
+            ("answer".to_string(), Data::Integer(121)),
+            ("short".to_string(), Data::Integer(12)),
+        ]
+    );
+    assert_eq!(report.statuses.len(), 5);
+    assert!(report.statuses.values().all(NodeStatus::is_succeeded));
+}
+
+#[tokio::test]
+async fn test_debug_all_nodes_returns_every_output() {
+    let config = pipeline().with_output(Output::renamed("total", "answer"));
+    let settings = DAGSettings {
+        debug_all_nodes: true,
+        ..settings()
+    };
+    let dag = build_dag(config, settings, None);
+
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.len(), 5);
+    assert_eq!(results.get("source"), Some(&Data::Integer(2)));
+    assert_eq!(results.get("total"), Some(&Data::Integer(121)));
+    assert!(!results.contains_key("answer"));
+}
+
+#[tokio::test]
+async fn test_cache_holds_declared_outputs_only() {
+    let cache = Arc::new(Cache::new(None::<String>, 100));
+    let settings = DAGSettings {
+        enable_memory_cache: true,
+        ..settings()
+    };
+    let dag = build_dag(pipeline().with_output("total"), settings, Some(cache));
+
+    let first = dag.execute_with_report(None).await.expect("Execution success");
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+    let cached = dag.get_cached_result().expect("Cached results exist");
+    assert_eq!(cached.node_results, first.results);
+
+    let repeated = dag.execute_with_report(None).await.expect("Execution success");
+    assert!(repeated.attempts.is_empty(), "Should hit the cache");
+    assert_eq!(repeated.results, first.results);
+    assert_eq!(repeated.statuses.keys().collect::<Vec<_>>(), vec!["total"]);
+}
+
+#[tokio::test]
+async fn test_sub_dag_outputs_its_declared_output() {
+    let config = DAGConfig::new("outer").with_node(
+        NodeConfig::new("inner", "SubDag").with_config(json!({
+            "dag": {
+                "alias": "inner",
+                "nodes": [
+                    { "id": "first", "component_type": "Adder", "config": { "value": 1 }, "inputs": 1 },
+                    { "id": "second", "component_type": "Adder", "config": { "value": 2 }, "depends_on": ["first"] },
+                    { "id": "other_leaf", "component_type": "Adder", "config": { "value": 3 }, "depends_on": ["first"] }
+                ],
+                "outputs": [{ "node": "second", "as": "result" }]
+            }
+        })),
+    );
+    let dag = build_dag(config, settings(), None);
+
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.get("inner"), Some(&Data::Integer(4)));
+}
+
+fn build_error(outputs: &Value) -> String {
+    let config = json!({
+        "alias": "invalid_outputs",
+        "nodes": [
+            { "id": "first", "component_type": "Adder", "config": { "value": 1 }, "inputs": 1 },
+            { "id": "second", "component_type": "Adder", "config": { "value": 2 }, "inputs": 1 }
+        ],
+        "outputs": outputs
+    });
+    DAGIR::from_json(&config).unwrap_err()
+}
+
+#[test]
+fn test_invalid_outputs() {
+    assert_eq!(
+        build_error(&json!(["missing"])),
+        "Output node missing is not in the DAG"
+    );
+    assert_eq!(
+        build_error(&json!(["first", { "node": "second", "as": "first" }])),
+        "More than one output is named first"
+    );
+}
+
+#[tokio::test]
+async fn test_outputs_move_into_namespace() {
+    let imported = DAGConfig::new("imported")
+        .with_node(adder("node", 1).with_inputs(json!(1)))
+        .with_output("node");
+    let merged = DAGConfig::new("base")
+        .with_node(adder("node", 2).with_dependencies(vec!["ns::node"]))
+        .merge_under_namespace(&imported, "ns")
+        .expect("Valid merge");
+    let dag = build_dag(merged, settings(), None);
+
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(
+        results.into_iter().collect::<Vec<_>>(),
+        vec![("ns::node".to_string(), Data::Integer(2))]
+    );
+}
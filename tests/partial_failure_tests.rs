@@ -0,0 +1,117 @@
+use baselard::component::{Data, Registry};
+use baselard::components::adder::Adder;
+use baselard::components::crash_test_dummy::CrashTestDummy;
+use baselard::dag::{DAGError, DAGSettings, NodeStatus, DAG};
+use baselard::dagir::DAGIR;
+use serde_json::json;
+
+fn setup_test_registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<Adder>("Adder");
+    registry.register::<CrashTestDummy>("CrashTestDummy");
+    registry
+}
+
+fn continue_on_error() -> DAGSettings {
+    DAGSettings {
+        continue_on_error: true,
+        ..DAGSettings::cache_off()
+    }
+}
+
+fn two_branch_config() -> serde_json::Value {
+    json!({
+        "alias": "partial_failure_test",
+        "nodes": [
+            {
+                "id": "healthy_1",
+                "component_type": "Adder",
+                "config": { "value": 1 },
+                "inputs": 1
+            },
+            {
+                "id": "healthy_2",
+                "component_type": "Adder",
+                "config": { "value": 2 },
+                "depends_on": ["healthy_1"]
+            },
+            {
+                "id": "failing",
+                "component_type": "CrashTestDummy",
+                "config": { "fail": true },
+                "inputs": "input"
+            },
+            {
+                "id": "after_failing",
+                "component_type": "CrashTestDummy",
+                "config": {},
+                "depends_on": ["failing"]
+            },
+            {
+                "id": "after_skipped",
+                "component_type": "CrashTestDummy",
+                "config": {},
+                "depends_on": ["after_failing"]
+            },
+            {
+                "id": "slow",
+                "component_type": "CrashTestDummy",
+                "config": { "sleep_duration_ms": 200 },
+                "timeout_ms": 20
+            }
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_healthy_branches_complete_when_continuing_on_error() {
+    let registry = setup_test_registry();
+    let dag = DAGIR::from_json(&two_branch_config())
+        .and_then(|ir| DAG::from_ir(&ir, &registry, continue_on_error(), None))
+        .expect("Valid DAG");
+
+    let report = dag.execute_with_report(None).await.expect("Should not abort");
+    assert!(!report.is_complete());
+
+    assert_eq!(report.results.get("healthy_1"), Some(&Data::Integer(2)));
+    assert_eq!(report.results.get("healthy_2"), Some(&Data::Integer(4)));
+    for node_id in ["failing", "after_failing", "after_skipped", "slow"] {
+        assert!(
+            !report.results.contains_key(node_id),
+            "{node_id} should have no output"
+        );
+    }
+
+    assert!(report.statuses["healthy_1"].is_succeeded());
+    assert!(report.statuses["healthy_2"].is_succeeded());
+    assert!(matches!(
+        &report.statuses["failing"],
+        NodeStatus::Failed(DAGError::ExecutionError { .. })
+    ));
+    assert!(matches!(
+        &report.statuses["after_failing"],
+        NodeStatus::Skipped { failed_dependency } if failed_dependency == "failing"
+    ));
+    assert!(matches!(
+        &report.statuses["after_skipped"],
+        NodeStatus::Skipped { failed_dependency } if failed_dependency == "after_failing"
+    ));
+    assert!(matches!(
+        &report.statuses["slow"],
+        NodeStatus::TimedOut { timeout_ms: 20 }
+    ));
+}
+
+#[tokio::test]
+async fn test_failure_aborts_without_continue_on_error() {
+    let registry = setup_test_registry();
+    let dag = DAGIR::from_json(&two_branch_config())
+        .and_then(|ir| DAG::from_ir(&ir, &registry, DAGSettings::cache_off(), None))
+        .expect("Valid DAG");
+
+    let result = dag.execute(None).await;
+    assert!(
+        matches!(&result, Err(DAGError::ExecutionError { node_id, .. }) if node_id == "failing"),
+        "Execution should abort on the failing node, got {result:?}"
+    );
+}
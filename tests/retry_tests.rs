@@ -0,0 +1,281 @@
+use baselard::component::Registry;
+use baselard::component::{Component, Data, DataType, Error};
+use baselard::dag::{Backoff, DAGError, DAGSettings, NodeExecutionContext, RetryOn, RetryPolicy, DAG};
+use baselard::dagir::DAGIR;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+#[derive(Deserialize)]
+struct FlakyConfig {
+    /// Number of attempts that fail before one succeeds
+    failing_attempts: u32,
+    /// Fail with an `ExecutionError` instead of a `TransientError`
+    #[serde(default)]
+    permanent: bool,
+}
+
+/// Fails its first few attempts, then returns the attempt it succeeded on
+struct Flaky {
+    config: FlakyConfig,
+}
+
+impl Component for Flaky {
+    fn configure(config: serde_json::Value) -> Result<Self, Error> {
+        let config = serde_json::from_value(config)
+            .map_err(|e| Error::ConfigurationError(e.to_string()))?;
+        Ok(Self { config })
+    }
+
+    fn execute(&self, context: NodeExecutionContext, _input: Data) -> Result<Data, DAGError> {
+        if context.attempt > self.config.failing_attempts {
+            return Ok(Data::Integer(i32::try_from(context.attempt).unwrap()));
+        }
+
+        let reason = format!("Attempt {} failed", context.attempt);
+        if self.config.permanent {
+            Err(DAGError::ExecutionError {
+                node_id: context.node_id,
+                reason,
+            })
+        } else {
+            Err(DAGError::TransientError {
+                node_id: context.node_id,
+                reason,
+            })
+        }
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Null
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Integer
+    }
+}
+
+/// How many `HangsOnce` executions are running, and the most at once
+static HANGING_RUNNING: AtomicUsize = AtomicUsize::new(0);
+static HANGING_MAX_RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+/// Hangs on its first attempt until cancelled, then succeeds right away
+struct HangsOnce;
+
+impl Component for HangsOnce {
+    fn configure(_: serde_json::Value) -> Result<Self, Error> {
+        Ok(Self)
+    }
+
+    fn execute(&self, context: NodeExecutionContext, _input: Data) -> Result<Data, DAGError> {
+        let running = HANGING_RUNNING.fetch_add(1, Ordering::SeqCst) + 1;
+        HANGING_MAX_RUNNING.fetch_max(running, Ordering::SeqCst);
+
+        let start = Instant::now();
+        while context.attempt == 1 && start.elapsed() < Duration::from_secs(5) {
+            if context.is_cancelled() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        HANGING_RUNNING.fetch_sub(1, Ordering::SeqCst);
+        Ok(Data::Integer(i32::try_from(context.attempt).unwrap()))
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Null
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Integer
+    }
+}
+
+fn setup_test_registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<Flaky>("Flaky");
+    registry.register::<HangsOnce>("HangsOnce");
+    registry
+}
+
+fn settings_with_retries(max_attempts: u32) -> DAGSettings {
+    DAGSettings {
+        retry_policy: Some(RetryPolicy::new(max_attempts, Backoff::Fixed { delay_ms: 1 })),
+        ..DAGSettings::cache_off()
+    }
+}
+
+fn build_dag(json_config: &serde_json::Value, settings: DAGSettings) -> DAG {
+    let registry = setup_test_registry();
+    DAGIR::from_json(json_config)
+        .and_then(|ir| DAG::from_ir(&ir, &registry, settings, None))
+        .expect("Valid DAG")
+}
+
+#[tokio::test]
+async fn test_transient_failures_are_retried_until_success() {
+    let dag = build_dag(
+        &json!({
+            "alias": "retry_success",
+            "nodes": [
+                {
+                    "id": "flaky",
+                    "component_type": "Flaky",
+                    "config": { "failing_attempts": 2 }
+                }
+            ]
+        }),
+        settings_with_retries(3),
+    );
+
+    let report = dag.execute_with_report(None).await.expect("Should succeed");
+    assert_eq!(report.results.get("flaky"), Some(&Data::Integer(3)));
+    assert_eq!(report.attempts.get("flaky"), Some(&3));
+}
+
+#[tokio::test]
+async fn test_exhausted_retries_report_attempts() {
+    let dag = build_dag(
+        &json!({
+            "alias": "retry_exhausted",
+            "nodes": [
+                {
+                    "id": "flaky",
+                    "component_type": "Flaky",
+                    "config": { "failing_attempts": 5 }
+                }
+            ]
+        }),
+        settings_with_retries(3),
+    );
+
+    match dag.execute(None).await {
+        Err(DAGError::FailedAfterRetries {
+            node_id,
+            attempts,
+            last_error,
+        }) => {
+            assert_eq!(node_id, "flaky");
+            assert_eq!(attempts, 3);
+            assert!(
+                matches!(*last_error, DAGError::TransientError { ref reason, .. } if reason == "Attempt 3 failed"),
+                "Unexpected last error: {last_error:?}"
+            );
+        }
+        other => panic!("Expected FailedAfterRetries, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_non_retryable_errors_fail_immediately() {
+    let dag = build_dag(
+        &json!({
+            "alias": "retry_non_retryable",
+            "nodes": [
+                {
+                    "id": "flaky",
+                    "component_type": "Flaky",
+                    "config": { "failing_attempts": 1, "permanent": true }
+                }
+            ]
+        }),
+        settings_with_retries(3),
+    );
+
+    let result = dag.execute(None).await;
+    assert!(
+        matches!(&result, Err(DAGError::ExecutionError { reason, .. }) if reason == "Attempt 1 failed"),
+        "Execution errors shouldn't be retried by default, got {result:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_node_retry_policy_overrides_dag_settings() {
+    let dag = build_dag(
+        &json!({
+            "alias": "retry_node_override",
+            "nodes": [
+                {
+                    "id": "no_retries",
+                    "component_type": "Flaky",
+                    "config": { "failing_attempts": 0 }
+                },
+                {
+                    "id": "flaky",
+                    "component_type": "Flaky",
+                    "config": { "failing_attempts": 1, "permanent": true },
+                    "retry": {
+                        "max_attempts": 2,
+                        "backoff": { "type": "exponential", "initial_delay_ms": 1, "multiplier": 2.0, "max_delay_ms": 5 },
+                        "jitter": true,
+                        "retry_on": "execution_errors"
+                    }
+                }
+            ]
+        }),
+        DAGSettings::cache_off(),
+    );
+
+    let report = dag.execute_with_report(None).await.expect("Should succeed");
+    assert_eq!(report.results.get("flaky"), Some(&Data::Integer(2)));
+    assert_eq!(report.attempts.get("flaky"), Some(&2));
+    assert_eq!(report.attempts.get("no_retries"), Some(&1));
+}
+
+#[tokio::test]
+async fn test_timed_out_attempt_stops_before_its_retry() {
+    let dag = build_dag(
+        &json!({
+            "alias": "retry_after_timeout",
+            "nodes": [
+                { "id": "hangs", "component_type": "HangsOnce", "config": {} }
+            ]
+        }),
+        DAGSettings {
+            per_node_timeout_ms: Some(100),
+            max_concurrent_nodes: Some(1),
+            ..settings_with_retries(2)
+        },
+    );
+
+    let start = Instant::now();
+    let report = dag.execute_with_report(None).await.expect("Should succeed");
+    assert_eq!(report.results.get("hangs"), Some(&Data::Integer(2)));
+    assert_eq!(report.attempts.get("hangs"), Some(&2));
+    assert!(
+        start.elapsed() < Duration::from_secs(2),
+        "The retry shouldn't wait for the timed-out attempt to finish on its own, took {:?}",
+        start.elapsed()
+    );
+    assert_eq!(
+        HANGING_MAX_RUNNING.load(Ordering::SeqCst),
+        1,
+        "The timed-out attempt ran alongside its retry"
+    );
+}
+
+#[test]
+fn test_exponential_backoff_is_capped() {
+    let policy = RetryPolicy::new(
+        10,
+        Backoff::Exponential {
+            initial_delay_ms: 10,
+            multiplier: 2.0,
+            max_delay_ms: 50,
+        },
+    );
+
+    assert_eq!(policy.delay_for(1), Duration::from_millis(10));
+    assert_eq!(policy.delay_for(2), Duration::from_millis(20));
+    assert_eq!(policy.delay_for(3), Duration::from_millis(40));
+    assert_eq!(policy.delay_for(4), Duration::from_millis(50));
+
+    let jittered = policy.with_jitter().with_retry_on(RetryOn::ExecutionErrors);
+    for attempt in 1..=5 {
+        let delay = jittered.delay_for(attempt);
+        assert!(delay <= Duration::from_millis(50), "Delay {delay:?} exceeds cap");
+        assert!(delay >= Duration::from_millis(5), "Delay {delay:?} below half");
+    }
+}
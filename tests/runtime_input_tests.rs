@@ -0,0 +1,120 @@
+use baselard::cache::Cache;
+use baselard::component::{Data, DataType, Registry};
+use baselard::components::adder::Adder;
+use baselard::dag::{DAGError, DAGSettings, DAG};
+use baselard::dagir::DAGIR;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn build_dag(settings: DAGSettings, cache: Option<Arc<Cache>>) -> DAG {
+    let mut registry = Registry::new();
+    registry.register::<Adder>("Adder");
+
+    let ir = DAGIR::from_json(&json!({
+        "alias": "runtime_inputs",
+        "nodes": [
+            { "id": "left", "component_type": "Adder", "config": { "value": 1 }, "inputs": 1 },
+            { "id": "right", "component_type": "Adder", "config": { "value": 10 }, "inputs": 10 },
+            { "id": "sum", "component_type": "Adder", "config": { "value": 0 }, "depends_on": ["left", "right"] }
+        ]
+    }))
+    .expect("Valid IR");
+    DAG::from_ir(&ir, &registry, settings, cache).expect("Valid DAG")
+}
+
+fn inputs(entries: &[(&str, i32)]) -> HashMap<String, Data> {
+    entries
+        .iter()
+        .map(|(node_id, value)| ((*node_id).to_string(), Data::Integer(*value)))
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_one_dag_serves_concurrent_requests() {
+    let dag = Arc::new(build_dag(DAGSettings::cache_off(), None));
+
+    let requests = (0..16).map(|i| {
+        let dag = Arc::clone(&dag);
+        tokio::spawn(async move {
+            let results = dag
+                .execute_with_inputs(Some(format!("request_{i}")), inputs(&[("left", i)]))
+                .await
+                .expect("Execution success");
+            (i, results)
+        })
+    });
+
+    for request in futures::future::join_all(requests).await {
+        let (i, results) = request.unwrap();
+        assert_eq!(results.get("left"), Some(&Data::Integer(i + 1)));
+        // Configured inputs apply to nodes without a runtime one
+        assert_eq!(results.get("right"), Some(&Data::Integer(20)));
+        assert_eq!(results.get("sum"), Some(&Data::Integer(i + 21)));
+    }
+
+    // The DAG's own inputs are untouched
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.get("sum"), Some(&Data::Integer(22)));
+}
+
+#[tokio::test]
+async fn test_runtime_inputs_are_validated() {
+    let dag = build_dag(DAGSettings::cache_off(), None);
+
+    let result = dag.execute_with_inputs(None, inputs(&[("missing", 1)])).await;
+    assert!(
+        matches!(&result, Err(DAGError::NodeNotFound { node }) if node == "missing"),
+        "Unexpected result: {result:?}"
+    );
+
+    let result = dag.execute_with_inputs(None, inputs(&[("sum", 1)])).await;
+    assert!(
+        matches!(&result, Err(DAGError::InvalidConfiguration(reason)) if reason.contains("Node sum gets its input from its dependencies")),
+        "Unexpected result: {result:?}"
+    );
+
+    let result = dag
+        .execute_with_inputs(None, HashMap::from([("left".to_string(), Data::Text("one".to_string()))]))
+        .await;
+    assert!(
+        matches!(
+            &result,
+            Err(DAGError::TypeMismatch { node_id, actual: DataType::Text, .. }) if node_id == "left"
+        ),
+        "Unexpected result: {result:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_cache_is_keyed_on_runtime_inputs() {
+    let cache = Arc::new(Cache::new(None::<String>, 100));
+    let settings = DAGSettings {
+        enable_memory_cache: true,
+        ..DAGSettings::cache_off()
+    };
+    let dag = build_dag(settings, Some(cache));
+
+    let first = dag
+        .execute_with_inputs_report(None, inputs(&[("left", 5), ("right", 50)]))
+        .await
+        .expect("Execution success");
+    assert_eq!(first.results.get("sum"), Some(&Data::Integer(66)));
+    assert!(!first.attempts.is_empty(), "First request should execute");
+    // Results are stored in the background
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+    let repeated = dag
+        .execute_with_inputs_report(None, inputs(&[("right", 50), ("left", 5)]))
+        .await
+        .expect("Execution success");
+    assert_eq!(repeated.results, first.results);
+    assert!(repeated.attempts.is_empty(), "Same inputs should hit the cache");
+
+    let other = dag
+        .execute_with_inputs_report(None, inputs(&[("left", 6), ("right", 50)]))
+        .await
+        .expect("Execution success");
+    assert_eq!(other.results.get("sum"), Some(&Data::Integer(67)));
+    assert!(!other.attempts.is_empty(), "Other inputs should execute");
+}
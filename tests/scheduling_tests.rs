@@ -0,0 +1,159 @@
+use baselard::component::{Component, Data, DataType, Error, Registry};
+use baselard::dag::{DAGError, DAGSettings, NodeExecutionContext, DAG};
+use baselard::dagir::{DAGConfig, NodeConfig, DAGIR};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// What the `Probe` nodes of one test observed
+#[derive(Default)]
+struct Observations {
+    started: Vec<String>,
+    running: usize,
+    max_running: usize,
+}
+
+fn observations() -> &'static Mutex<HashMap<String, Observations>> {
+    static OBSERVATIONS: OnceLock<Mutex<HashMap<String, Observations>>> = OnceLock::new();
+    OBSERVATIONS.get_or_init(Mutex::default)
+}
+
+/// Sleeps, recording when it starts and how many probes of its test run
+/// alongside it
+struct Probe {
+    test: String,
+    sleep_ms: u64,
+}
+
+impl Component for Probe {
+    fn configure(config: Value) -> Result<Self, Error> {
+        Ok(Self {
+            test: config["test"].as_str().unwrap_or_default().to_string(),
+            sleep_ms: config["sleep_ms"].as_u64().unwrap_or(20),
+        })
+    }
+
+    fn execute(&self, context: NodeExecutionContext, _input: Data) -> Result<Data, DAGError> {
+        {
+            let mut observations = observations().lock().unwrap();
+            let observed = observations.entry(self.test.clone()).or_default();
+            observed.started.push(context.node_id);
+            observed.running += 1;
+            observed.max_running = observed.max_running.max(observed.running);
+        }
+        std::thread::sleep(Duration::from_millis(self.sleep_ms));
+        observations().lock().unwrap().get_mut(&self.test).unwrap().running -= 1;
+        Ok(Data::Null)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Union(vec![DataType::Null, DataType::List(Box::new(DataType::Null))])
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Null
+    }
+}
+
+fn probe(test: &str, id: &str) -> NodeConfig {
+    NodeConfig::new(id, "Probe").with_config(json!({ "test": test }))
+}
+
+fn limited(max_concurrent_nodes: usize) -> DAGSettings {
+    DAGSettings {
+        per_node_timeout_ms: None,
+        max_concurrent_nodes: Some(max_concurrent_nodes),
+        ..DAGSettings::cache_off()
+    }
+}
+
+fn build_dag(config: DAGConfig, settings: DAGSettings) -> Result<DAG, String> {
+    let mut registry = Registry::new();
+    registry.register::<Probe>("Probe");
+    DAGIR::from_config(config).and_then(|ir| DAG::from_ir(&ir, &registry, settings, None))
+}
+
+/// Runs the DAG, returning the order its nodes started in and how many ran
+/// at once
+async fn run(test: &str, config: DAGConfig, settings: DAGSettings) -> (Vec<String>, usize) {
+    let dag = build_dag(config, settings).expect("Valid DAG");
+    dag.execute(None).await.expect("Execution success");
+
+    let observations = observations().lock().unwrap();
+    let observed = &observations[test];
+    (observed.started.clone(), observed.max_running)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_max_concurrent_nodes_per_dag() {
+    let test = "max_concurrent_nodes";
+    let config = DAGConfig::new(test)
+        .with_nodes((0..8).map(|i| probe(test, &format!("node_{i}"))));
+
+    let (started, max_running) = run(test, config, limited(3)).await;
+    assert_eq!(started.len(), 8);
+    assert_eq!(max_running, 3);
+}
+
+// Nodes are spawned in topological order, which a current-thread runtime
+// polls them in, so `a_blocker` always gets the only permit first and the
+// others all wait for it
+
+#[tokio::test]
+async fn test_waiting_nodes_execute_by_priority() {
+    let test = "priority";
+    let config = DAGConfig::new(test).with_nodes(vec![
+        probe(test, "a_blocker"),
+        probe(test, "b_low").with_priority(-1),
+        probe(test, "c_default"),
+        probe(test, "d_high").with_priority(10),
+        probe(test, "e_medium").with_priority(5),
+    ]);
+
+    let (started, max_running) = run(test, config, limited(1)).await;
+    assert_eq!(
+        started,
+        vec!["a_blocker", "d_high", "e_medium", "c_default", "b_low"]
+    );
+    assert_eq!(max_running, 1);
+}
+
+fn chains(test: &str) -> DAGConfig {
+    DAGConfig::new(test).with_nodes(vec![
+        probe(test, "a_blocker"),
+        probe(test, "x"),
+        probe(test, "y"),
+        probe(test, "y_2").with_dependencies(vec!["y"]),
+        probe(test, "z"),
+        probe(test, "z_2").with_dependencies(vec!["z"]),
+        probe(test, "z_3").with_dependencies(vec!["z_2"]),
+    ])
+}
+
+#[tokio::test]
+async fn test_waiting_nodes_execute_in_topological_order() {
+    let test = "topological";
+    let (started, _) = run(test, chains(test), limited(1)).await;
+    assert_eq!(started[..4], ["a_blocker", "x", "y", "z"]);
+}
+
+#[tokio::test]
+async fn test_critical_path_first() {
+    let test = "critical_path";
+    let settings = DAGSettings {
+        critical_path_first: true,
+        ..limited(1)
+    };
+
+    // Once z is done, z_2 is ahead of x as well
+    let (started, _) = run(test, chains(test), settings).await;
+    assert_eq!(started[..5], ["a_blocker", "z", "y", "z_2", "x"]);
+}
+
+#[test]
+fn test_max_concurrent_nodes_must_be_positive() {
+    let err = build_dag(DAGConfig::new("zero").with_node(probe("zero", "node")), limited(0))
+        .unwrap_err();
+    assert_eq!(err, "max_concurrent_nodes must be a positive integer");
+}
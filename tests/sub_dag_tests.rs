@@ -0,0 +1,262 @@
+use ascii_tree::write_tree;
+use baselard::cache::Cache;
+use baselard::component::{Component, Data, DataType, Error, Registry};
+use baselard::components::adder::Adder;
+use baselard::dag::{DAGError, DAGSettings, NodeExecutionContext, DAG};
+use baselard::dag_visualizer::TreeView;
+use baselard::dagir::{DAGConfig, DAGIR};
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How many `Napper` naps ran to the end without being cancelled
+static FINISHED_NAPS: AtomicUsize = AtomicUsize::new(0);
+
+/// Naps for 300ms, unless cancelled first
+struct Napper;
+
+impl Component for Napper {
+    fn configure(_: serde_json::Value) -> Result<Self, Error> {
+        Ok(Self)
+    }
+
+    fn execute(&self, context: NodeExecutionContext, input: Data) -> Result<Data, DAGError> {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_millis(300) {
+            if context.is_cancelled() {
+                return Err(DAGError::Cancelled {
+                    node_id: context.node_id,
+                });
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        FINISHED_NAPS.fetch_add(1, Ordering::SeqCst);
+        Ok(input)
+    }
+
+    fn input_type(&self) -> DataType {
+        DataType::Any
+    }
+
+    fn output_type(&self) -> DataType {
+        DataType::Any
+    }
+}
+
+fn setup_test_registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<Adder>("Adder");
+    registry.register::<Napper>("Napper");
+
+    let add_eleven: DAGConfig = serde_json::from_value(json!({
+        "alias": "add_eleven",
+        "nodes": [
+            { "id": "plus_one", "component_type": "Adder", "config": { "value": 1 } },
+            { "id": "plus_ten", "component_type": "Adder", "config": { "value": 10 }, "depends_on": ["plus_one"] }
+        ]
+    }))
+    .unwrap();
+    registry.register_dag(add_eleven);
+    registry
+}
+
+fn build_dag(json_config: &serde_json::Value) -> Result<DAG, String> {
+    let registry = setup_test_registry();
+    DAGIR::from_json(json_config)
+        .and_then(|ir| DAG::from_ir(&ir, &registry, DAGSettings::cache_off(), None))
+}
+
+fn composed_config() -> serde_json::Value {
+    json!({
+        "alias": "composed",
+        "nodes": [
+            { "id": "plus_one", "component_type": "Adder", "config": { "value": 1 }, "inputs": 1 },
+            { "id": "shared", "component_type": "SubDag", "config": { "dag": "add_eleven" }, "depends_on": ["plus_one"] },
+            {
+                "id": "embedded",
+                "component_type": "SubDag",
+                "config": {
+                    "dag": {
+                        "alias": "two_branches",
+                        "nodes": [
+                            { "id": "plus_one", "component_type": "Adder", "config": { "value": 1 }, "inputs": 100 },
+                            { "id": "plus_two", "component_type": "Adder", "config": { "value": 2 }, "depends_on": ["plus_one"] },
+                            { "id": "plus_three", "component_type": "Adder", "config": { "value": 3 }, "depends_on": ["plus_one"] }
+                        ]
+                    },
+                    "output": "plus_three"
+                }
+            },
+            { "id": "total", "component_type": "Adder", "config": { "value": 0 }, "depends_on": ["shared", "embedded"] }
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_sub_dags_execute_as_units() {
+    let dag = build_dag(&composed_config()).expect("Valid DAG");
+
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.get("plus_one"), Some(&Data::Integer(2)));
+    // The dependency's output goes to the sub-DAG's root node
+    assert_eq!(results.get("shared"), Some(&Data::Integer(13)));
+    // Without dependencies, the sub-DAG runs on its own inputs
+    assert_eq!(results.get("embedded"), Some(&Data::Integer(104)));
+    assert_eq!(results.get("total"), Some(&Data::Integer(117)));
+
+    // Sub-DAG nodes don't leak into the enclosing DAG's results
+    assert!(!results.contains_key("plus_ten"));
+    assert!(!results.contains_key("plus_three"));
+}
+
+/// Executes `json_config` with the memory cache on, returning `node`'s output
+async fn execute_cached(
+    registry: &Registry,
+    json_config: &serde_json::Value,
+    cache: &Arc<Cache>,
+    node: &str,
+) -> Option<Data> {
+    let settings = DAGSettings {
+        enable_memory_cache: true,
+        ..DAGSettings::cache_off()
+    };
+    let dag = DAGIR::from_json(json_config)
+        .and_then(|ir| DAG::from_ir(&ir, registry, settings, Some(Arc::clone(cache))))
+        .expect("Valid DAG");
+    let mut results = dag.execute(None).await.expect("Execution success");
+    // Results are cached in the background
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    results.shift_remove(node)
+}
+
+/// `add_eleven` registered anew as adding 100 instead
+fn add_hundred_instead(registry: &mut Registry) {
+    let add_hundred: DAGConfig = serde_json::from_value(json!({
+        "alias": "add_eleven",
+        "nodes": [{ "id": "plus_hundred", "component_type": "Adder", "config": { "value": 100 } }]
+    }))
+    .unwrap();
+    registry.register_dag(add_hundred);
+}
+
+#[tokio::test]
+async fn test_re_registered_sub_dag_misses_the_cache() {
+    let mut registry = setup_test_registry();
+    let cache = Arc::new(Cache::new(None::<String>, 100));
+    let config = json!({
+        "alias": "re_registered",
+        "nodes": [
+            { "id": "shared", "component_type": "SubDag", "config": { "dag": "add_eleven" }, "inputs": 1 }
+        ]
+    });
+
+    let output = execute_cached(&registry, &config, &cache, "shared").await;
+    assert_eq!(output, Some(Data::Integer(12)));
+
+    add_hundred_instead(&mut registry);
+    let output = execute_cached(&registry, &config, &cache, "shared").await;
+    assert_eq!(output, Some(Data::Integer(101)));
+}
+
+#[tokio::test]
+async fn test_re_registered_sub_dag_misses_the_node_cache() {
+    let mut registry = setup_test_registry();
+    let cache = Arc::new(Cache::new(None::<String>, 100));
+    let config = |leaf_value: i32| {
+        json!({
+            "alias": "re_registered_memo",
+            "nodes": [
+                { "id": "shared", "component_type": "SubDag", "config": { "dag": "add_eleven" }, "inputs": 1, "cacheable": true },
+                { "id": "leaf", "component_type": "Adder", "config": { "value": leaf_value }, "depends_on": ["shared"] }
+            ]
+        })
+    };
+
+    let output = execute_cached(&registry, &config(0), &cache, "leaf").await;
+    assert_eq!(output, Some(Data::Integer(12)));
+
+    // Another DAG, so that only the node's memoized output could be reused
+    add_hundred_instead(&mut registry);
+    let output = execute_cached(&registry, &config(1), &cache, "leaf").await;
+    assert_eq!(output, Some(Data::Integer(102)));
+}
+
+#[tokio::test]
+async fn test_deadline_cancels_sub_dag() {
+    let registry = setup_test_registry();
+    let dag = DAGIR::from_json(&json!({
+        "alias": "deadline_test",
+        "nodes": [
+            {
+                "id": "naps",
+                "component_type": "SubDag",
+                "config": {
+                    "dag": {
+                        "alias": "two_naps",
+                        "nodes": [
+                            { "id": "first", "component_type": "Napper", "config": {}, "inputs": 1 },
+                            { "id": "second", "component_type": "Napper", "config": {}, "depends_on": ["first"] }
+                        ]
+                    }
+                }
+            }
+        ]
+    }))
+    .and_then(|ir| {
+        let settings = DAGSettings {
+            per_node_timeout_ms: Some(1000),
+            dag_deadline_ms: Some(100),
+            ..DAGSettings::cache_off()
+        };
+        DAG::from_ir(&ir, &registry, settings, None)
+    })
+    .expect("Valid DAG");
+
+    let result = dag.execute(None).await;
+    assert!(
+        matches!(&result, Err(DAGError::DeadlineExceeded { .. })),
+        "Unexpected result: {result:?}"
+    );
+
+    // Long enough for both naps to have run, had the sub-DAG kept going
+    tokio::time::sleep(Duration::from_millis(700)).await;
+    assert_eq!(FINISHED_NAPS.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_sub_dag_output_must_be_declared_when_ambiguous() {
+    let mut config = composed_config();
+    config["nodes"][2]["config"]
+        .as_object_mut()
+        .unwrap()
+        .remove("output");
+
+    let err = build_dag(&config).unwrap_err();
+    assert!(
+        err.contains("SubDag node embedded: the sub-DAG has several candidate output nodes"),
+        "Unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_sub_dags_are_subtrees_in_build_tree() {
+    let ir = DAGIR::from_json(&composed_config()).expect("Valid IR");
+
+    let mut tree = String::new();
+    write_tree(&mut tree, &ir.build_tree(TreeView::Execution)).unwrap();
+    assert!(tree.contains("shared [SubDag add_eleven]"), "Unexpected tree:\n{tree}");
+    assert!(tree.contains("embedded [SubDag two_branches]"), "Unexpected tree:\n{tree}");
+    assert!(tree.contains("sub-DAG two_branches"), "Unexpected tree:\n{tree}");
+    assert!(!tree.contains("sub-DAG add_eleven"), "Unexpected tree:\n{tree}");
+
+    let mut tree = String::new();
+    let registry = setup_test_registry();
+    write_tree(
+        &mut tree,
+        &ir.build_tree_with_registry(TreeView::Execution, &registry),
+    )
+    .unwrap();
+    assert!(tree.contains("sub-DAG add_eleven"), "Unexpected tree:\n{tree}");
+    assert!(tree.contains("plus_ten"), "Unexpected tree:\n{tree}");
+}
@@ -0,0 +1,131 @@
+use baselard::cache::Cache;
+use baselard::component::{Data, Registry};
+use baselard::components::adder::Adder;
+use baselard::components::crash_test_dummy::CrashTestDummy;
+use baselard::dag::{DAGError, DAGSettings, DAG};
+use baselard::dagir::{DAGConfig, NodeConfig, DAGIR};
+use serde_json::json;
+use std::sync::Arc;
+
+fn setup_test_registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<Adder>("Adder");
+    registry.register::<CrashTestDummy>("CrashTestDummy");
+    registry
+}
+
+fn adder(id: &str, value: i32) -> NodeConfig {
+    NodeConfig::new(id, "Adder").with_config(json!({ "value": value }))
+}
+
+fn failing(id: &str) -> NodeConfig {
+    NodeConfig::new(id, "CrashTestDummy").with_config(json!({ "fail": true }))
+}
+
+/// Two chains sharing `root`, and nodes that fail if they execute
+fn pipeline() -> DAGConfig {
+    DAGConfig::new("targets").with_nodes(vec![
+        adder("root", 1).with_inputs(json!(1)),
+        adder("left", 10).with_dependencies(vec!["root"]),
+        adder("right", 100).with_dependencies(vec!["root"]),
+        adder("both", 0).with_dependencies(vec!["left", "right"]),
+        failing("after_left").with_dependencies(vec!["left"]),
+        failing("unrelated").with_inputs(json!("a")),
+    ])
+}
+
+fn build_dag(config: DAGConfig, settings: DAGSettings, cache: Option<Arc<Cache>>) -> DAG {
+    let ir = DAGIR::from_config(config).expect("Valid IR");
+    DAG::from_ir(&ir, &setup_test_registry(), settings, cache).expect("Valid DAG")
+}
+
+#[tokio::test]
+async fn test_execute_targets_runs_their_ancestors_only() {
+    let dag = build_dag(pipeline(), DAGSettings::cache_off(), None);
+
+    let results = dag
+        .execute_targets(None, &["both".to_string(), "left".to_string()])
+        .await
+        .expect("Failing nodes aren't needed");
+    assert_eq!(
+        results.into_iter().collect::<Vec<_>>(),
+        vec![
+            ("both".to_string(), Data::Integer(114)),
+            ("left".to_string(), Data::Integer(12)),
+        ]
+    );
+
+    let result = dag.execute_targets(None, &["after_left".to_string()]).await;
+    assert!(
+        matches!(&result, Err(DAGError::ExecutionError { node_id, .. }) if node_id == "after_left"),
+        "Unexpected result: {result:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_declared_outputs_prune_dead_nodes() {
+    let dag = build_dag(
+        pipeline().with_output("right"),
+        DAGSettings::cache_off(),
+        None,
+    );
+
+    let report = dag.execute_with_report(None).await.expect("Execution success");
+    assert_eq!(report.results.get("right"), Some(&Data::Integer(102)));
+    assert_eq!(
+        report.statuses.keys().collect::<Vec<_>>(),
+        vec!["root", "right"]
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_targets() {
+    let dag = build_dag(pipeline(), DAGSettings::cache_off(), None);
+
+    let result = dag.execute_targets(None, &["missing".to_string()]).await;
+    assert!(
+        matches!(&result, Err(DAGError::NodeNotFound { node }) if node == "missing"),
+        "Unexpected result: {result:?}"
+    );
+
+    let result = dag.execute_targets(None, &[]).await;
+    assert!(
+        matches!(&result, Err(DAGError::InvalidConfiguration(_))),
+        "Unexpected result: {result:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_targets_are_cached_apart() {
+    let cache = Arc::new(Cache::new(None::<String>, 100));
+    let settings = DAGSettings {
+        enable_memory_cache: true,
+        ..DAGSettings::cache_off()
+    };
+    let config = DAGConfig::new("cached_targets").with_nodes(vec![
+        adder("root", 1).with_inputs(json!(1)),
+        adder("leaf", 10).with_dependencies(vec!["root"]),
+    ]);
+    let dag = build_dag(config, settings, Some(cache));
+
+    let targeted = dag
+        .execute_targets(None, &["root".to_string()])
+        .await
+        .expect("Execution success");
+    assert_eq!(targeted.len(), 1);
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+    let report = dag.execute_with_report(None).await.expect("Execution success");
+    assert!(!report.attempts.is_empty(), "Should not hit the targets' cache");
+    assert_eq!(report.results.len(), 2);
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+    let targeted = dag
+        .execute_targets(None, &["root".to_string()])
+        .await
+        .expect("Execution success");
+    assert_eq!(
+        targeted.into_iter().collect::<Vec<_>>(),
+        vec![("root".to_string(), Data::Integer(2))]
+    );
+}
@@ -0,0 +1,130 @@
+use baselard::component::Registry;
+use baselard::components::adder::Adder;
+use baselard::components::crash_test_dummy::CrashTestDummy;
+use baselard::dag::{DAGError, DAGSettings, DAG};
+use baselard::dagir::DAGIR;
+use serde_json::json;
+use std::time::{Duration, Instant};
+
+fn setup_test_registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<Adder>("Adder");
+    registry.register::<CrashTestDummy>("CrashTestDummy");
+    registry
+}
+
+fn build_dag(json_config: &serde_json::Value, settings: DAGSettings) -> DAG {
+    let registry = setup_test_registry();
+    DAGIR::from_json(json_config)
+        .and_then(|ir| DAG::from_ir(&ir, &registry, settings, None))
+        .expect("Valid DAG")
+}
+
+#[tokio::test]
+async fn test_node_timeout_overrides_dag_settings() {
+    let dag = build_dag(
+        &json!({
+            "alias": "node_timeout_override",
+            "nodes": [
+                {
+                    "id": "fast",
+                    "component_type": "Adder",
+                    "config": { "value": 1 },
+                    "inputs": 1
+                },
+                {
+                    "id": "slow_model",
+                    "component_type": "CrashTestDummy",
+                    "config": { "sleep_duration_ms": 60 },
+                    "depends_on": ["fast"],
+                    "timeout_ms": 500
+                }
+            ]
+        }),
+        DAGSettings {
+            per_node_timeout_ms: Some(30),
+            ..DAGSettings::cache_off()
+        },
+    );
+
+    let results = dag.execute(None).await.expect("Slow node should get its own timeout");
+    assert_eq!(results.len(), 2);
+}
+
+#[tokio::test]
+async fn test_node_timeout_error() {
+    let dag = build_dag(
+        &json!({
+            "alias": "node_timeout_error",
+            "nodes": [
+                {
+                    "id": "too_slow",
+                    "component_type": "CrashTestDummy",
+                    "config": { "sleep_duration_ms": 200 },
+                    "timeout_ms": 20
+                }
+            ]
+        }),
+        DAGSettings::cache_off(),
+    );
+
+    let result = dag.execute(None).await;
+    assert!(
+        matches!(
+            &result,
+            Err(DAGError::NodeTimeout { node_id, timeout_ms: 20 }) if node_id == "too_slow"
+        ),
+        "Expected a node timeout, got {result:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_dag_deadline_cancels_remaining_nodes() {
+    let dag = build_dag(
+        &json!({
+            "alias": "dag_deadline",
+            "nodes": [
+                {
+                    "id": "first",
+                    "component_type": "CrashTestDummy",
+                    "config": { "sleep_duration_ms": 30 }
+                },
+                {
+                    "id": "second",
+                    "component_type": "CrashTestDummy",
+                    "config": { "sleep_duration_ms": 30 },
+                    "depends_on": ["first"]
+                },
+                {
+                    "id": "third",
+                    "component_type": "CrashTestDummy",
+                    "config": { "sleep_duration_ms": 30 },
+                    "depends_on": ["second"]
+                }
+            ]
+        }),
+        DAGSettings {
+            dag_deadline_ms: Some(45),
+            ..DAGSettings::cache_off()
+        },
+    );
+
+    let start = Instant::now();
+    let result = dag.execute(None).await;
+    assert!(
+        start.elapsed() < Duration::from_millis(80),
+        "Execution should stop at the deadline, took {:?}",
+        start.elapsed()
+    );
+
+    match result {
+        Err(DAGError::DeadlineExceeded {
+            deadline_ms,
+            pending_nodes,
+        }) => {
+            assert_eq!(deadline_ms, 45);
+            assert_eq!(pending_nodes, vec!["second".to_string(), "third".to_string()]);
+        }
+        other => panic!("Expected the DAG deadline to be exceeded, got {other:?}"),
+    }
+}
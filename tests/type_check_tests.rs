@@ -0,0 +1,241 @@
+use baselard::component::{Data, DataType, Registry};
+use baselard::components::adder::Adder;
+use baselard::components::json_to_data_processor::JsonToDataProcessor;
+use baselard::components::string_length_counter::StringLengthCounter;
+use baselard::components::wildcard_processor::WildcardProcessor;
+use baselard::dag::{DAGSettings, DAG};
+use baselard::dagir::DAGIR;
+use serde_json::json;
+
+fn setup_test_registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register::<Adder>("Adder");
+    registry.register::<JsonToDataProcessor>("JsonToDataProcessor");
+    registry.register::<StringLengthCounter>("StringLengthCounter");
+    registry.register::<WildcardProcessor>("WildcardProcessor");
+    registry
+}
+
+fn build_dag(json_config: &serde_json::Value) -> Result<DAG, String> {
+    build_dag_with_settings(json_config, DAGSettings::cache_off())
+}
+
+fn build_dag_with_settings(
+    json_config: &serde_json::Value,
+    settings: DAGSettings,
+) -> Result<DAG, String> {
+    let registry = setup_test_registry();
+    DAGIR::from_json(json_config).and_then(|ir| DAG::from_ir(&ir, &registry, settings, None))
+}
+
+#[test]
+fn test_every_incompatible_edge_is_reported() {
+    let err = build_dag(&json!({
+        "alias": "miswired_test",
+        "nodes": [
+            { "id": "number", "component_type": "Adder", "config": { "value": 1 }, "inputs": 1 },
+            { "id": "length", "component_type": "StringLengthCounter", "config": {}, "depends_on": ["number"] },
+            {
+                "id": "wildcard",
+                "component_type": "WildcardProcessor",
+                "config": { "expected_input_keys": [], "expected_output_keys": [] },
+                "depends_on": ["length"]
+            }
+        ]
+    }))
+    .unwrap_err();
+
+    assert!(err.starts_with("DAG has 2 type error(s)"), "Unexpected error: {err}");
+    assert!(
+        err.contains("number -> length: output Integer is incompatible with input Text"),
+        "Unexpected error: {err}"
+    );
+    assert!(
+        err.contains("length -> wildcard: output Integer is incompatible with input Json"),
+        "Unexpected error: {err}"
+    );
+}
+
+#[tokio::test]
+async fn test_fan_in_list_type_accepted() {
+    let dag = build_dag(&json!({
+        "alias": "fan_in_test",
+        "nodes": [
+            { "id": "number", "component_type": "Adder", "config": { "value": 1 }, "inputs": 1 },
+            { "id": "length", "component_type": "StringLengthCounter", "config": {}, "inputs": "abc" },
+            { "id": "sum", "component_type": "Adder", "config": { "value": 0 }, "depends_on": ["number", "length"] }
+        ]
+    }))
+    .expect("Integer outputs fan in to a List of Integers");
+
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.get("sum"), Some(&Data::Integer(5)));
+}
+
+#[test]
+fn test_fan_in_rejects_incompatible_element() {
+    let err = build_dag(&json!({
+        "alias": "fan_in_mismatch_test",
+        "nodes": [
+            { "id": "number", "component_type": "Adder", "config": { "value": 1 }, "inputs": 1 },
+            {
+                "id": "wildcard",
+                "component_type": "WildcardProcessor",
+                "config": { "expected_input_keys": [], "expected_output_keys": [] },
+                "inputs": { "key": "value" }
+            },
+            { "id": "sum", "component_type": "Adder", "config": { "value": 0 }, "depends_on": ["number", "wildcard"] }
+        ]
+    }))
+    .unwrap_err();
+
+    assert!(err.starts_with("DAG has 1 type error(s)"), "Unexpected error: {err}");
+    assert!(
+        err.contains("wildcard -> sum: output Json is incompatible with list input"),
+        "Unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_fan_in_into_non_list_input_rejected() {
+    let err = build_dag(&json!({
+        "alias": "fan_in_non_list_test",
+        "nodes": [
+            { "id": "first", "component_type": "StringLengthCounter", "config": {}, "inputs": "a" },
+            { "id": "second", "component_type": "StringLengthCounter", "config": {}, "inputs": "b" },
+            { "id": "length", "component_type": "StringLengthCounter", "config": {}, "depends_on": ["first", "second"] }
+        ]
+    }))
+    .unwrap_err();
+
+    assert!(
+        err.contains("length: has 2 dependencies, so its input is a List, but it only accepts Text"),
+        "Unexpected error: {err}"
+    );
+}
+
+#[test]
+fn test_union_source_may_flow_into_any_member() {
+    let source = DataType::Union(vec![DataType::Integer, DataType::Text]);
+    assert!(source.can_flow_into(&DataType::Text));
+    assert!(!source.can_flow_into(&DataType::Json));
+    assert!(DataType::List(Box::new(source.clone()))
+        .can_flow_into(&DataType::List(Box::new(DataType::Integer))));
+    assert!(!source.is_compatible_with(&DataType::Text));
+}
+
+#[tokio::test]
+async fn test_union_outputs_only_flow_into_members_when_optimistic() {
+    let config = json!({
+        "alias": "union_output_test",
+        "nodes": [
+            {
+                "id": "to_data",
+                "component_type": "JsonToDataProcessor",
+                "config": {},
+                "inputs": { "type": "integer", "value": 2 }
+            },
+            { "id": "number", "component_type": "Adder", "config": { "value": 1 }, "depends_on": ["to_data"] }
+        ]
+    });
+
+    let err = build_dag(&config).unwrap_err();
+    assert!(
+        err.contains("to_data -> number: output Union("),
+        "Every type the output may have must be accepted: {err}"
+    );
+
+    let dag = build_dag_with_settings(
+        &config,
+        DAGSettings {
+            optimistic_type_checks: true,
+            ..DAGSettings::cache_off()
+        },
+    )
+    .expect("Valid DAG");
+    let results = dag.execute(None).await.expect("Execution success");
+    assert_eq!(results.get("number"), Some(&Data::Integer(3)));
+}
+
+#[test]
+fn test_list_types_are_inferred_from_every_element() {
+    let list = |items: Vec<Data>| Data::List(items).get_type();
+    let list_of = |element_type: DataType| DataType::List(Box::new(element_type));
+
+    assert_eq!(list(vec![]), list_of(DataType::Never));
+    assert_eq!(
+        list(vec![Data::Integer(1), Data::Integer(2)]),
+        list_of(DataType::Integer)
+    );
+    assert_eq!(
+        list(vec![Data::Integer(1), Data::Text("a".to_string()), Data::Integer(2)]),
+        list_of(DataType::Union(vec![DataType::Integer, DataType::Text]))
+    );
+    assert_eq!(
+        list(vec![Data::Null, Data::Text("a".to_string())]),
+        list_of(DataType::Optional(Box::new(DataType::Text)))
+    );
+    assert_eq!(
+        list(vec![Data::List(vec![]), Data::List(vec![Data::Integer(1)])]),
+        list_of(DataType::Union(vec![
+            list_of(DataType::Never),
+            list_of(DataType::Integer)
+        ]))
+    );
+}
+
+#[test]
+fn test_empty_lists_fit_any_list_type() {
+    let empty = Data::List(vec![]);
+    assert!(empty
+        .get_type()
+        .is_compatible_with(&DataType::List(Box::new(DataType::Text))));
+    assert!(empty.validate_type(&DataType::List(Box::new(DataType::Text))));
+
+    let heterogeneous = Data::List(vec![Data::Integer(1), Data::Text("a".to_string())]);
+    let mixed = DataType::List(Box::new(DataType::Union(vec![
+        DataType::Text,
+        DataType::Integer,
+        DataType::Json,
+    ])));
+    assert!(heterogeneous.get_type().is_compatible_with(&mixed));
+    assert!(!heterogeneous
+        .get_type()
+        .is_compatible_with(&DataType::List(Box::new(DataType::Integer))));
+}
+
+#[test]
+fn test_optional_and_any_types() {
+    let optional = DataType::Optional(Box::new(DataType::Integer));
+    assert!(DataType::Null.is_compatible_with(&optional));
+    assert!(DataType::Integer.is_compatible_with(&optional));
+    assert!(!DataType::Text.is_compatible_with(&optional));
+    assert!(!optional.is_compatible_with(&DataType::Integer));
+    assert!(optional.can_flow_into(&DataType::Integer));
+    assert!(optional.is_compatible_with(&DataType::Union(vec![DataType::Null, DataType::Integer])));
+    assert!(Data::Null.validate_type(&optional));
+    assert!(Data::Integer(1).validate_type(&optional));
+    assert!(!Data::Text("a".to_string()).validate_type(&optional));
+
+    assert!(optional.is_compatible_with(&DataType::Any));
+    assert!(!DataType::Any.is_compatible_with(&DataType::Text));
+    assert!(DataType::Any.can_flow_into(&DataType::Text));
+    assert!(Data::Json(json!({})).validate_type(&DataType::Any));
+}
+
+#[test]
+fn test_heterogeneous_inputs_are_reported_as_unions() {
+    let Err(err) = build_dag(&json!({
+        "alias": "heterogeneous_inputs_test",
+        "nodes": [
+            { "id": "sum", "component_type": "Adder", "config": { "value": 1 }, "inputs": [1, "two"] }
+        ]
+    })) else {
+        panic!("Text elements should be rejected");
+    };
+
+    assert!(
+        err.contains("got List(Union([Integer, Text]))"),
+        "Unexpected error: {err}"
+    );
+}